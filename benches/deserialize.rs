@@ -0,0 +1,73 @@
+//! Compares the single-pass deserialization of `GraphQLConfiguration` with
+//! the `#[serde(flatten)]` derive it replaced, on a large configuration, and
+//! times the loader, which detects the version of the document before
+//! deserializing it.
+//!
+//! Run with `cargo bench`.
+
+extern crate graphql_config;
+#[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate serde_json;
+
+use std::time::{Duration, Instant};
+
+use graphql_config::map::Map;
+use graphql_config::projects::ProjectName;
+use graphql_config::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Flattened {
+    projects: Option<Map<ProjectName, GraphQLProjectConfiguration>>,
+    #[serde(flatten)]
+    root: GraphQLProjectConfiguration,
+}
+
+fn document() -> String {
+    let projects: serde_json::Map<String, serde_json::Value> = (0..500)
+        .map(|i| {
+            let project = json!({
+                "name": format!("package-{}", i),
+                "schemaPath": format!("packages/{}/schema.graphql", i),
+                "includes": [format!("packages/{}/src/**/*.graphql", i)],
+                "excludes": ["**/node_modules/**"],
+                "extensions": { "endpoints": { "default": "http://localhost:4000" } }
+            });
+            (format!("package{}", i), project)
+        })
+        .collect();
+    json!({
+        "schemaPath": "schema.graphql",
+        "excludes": ["**/node_modules/**"],
+        "projects": projects
+    })
+    .to_string()
+}
+
+fn time(name: &str, mut parse: impl FnMut()) {
+    const ITERATIONS: u32 = 50;
+
+    parse();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        parse();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    println!("{:<12} {:>10.3} ms", name, elapsed.as_secs_f64() * 1000.0);
+}
+
+fn main() {
+    let document = document();
+
+    time("loader", || {
+        document.parse::<GraphQLConfiguration>().unwrap();
+    });
+    time("single-pass", || {
+        serde_json::from_str::<GraphQLConfiguration>(&document).unwrap();
+    });
+    time("flatten", || {
+        serde_json::from_str::<Flattened>(&document).unwrap();
+    });
+}
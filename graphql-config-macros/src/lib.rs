@@ -0,0 +1,115 @@
+//! `include_graphql_config!`, a configuration read at compile time.
+//!
+//! The macro reads a configuration file, relative to the directory of the
+//! `Cargo.toml` of the crate using it, and fails the build if the file does
+//! not load. The configuration is embedded in the binary and parsed again on
+//! first use, which always succeeds, so command line tools can ship with a
+//! default configuration known to be valid.
+//!
+//! ```ignore
+//! #[macro_use]
+//! extern crate graphql_config_macros;
+//! extern crate graphql_config;
+//!
+//! use graphql_config::GraphQLConfiguration;
+//!
+//! fn default_config() -> &'static GraphQLConfiguration {
+//!     include_graphql_config!("defaults/.graphqlconfig")
+//! }
+//! ```
+//!
+//! The crate using the macro must depend on `graphql-config` itself, which
+//! the expansion refers to.
+
+extern crate graphql_config;
+extern crate proc_macro;
+extern crate serde_json;
+
+use std::convert::TryFrom;
+use std::env;
+use std::path::PathBuf;
+
+use graphql_config::GraphQLConfiguration;
+use proc_macro::{TokenStream, TokenTree};
+
+/// Reads the configuration file at the path in the string literal argument,
+/// relative to `CARGO_MANIFEST_DIR`, and expands to a
+/// `&'static GraphQLConfiguration` of it. The crate is rebuilt when the file
+/// changes.
+#[proc_macro]
+pub fn include_graphql_config(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(expanded) => expanded,
+        Err(error) => format!("compile_error!({:?})", error)
+            .parse()
+            .expect("compile_error! invocations parse"),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let relative = path_argument(input)?;
+    let dir = env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .ok_or("`CARGO_MANIFEST_DIR` is not set")?;
+    let path = dir.join(&relative);
+
+    let config = GraphQLConfiguration::try_from(path.as_path())
+        .map_err(|error| format!("{}: {}", path.display(), error))?;
+    let json = serde_json::to_string(&config).map_err(|error| error.to_string())?;
+
+    // `include_bytes!` makes the file a dependency of the crate, so changes
+    // to it rebuild the crate.
+    let expanded = format!(
+        "{{
+            const _: &[u8] = include_bytes!({path:?});
+            static CONFIG: ::std::sync::OnceLock<::graphql_config::GraphQLConfiguration> =
+                ::std::sync::OnceLock::new();
+            CONFIG.get_or_init(|| {{
+                <::graphql_config::GraphQLConfiguration as ::std::convert::TryFrom<&str>>::try_from({json:?})
+                    .expect(\"the configuration was loaded at compile time\")
+            }})
+        }}",
+        path = path.to_string_lossy(),
+        json = json
+    );
+    expanded.parse().map_err(|error| format!("{:?}", error))
+}
+
+/// The value of the string literal which is the only argument.
+fn path_argument(input: TokenStream) -> Result<String, String> {
+    let usage =
+        "include_graphql_config! takes the path of the configuration file as a string literal";
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        _ => return Err(usage.to_owned()),
+    };
+
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.chars().take_while(|&c| c == '#').count();
+        return raw
+            .get(hashes + 1..raw.len() - hashes - 1)
+            .map(str::to_owned)
+            .ok_or_else(|| usage.to_owned());
+    }
+    if literal.len() < 2 || !literal.starts_with('"') || !literal.ends_with('"') {
+        return Err(usage.to_owned());
+    }
+    let mut value = String::new();
+    let mut chars = literal[1..literal.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => value.push('\\'),
+            Some('"') => value.push('"'),
+            Some('\'') => value.push('\''),
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            _ => return Err(format!("unsupported escape in {}", literal)),
+        }
+    }
+    Ok(value)
+}
@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate graphql_config_macros;
+extern crate graphql_config;
+
+use graphql_config::GraphQLConfiguration;
+
+fn embedded() -> &'static GraphQLConfiguration {
+    include_graphql_config!("tests/fixtures/.graphqlconfig.yml")
+}
+
+#[test]
+fn it_embeds_the_configuration() {
+    let config = embedded();
+    assert_eq!(config.root.schema_path, Some("schema.graphql".into()));
+    assert_eq!(
+        config
+            .iter_projects()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        vec!["web"]
+    );
+    assert!(std::ptr::eq(config, embedded()));
+}
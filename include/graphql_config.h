@@ -0,0 +1,33 @@
+/* The C interface of graphql-config, built with the `ffi` feature. */
+
+#ifndef GRAPHQL_CONFIG_H
+#define GRAPHQL_CONFIG_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A loaded configuration. */
+typedef struct GraphQLConfig GraphQLConfig;
+
+/* Loads the configuration file at `path`, or returns NULL. */
+GraphQLConfig *graphql_config_load(const char *path);
+
+/* The project `path` belongs to, or NULL. Freed with
+ * `graphql_config_string_free`. */
+char *graphql_config_project_for_file(const GraphQLConfig *config, const char *path);
+
+/* Frees a configuration returned by `graphql_config_load`. */
+void graphql_config_free(GraphQLConfig *config);
+
+/* Frees a string returned by the library. */
+void graphql_config_string_free(char *string);
+
+/* Why the last function returning NULL on this thread failed, or NULL. */
+const char *graphql_config_last_error(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,342 @@
+//! Conversion of [Apollo](https://www.apollographql.com/docs/devtools/apollo-config/)
+//! configurations, the JSON equivalent of an `apollo.config.js`.
+//!
+//! The `client` and `service` blocks of the Apollo configuration become the
+//! projects `client` and `service`:
+//!
+//! - `localSchemaFile` becomes the `schemaPath`,
+//! - a remote service becomes the `default` endpoint, with its `headers`,
+//! - `includes` and `excludes` are kept, or take the defaults of Apollo,
+//! - any other option, such as `tagName` or the name of a service registered
+//!   with Apollo, is kept in the `apollo` extension of the project.
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # extern crate serde_json;
+//! # use std::convert::TryFrom;
+//! # use graphql_config::apollo::ApolloConfig;
+//! # use graphql_config::GraphQLConfiguration;
+//! # fn main() {
+//! let apollo: ApolloConfig = serde_json::from_str(r#"{
+//!     "client": {
+//!         "service": { "name": "api", "url": "http://localhost:4000/graphql" },
+//!         "includes": ["src/**/*.ts"]
+//!     }
+//! }"#).unwrap();
+//! let config = GraphQLConfiguration::try_from(&apollo).unwrap();
+//!
+//! let client = &config.projects.as_ref().unwrap()["client"];
+//! assert_eq!(
+//!     client.extensions.as_ref().unwrap()["endpoints"]["default"]["url"],
+//!     "http://localhost:4000/graphql"
+//! );
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::glob::{GlobPattern, PatternError};
+use crate::map::Map;
+use crate::projects::ProjectName;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// The name of the extension keeping the options which have no
+/// graphql-config equivalent.
+pub const EXTENSION: &str = "apollo";
+
+const CLIENT_INCLUDES: &[&str] = &["src/**/*.{ts,tsx,js,jsx,graphql,gql}"];
+const SERVICE_INCLUDES: &[&str] = &["src/**/*.{ts,tsx,js,jsx}"];
+const EXCLUDES: &[&str] = &["**/node_modules", "**/__tests__"];
+
+/// An Apollo configuration, with a `client` block, a `service` block, or
+/// both.
+#[derive(Deserialize, Clone, Default, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct ApolloConfig {
+    /// The configuration of the operations of a client.
+    pub client: Option<ApolloBlock>,
+    /// The configuration of a GraphQL service.
+    pub service: Option<ApolloBlock>,
+}
+
+/// A `client` or `service` block.
+#[derive(Deserialize, Clone, Default, PartialEq, Debug)]
+#[non_exhaustive]
+#[serde(rename_all = "camelCase")]
+pub struct ApolloBlock {
+    /// The schema of a client: the name of a service registered with Apollo,
+    /// or a local or remote service.
+    pub service: Option<ApolloService>,
+    /// The schema of a service, read from files.
+    pub local_schema_file: Option<SchemaFiles>,
+    /// The endpoint of a service.
+    pub endpoint: Option<ApolloEndpoint>,
+    /// Files which belong to the block.
+    pub includes: Option<Vec<String>>,
+    /// Files which do not belong to the block.
+    pub excludes: Option<Vec<String>>,
+    /// Every other option of the block.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// The `service` of a `client` block.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum ApolloService {
+    /// A service registered with Apollo, by name.
+    Registered(String),
+    /// A service with a schema available locally or at an endpoint.
+    Service {
+        /// The name of the service.
+        name: Option<String>,
+        /// The schema of the service, read from files.
+        #[serde(rename = "localSchemaFile")]
+        local_schema_file: Option<SchemaFiles>,
+        /// The URL of the service.
+        url: Option<String>,
+        /// Headers sent to the service.
+        headers: Option<Map<String, String>>,
+    },
+}
+
+/// The `endpoint` of a `service` block: its URL, or an object with headers.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum ApolloEndpoint {
+    /// The URL of the endpoint.
+    Url(String),
+    /// The URL of the endpoint and headers sent to it.
+    Object {
+        /// The URL of the endpoint.
+        url: String,
+        /// Headers sent to the endpoint.
+        headers: Option<Map<String, String>>,
+    },
+}
+
+/// `localSchemaFile` accepts a single file or a list of them.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum SchemaFiles {
+    /// A single schema file.
+    One(String),
+    /// Schema files merged into the schema.
+    Many(Vec<String>),
+}
+
+/// Why an Apollo configuration could not be converted.
+#[derive(Debug)]
+pub enum ApolloError {
+    /// A block has several schema files, where graphql-config accepts one.
+    /// The variant holds the name of the block.
+    SeveralSchemaFiles(&'static str),
+    /// An `includes` or `excludes` pattern of the block named by the variant
+    /// is malformed.
+    Pattern(&'static str, PatternError),
+}
+
+impl fmt::Display for ApolloError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ApolloError::SeveralSchemaFiles(block) => write!(
+                f,
+                "`{}`: `localSchemaFile` lists several files, a single one is supported",
+                block
+            ),
+            ApolloError::Pattern(block, ref error) => write!(f, "`{}`: {}", block, error),
+        }
+    }
+}
+
+impl Error for ApolloError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ApolloError::SeveralSchemaFiles(_) => None,
+            ApolloError::Pattern(_, ref error) => Some(error),
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a ApolloConfig> for GraphQLConfiguration {
+    type Error = ApolloError;
+
+    fn try_from(apollo: &'a ApolloConfig) -> Result<GraphQLConfiguration, ApolloError> {
+        let mut projects = Map::new();
+        if let Some(client) = apollo.client.as_ref() {
+            projects.insert(
+                ProjectName::new("client").expect("the name is valid"),
+                convert(client, "client", CLIENT_INCLUDES)?,
+            );
+        }
+        if let Some(service) = apollo.service.as_ref() {
+            projects.insert(
+                ProjectName::new("service").expect("the name is valid"),
+                convert(service, "service", SERVICE_INCLUDES)?,
+            );
+        }
+
+        let mut config = GraphQLConfiguration::new();
+        config.projects = Some(projects);
+        Ok(config)
+    }
+}
+
+fn convert(
+    block: &ApolloBlock,
+    name: &'static str,
+    default_includes: &[&str],
+) -> Result<GraphQLProjectConfiguration, ApolloError> {
+    let mut apollo = block.other.clone();
+    let mut schema_files = block.local_schema_file.as_ref();
+    let mut endpoint = block.endpoint.as_ref().map(|endpoint| match *endpoint {
+        ApolloEndpoint::Url(ref url) => (url.clone(), None),
+        ApolloEndpoint::Object {
+            ref url,
+            ref headers,
+        } => (url.clone(), headers.clone()),
+    });
+    match block.service {
+        Some(ApolloService::Registered(ref service)) => {
+            apollo.insert("service".to_owned(), Value::String(service.clone()));
+        }
+        Some(ApolloService::Service {
+            name: ref service,
+            ref local_schema_file,
+            ref url,
+            ref headers,
+        }) => {
+            if let Some(service) = service {
+                apollo.insert("service".to_owned(), Value::String(service.clone()));
+            }
+            schema_files = schema_files.or(local_schema_file.as_ref());
+            if let Some(url) = url {
+                endpoint = Some((url.clone(), headers.clone()));
+            }
+        }
+        None => {}
+    }
+
+    let mut project = GraphQLProjectConfiguration::new();
+    project.schema_path = match schema_files {
+        Some(SchemaFiles::One(file)) => Some(file.into()),
+        Some(SchemaFiles::Many(files)) => match files.as_slice() {
+            [] => None,
+            [file] => Some(file.into()),
+            _ => return Err(ApolloError::SeveralSchemaFiles(name)),
+        },
+        None => None,
+    };
+    let patterns = |patterns: Option<&Vec<String>>, defaults: &[&str]| {
+        let patterns: Result<Vec<GlobPattern>, PatternError> = match patterns {
+            Some(patterns) => patterns.iter().map(GlobPattern::new).collect(),
+            None => defaults
+                .iter()
+                .map(|pattern| GlobPattern::new(*pattern))
+                .collect(),
+        };
+        patterns
+            .map(Some)
+            .map_err(|error| ApolloError::Pattern(name, error))
+    };
+    project.includes = patterns(block.includes.as_ref(), default_includes)?;
+    project.excludes = patterns(block.excludes.as_ref(), EXCLUDES)?;
+
+    let mut extensions = Map::new();
+    if let Some((url, headers)) = endpoint {
+        let endpoint = match headers {
+            Some(headers) => serde_json::json!({ "url": url, "headers": headers }),
+            None => serde_json::json!({ "url": url }),
+        };
+        extensions.insert(
+            "endpoints".to_owned(),
+            serde_json::json!({ "default": endpoint }),
+        );
+    }
+    if !apollo.is_empty() {
+        extensions.insert(
+            EXTENSION.to_owned(),
+            Value::Object(apollo.into_iter().collect()),
+        );
+    }
+    if !extensions.is_empty() {
+        project.extensions = Some(extensions);
+    }
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_client_and_service_blocks() {
+        let apollo: ApolloConfig = ::serde_json::from_value(json!({
+            "client": {
+                "service": { "name": "api", "localSchemaFile": "./schema.graphql" },
+                "includes": ["./src/**/*.tsx"],
+                "tagName": "gql"
+            },
+            "service": {
+                "endpoint": {
+                    "url": "http://localhost:4000/graphql",
+                    "headers": { "Authorization": "Bearer ${env:TOKEN}" }
+                }
+            }
+        }))
+        .unwrap();
+
+        let config = GraphQLConfiguration::try_from(&apollo).unwrap();
+
+        assert_eq!(
+            ::serde_json::to_value(&config).unwrap(),
+            json!({
+                "name": null,
+                "schemaPath": null,
+                "includes": null,
+                "excludes": null,
+                "extensions": null,
+                "projects": {
+                    "client": {
+                        "name": null,
+                        "schemaPath": "./schema.graphql",
+                        "includes": ["./src/**/*.tsx"],
+                        "excludes": ["**/node_modules", "**/__tests__"],
+                        "extensions": { "apollo": { "service": "api", "tagName": "gql" } }
+                    },
+                    "service": {
+                        "name": null,
+                        "schemaPath": null,
+                        "includes": ["src/**/*.{ts,tsx,js,jsx}"],
+                        "excludes": ["**/node_modules", "**/__tests__"],
+                        "extensions": {
+                            "endpoints": {
+                                "default": {
+                                    "url": "http://localhost:4000/graphql",
+                                    "headers": { "Authorization": "Bearer ${env:TOKEN}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_rejects_several_schema_files() {
+        let apollo: ApolloConfig = ::serde_json::from_value(json!({
+            "service": { "localSchemaFile": ["a.graphql", "b.graphql"] }
+        }))
+        .unwrap();
+
+        match GraphQLConfiguration::try_from(&apollo) {
+            Err(ApolloError::SeveralSchemaFiles("service")) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
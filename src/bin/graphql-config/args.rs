@@ -0,0 +1,121 @@
+//! Parsing of the command line, and the options every command shares.
+
+use std::convert::TryFrom;
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+use graphql_config::discover;
+use graphql_config::env::Env;
+use graphql_config::hosts::HostMap;
+use graphql_config::load::LoadError;
+use graphql_config::GraphQLConfiguration;
+
+/// Why `Args::config_path` has no path.
+pub enum ConfigPathError {
+    /// `--config` has no value, or the working directory cannot be read.
+    Invalid(String),
+    /// No configuration file was found.
+    NotFound(String),
+}
+
+/// The arguments after the command name, consumed by the command.
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn new(args: impl Iterator<Item = String>) -> Args {
+        Args {
+            args: args.collect(),
+        }
+    }
+
+    /// Takes the command, the first argument.
+    pub fn command(&mut self) -> Option<String> {
+        if self.args.is_empty() {
+            None
+        } else {
+            Some(self.args.remove(0))
+        }
+    }
+
+    /// Takes the value of `--name value` or `--name=value`.
+    pub fn option(&mut self, name: &str) -> Result<Option<String>, String> {
+        let flag = format!("--{}", name);
+        let prefix = format!("--{}=", name);
+        for index in 0..self.args.len() {
+            if self.args[index] == flag {
+                if index + 1 == self.args.len() {
+                    return Err(format!("`{}` needs a value", flag));
+                }
+                let value = self.args.remove(index + 1);
+                self.args.remove(index);
+                return Ok(Some(value));
+            }
+            if self.args[index].starts_with(&prefix) {
+                return Ok(Some(self.args.remove(index)[prefix.len()..].to_owned()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Takes `--name`, returning whether it was given.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let flag = format!("--{}", name);
+        match self.args.iter().position(|arg| *arg == flag) {
+            Some(index) => {
+                self.args.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes the remaining arguments, which must not be options.
+    pub fn positional(self) -> Result<Vec<String>, String> {
+        match self.args.iter().find(|arg| arg.starts_with("--")) {
+            Some(option) => Err(format!("unknown option `{}`", option)),
+            None => Ok(self.args),
+        }
+    }
+
+    /// Takes the path of `--config`, or finds the configuration file of the
+    /// working directory or of its closest ancestor with one.
+    pub fn config_path(&mut self) -> Result<PathBuf, ConfigPathError> {
+        if let Some(path) = self.option("config").map_err(ConfigPathError::Invalid)? {
+            return Ok(path.into());
+        }
+        let dir =
+            env::current_dir().map_err(|error| ConfigPathError::Invalid(error.to_string()))?;
+        discover::find_config_file(dir)
+            .map_err(|error| ConfigPathError::NotFound(error.to_string()))
+    }
+}
+
+/// Loads the configuration at `path`.
+pub fn load(path: &Path) -> Result<GraphQLConfiguration, LoadError> {
+    GraphQLConfiguration::try_from(path)
+}
+
+/// The host map of `GRAPHQL_CONFIG_HOSTS`, for the commands fetching
+/// schemas from endpoints.
+pub fn host_map() -> Result<HostMap, String> {
+    HostMap::from_env(&Env::process()).map_err(|error| format!("`GRAPHQL_CONFIG_HOSTS`: {}", error))
+}
+
+/// `path` relative to the working directory, with `.` and `..` resolved
+/// without following symbolic links.
+pub fn absolute(path: &Path) -> Result<PathBuf, String> {
+    let dir = env::current_dir().map_err(|error| error.to_string())?;
+    let mut absolute = PathBuf::new();
+    for component in dir.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            component => absolute.push(component),
+        }
+    }
+    Ok(absolute)
+}
@@ -0,0 +1,43 @@
+//! `graphql-config diff <a> <b>`: prints the changes turning the
+//! configuration `a` into `b`, one per line.
+//!
+//! Every change is reported as a warning, so the command exits with 0 when
+//! the configurations are equal and, with `--strict`, with 1 when they
+//! differ.
+
+use std::path::Path;
+
+use args::Args;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("diff");
+    diff(args, &mut report);
+    output.finish(report)
+}
+
+fn diff(args: Args, report: &mut Report) -> Option<()> {
+    let paths = report.usage(args.positional())?;
+    if paths.len() != 2 {
+        report.error("usage", "diff takes two configuration files");
+        return None;
+    }
+    let before = report.load(Path::new(&paths[0]));
+    let after = report.load(Path::new(&paths[1]));
+    let (before, after) = (before?, after?);
+
+    let diff = before.diff(&after);
+    report.print(diff.to_string());
+    for change in &diff.changes {
+        report
+            .warning("config-change", change)
+            .at(&paths[1])
+            .hidden_in_text();
+    }
+    report.set_result(serde_json::to_value(&diff).expect("diffs serialize"));
+    Some(())
+}
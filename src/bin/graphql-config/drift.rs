@@ -0,0 +1,84 @@
+//! `graphql-config drift`: checks that the schema files of projects are
+//! committed and match their endpoint, for CI.
+//!
+//! Every project with a schema file is checked, or the one of `--project`.
+//! Projects with the endpoint named by `--endpoint`, `default` unless given,
+//! are introspected and compared with it too. A schema which has drifted is
+//! an error.
+
+use graphql_config::drift::SchemaDrift;
+use graphql_config::resolved::ResolvedGraphQLConfig;
+
+use args::{self, Args};
+use get_schema;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("drift");
+    drift(args, &mut report);
+    output.finish(report)
+}
+
+fn drift(mut args: Args, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    let project = report.usage(args.option("project"))?;
+    let endpoint = report.usage(args.option("endpoint"))?;
+    report.usage(args.positional())?;
+    let endpoint = endpoint.as_deref().unwrap_or("default");
+
+    let config = ResolvedGraphQLConfig::new(report.load(&path)?, &path);
+    let config = config.with_host_map(report.usage(args::host_map())?);
+    let names: Vec<String> = match project {
+        Some(project) => vec![project],
+        None => config
+            .projects()
+            .filter(|(_, project)| project.schema_path.is_some())
+            .map(|(name, _)| name.to_string())
+            .collect(),
+    };
+
+    let mut result = Vec::new();
+    for name in &names {
+        let drift = match SchemaDrift::check(&config, name) {
+            Ok(drift) => drift,
+            Err(error) => {
+                report.error("schema-drift", error);
+                continue;
+            }
+        };
+        let drift = if config.endpoint(name, endpoint).is_some() {
+            match get_schema::introspect(&config, name, endpoint) {
+                Ok(remote) => drift.with_remote(remote),
+                Err(error) => {
+                    report.error("endpoint", format!("project `{}`: {}", name, error));
+                    continue;
+                }
+            }
+        } else {
+            drift
+        };
+        let text = drift.to_string();
+        if drift.is_drifted() {
+            for line in text.lines() {
+                report
+                    .error("schema-drift", line)
+                    .at(&drift.schema_path)
+                    .hidden_in_text();
+            }
+        }
+        report.print(text);
+        result.push(serde_json::json!({
+            "project": name,
+            "schemaPath": drift.schema_path,
+            "uncommitted": drift.uncommitted(),
+            "outdated": drift.outdated(),
+            "outdatedInHead": drift.outdated_in_head(),
+        }));
+    }
+    report.set_result(result.into());
+    Some(())
+}
@@ -0,0 +1,132 @@
+//! `graphql-config get-schema`: introspects the endpoint of projects and
+//! writes their schema, as SDL, to their `schemaPath`.
+//!
+//! The endpoint is the one named by `--endpoint`, `default` unless given, of
+//! the `endpoints` extension, with the `headers` of its object form sent
+//! along. Without `--project`, every project with such an endpoint is
+//! fetched, several at a time.
+
+use std::fs;
+
+use serde_json::Value;
+
+use graphql_config::fetch;
+use graphql_config::introspection::{self, INTROSPECTION_QUERY};
+use graphql_config::resolved::ResolvedGraphQLConfig;
+use graphql_config::schema::SchemaPointer;
+
+use args::{self, Args};
+use http;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("get-schema");
+    get_schemas(args, &mut report);
+    output.finish(report)
+}
+
+fn get_schemas(mut args: Args, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    let project = report.usage(args.option("project"))?;
+    let endpoint = report.usage(args.option("endpoint"))?;
+    report.usage(args.positional())?;
+    let endpoint = endpoint.as_deref().unwrap_or("default");
+
+    let config = ResolvedGraphQLConfig::new(report.load(&path)?, &path);
+    let config = config.with_host_map(report.usage(args::host_map())?);
+    let fetched: Vec<(String, Result<String, String>)> = match project {
+        Some(project) => {
+            if config.project(&project).is_none() {
+                report.error(
+                    "unknown-project",
+                    format!("there is no project `{}`", project),
+                );
+                return None;
+            }
+            let written = get_schema(&config, &project, endpoint);
+            vec![(project, written)]
+        }
+        None => {
+            fetch::fetch_all_schemas(&config, endpoint, fetch::DEFAULT_CONCURRENCY, |request| {
+                get_schema(&config, &request.project, endpoint)
+            })
+            .into_iter()
+            .map(|fetch| (fetch.request.project.into_string(), fetch.result))
+            .collect()
+        }
+    };
+    if fetched.is_empty() {
+        report.error(
+            "endpoint",
+            format!("no project has the endpoint `{}`", endpoint),
+        );
+        return None;
+    }
+
+    let mut written = Vec::new();
+    for (name, result) in fetched {
+        match result {
+            Ok(path) => {
+                report.println(format!("project `{}`: wrote {}", name, path));
+                written.push(serde_json::json!({ "project": name, "path": path }));
+            }
+            Err(error) => {
+                report.error("endpoint", format!("project `{}`: {}", name, error));
+            }
+        }
+    }
+    report.set_result(written.into());
+    Some(())
+}
+
+fn get_schema(
+    config: &ResolvedGraphQLConfig,
+    name: &str,
+    endpoint: &str,
+) -> Result<String, String> {
+    let schema_path = config
+        .project(name)
+        .and_then(|project| project.schema_path)
+        .ok_or("there is no `schemaPath` to write the schema to")?;
+    let schema_path = match SchemaPointer::classify(&schema_path.to_string_lossy(), config.dir()) {
+        SchemaPointer::File(schema_path) => schema_path,
+        _ => {
+            return Err(format!(
+                "`schemaPath` `{}` is not a file",
+                schema_path.display()
+            ))
+        }
+    };
+
+    let sdl = introspect(config, name, endpoint)?;
+
+    if let Some(dir) = schema_path.parent() {
+        fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    fs::write(&schema_path, sdl)
+        .map_err(|error| format!("`{}`: {}", schema_path.display(), error))?;
+    Ok(schema_path.display().to_string())
+}
+
+/// The schema of the `endpoint` of the project `name`, as SDL.
+pub fn introspect(
+    config: &ResolvedGraphQLConfig,
+    name: &str,
+    endpoint: &str,
+) -> Result<String, String> {
+    let url = config
+        .endpoint(name, endpoint)
+        .ok_or_else(|| format!("there is no endpoint `{}`", endpoint))?;
+    let body = serde_json::json!({ "query": INTROSPECTION_QUERY }).to_string();
+    let response = http::post_json(&url, &config.endpoint_headers(name, endpoint), &body)?;
+    let response: Value = serde_json::from_str(&response)
+        .map_err(|error| format!("`{}` answered invalid JSON: {}", url, error))?;
+    if let Some(errors) = response.get("errors") {
+        return Err(format!("`{}` answered with errors: {}", url, errors));
+    }
+    introspection::to_sdl(&response).map_err(|error| format!("`{}`: {}", url, error))
+}
@@ -0,0 +1,82 @@
+//! Just enough HTTP to send a GraphQL request.
+//!
+//! The command has no dependencies beyond the library, so this speaks
+//! HTTP/1.1 over a `TcpStream` itself. Only `http://` URLs are supported;
+//! endpoints behind TLS need a proxy or a schema fetched by other means.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Posts `body` as JSON to `url` with the extra `headers`, and returns the
+/// body of a successful response.
+pub fn post_json(url: &str, headers: &[(String, String)], body: &str) -> Result<String, String> {
+    let (host, path) = match url.strip_prefix("http://") {
+        Some(rest) => match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        },
+        None if url.starts_with("https://") => {
+            return Err(format!("`{}`: https endpoints are not supported", url))
+        }
+        None => return Err(format!("`{}` is not an http URL", url)),
+    };
+    let address = if host.contains(':') {
+        host.to_owned()
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nAccept: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        path,
+        host,
+        body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    let failed = |error: ::std::io::Error| format!("`{}`: {}", url, error);
+    let mut stream = TcpStream::connect(&address).map_err(failed)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(failed)?;
+    stream.write_all(request.as_bytes()).map_err(failed)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(failed)?;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| format!("`{}`: malformed response", url))?;
+    let mut lines = head.lines();
+    let status = lines.next().unwrap_or_default();
+    if status.split(' ').nth(1) != Some("200") {
+        return Err(format!("`{}` answered `{}`", url, status));
+    }
+    let chunked = lines.any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+    if chunked {
+        dechunk(body).ok_or_else(|| format!("`{}`: malformed chunked response", url))
+    } else {
+        Ok(body.to_owned())
+    }
+}
+
+fn dechunk(mut body: &str) -> Option<String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.push_str(rest.get(..size)?);
+        body = rest.get(size..)?.strip_prefix("\r\n")?;
+    }
+}
@@ -0,0 +1,187 @@
+//! `graphql-config init`: writes a starter configuration in the working
+//! directory, from one of the `ConfigTemplate`s.
+//!
+//! The template is chosen with `--template single|monorepo|endpoint`, along
+//! with `--packages a,b` for `monorepo` and `--endpoint <url>` for
+//! `endpoint`. Without `--template`, the choices are asked for when stdin is
+//! a terminal, and `single` is used otherwise. `--config-format yaml` writes
+//! `.graphqlconfig.yml` rather than `.graphqlconfig`, and an existing
+//! configuration is only replaced with `--force`.
+
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use serde_json::Value;
+
+use graphql_config::discover;
+use graphql_config::template::ConfigTemplate;
+
+use args::Args;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("init");
+    init(args, &mut report);
+    output.finish(report)
+}
+
+fn init(mut args: Args, report: &mut Report) -> Option<()> {
+    let Options {
+        template,
+        packages,
+        endpoint,
+        config_format,
+        force,
+    } = report.usage(Options::take(&mut args))?;
+    report.usage(args.positional())?;
+    let file_name = match config_format.as_deref() {
+        None | Some("json") => ".graphqlconfig",
+        Some("yaml") => ".graphqlconfig.yml",
+        Some(format) => {
+            report.error(
+                "usage",
+                format!("unknown configuration format `{}`", format),
+            );
+            return None;
+        }
+    };
+
+    let template = match template {
+        Some(template) => from_options(&template, packages, endpoint),
+        None if io::stdin().is_terminal() => prompt(),
+        None => Ok(ConfigTemplate::SingleProject),
+    };
+    let config = report
+        .usage(template.and_then(|template| template.build().map_err(|error| error.to_string())))?;
+
+    let dir = match env::current_dir() {
+        Ok(dir) => dir,
+        Err(error) => {
+            report.error("io", error);
+            return None;
+        }
+    };
+    if let Some(existing) = discover::config_file(&dir) {
+        if !force {
+            report
+                .error(
+                    "config-exists",
+                    format!(
+                        "`{}` already exists, pass --force to write `{}` anyway",
+                        existing.display(),
+                        file_name
+                    ),
+                )
+                .at(existing);
+            return None;
+        }
+    }
+
+    let mut config = serde_json::to_value(&config).expect("configurations serialize");
+    remove_nulls(&mut config);
+    let contents = if file_name.ends_with(".yml") {
+        serde_yaml::to_string(&config).expect("values serialize")
+    } else {
+        serde_json::to_string_pretty(&config).expect("values serialize") + "\n"
+    };
+    let path = dir.join(file_name);
+    match fs::write(&path, contents) {
+        Ok(()) => {
+            report.println(format!("wrote {}", path.display()));
+            report.set_result(serde_json::json!({ "path": path, "config": config }));
+        }
+        Err(error) => {
+            report.error("io", error).at(path);
+        }
+    }
+    Some(())
+}
+
+struct Options {
+    template: Option<String>,
+    packages: Option<String>,
+    endpoint: Option<String>,
+    config_format: Option<String>,
+    force: bool,
+}
+
+impl Options {
+    fn take(args: &mut Args) -> Result<Options, String> {
+        Ok(Options {
+            template: args.option("template")?,
+            packages: args.option("packages")?,
+            endpoint: args.option("endpoint")?,
+            config_format: args.option("config-format")?,
+            force: args.flag("force"),
+        })
+    }
+}
+
+fn from_options(
+    template: &str,
+    packages: Option<String>,
+    endpoint: Option<String>,
+) -> Result<ConfigTemplate, String> {
+    match template {
+        "single" => Ok(ConfigTemplate::SingleProject),
+        "monorepo" => packages
+            .map(|packages| ConfigTemplate::Monorepo(split(&packages)))
+            .ok_or_else(|| "`--template monorepo` needs `--packages`".to_owned()),
+        "endpoint" => endpoint
+            .map(ConfigTemplate::EndpointBased)
+            .ok_or_else(|| "`--template endpoint` needs `--endpoint`".to_owned()),
+        template => Err(format!("unknown template `{}`", template)),
+    }
+}
+
+fn prompt() -> Result<ConfigTemplate, String> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut ask = |question: &str| -> Result<String, String> {
+        print!("{} ", question);
+        io::stdout().flush().map_err(|error| error.to_string())?;
+        match lines.next() {
+            Some(line) => line
+                .map(|line| line.trim().to_owned())
+                .map_err(|error| error.to_string()),
+            None => Err("no answer".to_owned()),
+        }
+    };
+
+    match ask("Is the repository a monorepo of several packages? [y/N]")?.as_str() {
+        "y" | "Y" | "yes" => {
+            let packages = ask("What are the names of the packages, separated by commas?")?;
+            return Ok(ConfigTemplate::Monorepo(split(&packages)));
+        }
+        _ => {}
+    }
+    let endpoint = ask("What is the URL of the GraphQL endpoint, if the schema is served by one?")?;
+    if endpoint.is_empty() {
+        Ok(ConfigTemplate::SingleProject)
+    } else {
+        Ok(ConfigTemplate::EndpointBased(endpoint))
+    }
+}
+
+fn split(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Removes the unset fields, which configurations serialize as `null`.
+fn remove_nulls(value: &mut Value) {
+    if let Value::Object(ref mut fields) = *value {
+        fields.retain(|_, field| !field.is_null());
+        for field in fields.values_mut() {
+            remove_nulls(field);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! The `graphql-config` command, enabled by the `cli` feature.
+
+extern crate graphql_config;
+extern crate serde_json;
+extern crate serde_yaml;
+
+mod args;
+mod diff;
+mod drift;
+mod get_schema;
+mod http;
+mod init;
+mod migrate;
+mod projects;
+mod report;
+mod show;
+mod validate;
+mod which_project;
+
+use std::env;
+use std::process;
+
+use args::Args;
+
+const USAGE: &str = "usage: graphql-config <command> [options]
+
+commands:
+  diff <a> <b>
+              print the changes from the configuration a to b, as warnings
+  drift       check that the schema files of projects are committed and
+              match their endpoint, with --project and --endpoint as for
+              get-schema
+  get-schema  introspect the endpoint of projects and write their schema
+              to their schemaPath, with --project <name> for one project and
+              --endpoint <name> for another endpoint than `default`
+  init        write a starter configuration, see --template
+              single|monorepo|endpoint, --packages, --endpoint,
+              --config-format json|yaml and --force
+  migrate     rewrite the configuration as a v3 .graphqlrc.yml next to it,
+              with --dry-run to print it instead and --force to replace one
+  projects    list the projects with their schema and number of documents
+  show        print the project of --project <name> with the top-level
+              settings merged in, as JSON, or YAML with --format yaml
+  validate    check the configuration and print diagnostics
+  which-project <file>
+              print the project the file belongs to
+
+options:
+  --config <path>    the configuration file, instead of looking for one
+                     in the working directory and its ancestors
+  --format text|json|sarif
+                     print the output as text, as a JSON report of the
+                     findings and the result, or as a SARIF log for CI
+                     systems to annotate pull requests with
+  --strict           fail on warnings too
+
+exit codes:
+  0  nothing is wrong
+  1  there are warnings, with --strict
+  2  there are errors, or the command line is invalid
+  3  there is no configuration file
+
+environment:
+  GRAPHQL_CONFIG_HOSTS
+              hosts of endpoints to rewrite, as from=to pairs separated by
+              commas or `docker` for localhost=host.docker.internal";
+
+fn main() {
+    let mut args = Args::new(env::args().skip(1));
+    let code = match args.command().as_deref() {
+        Some("diff") => diff::run(args),
+        Some("drift") => drift::run(args),
+        Some("get-schema") => get_schema::run(args),
+        Some("init") => init::run(args),
+        Some("migrate") => migrate::run(args),
+        Some("projects") => projects::run(args),
+        Some("show") => show::run(args),
+        Some("validate") => validate::run(args),
+        Some("which-project") => which_project::run(args),
+        Some("help") | Some("--help") | Some("-h") | None => {
+            println!("{}", USAGE);
+            0
+        }
+        Some(command) => {
+            eprintln!("unknown command `{}`\n\n{}", command, USAGE);
+            report::ERRORS
+        }
+    };
+    process::exit(code);
+}
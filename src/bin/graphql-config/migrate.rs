@@ -0,0 +1,138 @@
+//! `graphql-config migrate`: rewrites a legacy configuration as a v3
+//! `.graphqlrc.yml` next to it, and reports what needs a look by hand.
+//!
+//! The legacy file is kept, and `.graphqlrc.yml` takes precedence over it
+//! when configurations are looked for. An existing `.graphqlrc.yml` is only
+//! replaced with `--force`, and `--dry-run` prints the migrated
+//! configuration instead of writing it.
+
+use std::fs;
+
+use graphql_config::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+use args::Args;
+use report::{self, Output, Report};
+
+const FILE_NAME: &str = ".graphqlrc.yml";
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("migrate");
+    migrate(args, &mut report);
+    output.finish(report)
+}
+
+fn migrate(mut args: Args, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    let force = args.flag("force");
+    let dry_run = args.flag("dry-run");
+    report.usage(args.positional())?;
+    let config = report.load(&path)?;
+
+    let migrated = match config.export_v3_yaml() {
+        Ok(migrated) => migrated,
+        Err(error) => {
+            report.error("migration", error).at(&path);
+            return None;
+        }
+    };
+    if dry_run {
+        report.print(migrated.clone());
+        report.set_result(serde_json::json!({ "path": null, "contents": migrated }));
+    } else {
+        let target = path.with_file_name(FILE_NAME);
+        if target == path {
+            report
+                .error(
+                    "migration",
+                    format!("`{}` is already a v3 configuration file", path.display()),
+                )
+                .at(&path);
+            return None;
+        }
+        if target.exists() && !force {
+            report
+                .error(
+                    "config-exists",
+                    format!(
+                        "`{}` already exists, pass --force to replace it",
+                        target.display()
+                    ),
+                )
+                .at(&target);
+            return None;
+        }
+        if let Err(error) = fs::write(&target, &migrated) {
+            report.error("io", error).at(&target);
+            return None;
+        }
+        report.println(format!("wrote {}", target.display()));
+        report.println(format!(
+            "kept {}, which can be removed once every tool reads the new file",
+            path.display()
+        ));
+        report.set_result(serde_json::json!({ "path": target, "contents": migrated }));
+    }
+    for note in notes(&config) {
+        report.note("migration", note).at(&path);
+    }
+    Some(())
+}
+
+/// What the conversion could not carry over unchanged.
+fn notes(config: &GraphQLConfiguration) -> Vec<String> {
+    let mut notes = Vec::new();
+    project_notes(
+        "the top-level configuration",
+        None,
+        &config.root,
+        &mut notes,
+    );
+    for (key, project) in config.projects.iter().flatten() {
+        let owner = format!("project `{}`", key);
+        project_notes(&owner, Some(key.as_ref()), project, &mut notes);
+    }
+    notes
+}
+
+fn project_notes(
+    owner: &str,
+    key: Option<&str>,
+    project: &GraphQLProjectConfiguration,
+    notes: &mut Vec<String>,
+) {
+    match (project.name.as_deref(), key) {
+        (Some(name), Some(key)) if name == key => {}
+        (Some(name), _) => notes.push(format!(
+            "{}: the name `{}` was dropped, v3 names projects by their key",
+            owner, name
+        )),
+        (None, _) => {}
+    }
+    if project
+        .includes
+        .as_ref()
+        .is_some_and(|includes| !includes.is_empty())
+    {
+        notes.push(format!(
+            "{}: `includes` became `include`, move the patterns of operations and fragments to `documents`",
+            owner
+        ));
+    }
+    let placeholders = project
+        .schema_path
+        .as_ref()
+        .is_some_and(|schema_path| schema_path.to_string_lossy().contains("${env:"))
+        || project.extensions.as_ref().is_some_and(|extensions| {
+            serde_json::to_string(extensions).is_ok_and(|extensions| extensions.contains("${env:"))
+        });
+    if placeholders {
+        notes.push(format!(
+            "{}: v3 writes the `${{env:NAME}}` placeholders as `${{NAME}}`",
+            owner
+        ));
+    }
+}
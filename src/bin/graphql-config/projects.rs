@@ -0,0 +1,63 @@
+//! `graphql-config projects`: lists the projects, one per line, with the
+//! schema they point to and the number of documents they include.
+
+use graphql_config::resolved::ResolvedGraphQLConfig;
+use serde_json::Value;
+
+use args::Args;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("projects");
+    projects(args, &mut report);
+    output.finish(report)
+}
+
+fn projects(mut args: Args, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    report.usage(args.positional())?;
+    let config = ResolvedGraphQLConfig::new(report.load(&path)?, &path);
+
+    let handles = config.project_handles();
+    let width = handles
+        .iter()
+        .map(|handle| handle.name().len())
+        .max()
+        .unwrap_or_default();
+    let mut result = Vec::new();
+    for handle in &handles {
+        let schema_path = &handle.config().schema_path;
+        let schema = schema_path
+            .as_ref()
+            .map_or_else(|| "-".into(), |schema_path| schema_path.to_string_lossy());
+        let (documents, count) = match handle.document_files() {
+            Ok(files) => match files.len() {
+                1 => ("1 document".to_owned(), Value::from(1)),
+                count => (format!("{} documents", count), Value::from(count)),
+            },
+            Err(error) => {
+                let message = format!("project `{}`: {}", handle.name(), error);
+                report.error("io", message).hidden_in_text();
+                (format!("error: {}", error), Value::Null)
+            }
+        };
+        report.println(format!(
+            "{:width$}  {}  {}",
+            handle.name(),
+            schema,
+            documents,
+            width = width
+        ));
+        result.push(serde_json::json!({
+            "name": handle.name(),
+            "schemaPath": schema_path,
+            "documents": count,
+        }));
+    }
+    report.set_result(Value::Array(result));
+    Some(())
+}
@@ -0,0 +1,395 @@
+//! What commands report, and how it is printed: as text, as JSON, or as
+//! SARIF for CI systems to annotate pull requests with.
+//!
+//! Every command takes `--format text|json|sarif` and `--strict`, and exits
+//! with `OK` when nothing is wrong, `WARNINGS` when there are warnings and
+//! `--strict` is given, `ERRORS` when there are errors, and `NOT_FOUND` when
+//! there is no configuration file to read.
+
+use std::env;
+use std::fmt;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use graphql_config::load::LoadError;
+use graphql_config::GraphQLConfiguration;
+
+use args::{self, Args, ConfigPathError};
+
+pub const OK: i32 = 0;
+pub const WARNINGS: i32 = 1;
+pub const ERRORS: i32 = 2;
+pub const NOT_FOUND: i32 = 3;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+impl Format {
+    fn parse(format: &str) -> Result<Format, String> {
+        match format {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "sarif" => Ok(Format::Sarif),
+            format => Err(format!("unknown format `{}`", format)),
+        }
+    }
+}
+
+/// How a command prints its report: `--format` and `--strict`.
+pub struct Output {
+    pub format: Format,
+    strict: bool,
+}
+
+impl Output {
+    /// Takes `--format` and `--strict`.
+    pub fn take(args: &mut Args) -> Result<Output, String> {
+        let format = args.option("format")?;
+        Output::new(format.as_deref(), args.flag("strict"))
+    }
+
+    /// The output of `--format`, text unless given, for the commands which
+    /// accept other formats too.
+    pub fn new(format: Option<&str>, strict: bool) -> Result<Output, String> {
+        Ok(Output {
+            format: format.map_or(Ok(Format::Text), Format::parse)?,
+            strict,
+        })
+    }
+
+    /// Prints `report` and returns the exit code it amounts to.
+    pub fn finish(&self, report: Report) -> i32 {
+        let code = report.code(self.strict);
+        match self.format {
+            Format::Text => report.print_text(),
+            Format::Json => println!("{:#}", report.json(code)),
+            Format::Sarif => println!("{:#}", report.sarif()),
+        }
+        code
+    }
+}
+
+/// Prints an error of the command line found before the output is known.
+pub fn usage_error(error: &str) -> i32 {
+    eprintln!("error: {}", error);
+    ERRORS
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+        }
+    }
+}
+
+/// A problem, or a remark, about a file. `rule` names its kind, as the
+/// `ruleId` of SARIF.
+pub struct Finding {
+    level: Level,
+    rule: &'static str,
+    message: String,
+    path: Option<PathBuf>,
+    /// The line and column, both starting at 1.
+    location: Option<(usize, usize)>,
+    in_text: bool,
+}
+
+impl Finding {
+    /// Attaches the finding to `path`.
+    pub fn at(&mut self, path: impl Into<PathBuf>) -> &mut Finding {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Leaves the finding out of the text output, for the commands whose
+    /// text already describes it.
+    pub fn hidden_in_text(&mut self) -> &mut Finding {
+        self.in_text = false;
+        self
+    }
+
+    fn json(&self) -> Value {
+        let mut json = serde_json::json!({
+            "level": self.level.as_str(),
+            "rule": self.rule,
+            "message": self.message,
+        });
+        if let Some(ref path) = self.path {
+            json["path"] = Value::from(path.to_string_lossy());
+        }
+        if let Some((line, column)) = self.location {
+            json["line"] = Value::from(line);
+            json["column"] = Value::from(column);
+        }
+        json
+    }
+
+    fn sarif(&self) -> Value {
+        let mut result = serde_json::json!({
+            "ruleId": self.rule,
+            "level": self.level.as_str(),
+            "message": { "text": self.message },
+        });
+        if let Some(ref path) = self.path {
+            let mut location = serde_json::json!({ "artifactLocation": artifact_location(path) });
+            if let Some((line, column)) = self.location {
+                location["region"] =
+                    serde_json::json!({ "startLine": line, "startColumn": column });
+            }
+            result["locations"] = serde_json::json!([{ "physicalLocation": location }]);
+        }
+        result
+    }
+}
+
+/// `path:line:column: level: message`, without what is unknown.
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref path) = self.path {
+            write!(f, "{}", path.display())?;
+            if let Some((line, column)) = self.location {
+                write!(f, ":{}:{}", line, column)?;
+            }
+            write!(f, ": ")?;
+        }
+        write!(f, "{}: {}", self.level.as_str(), self.message)
+    }
+}
+
+/// Paths in the working directory are relative to `%SRCROOT%`, the
+/// checkout CI systems resolve them from.
+fn artifact_location(path: &Path) -> Value {
+    let relative = env::current_dir()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok().map(Path::to_owned));
+    match relative {
+        Some(relative) => serde_json::json!({
+            "uri": relative.to_string_lossy().replace('\\', "/"),
+            "uriBaseId": "%SRCROOT%",
+        }),
+        None => serde_json::json!({
+            "uri": format!("file://{}", path.to_string_lossy().replace('\\', "/")),
+        }),
+    }
+}
+
+enum Entry {
+    Stdout(String),
+    Stderr(String),
+    Finding(usize),
+}
+
+/// The output of a command: the text it prints, its findings, and its
+/// result as JSON.
+pub struct Report {
+    command: &'static str,
+    entries: Vec<Entry>,
+    findings: Vec<Finding>,
+    findings_on_stdout: bool,
+    not_found: bool,
+    result: Value,
+}
+
+impl Report {
+    pub fn new(command: &'static str) -> Report {
+        Report {
+            command,
+            entries: Vec::new(),
+            findings: Vec::new(),
+            findings_on_stdout: false,
+            not_found: false,
+            result: Value::Null,
+        }
+    }
+
+    /// Prints the findings on stdout rather than stderr in text, for the
+    /// commands whose output they are.
+    pub fn findings_on_stdout(&mut self) {
+        self.findings_on_stdout = true;
+    }
+
+    /// Prints `text` on stdout, in text only.
+    pub fn print(&mut self, text: impl Into<String>) {
+        self.entries.push(Entry::Stdout(text.into()));
+    }
+
+    /// Prints `line` and a newline on stdout, in text only.
+    pub fn println(&mut self, line: impl fmt::Display) {
+        self.print(format!("{}\n", line));
+    }
+
+    /// Prints `line` and a newline on stderr, in text only.
+    pub fn eprintln(&mut self, line: impl fmt::Display) {
+        self.entries.push(Entry::Stderr(format!("{}\n", line)));
+    }
+
+    /// Sets the `result` of the JSON output.
+    pub fn set_result(&mut self, result: Value) {
+        self.result = result;
+    }
+
+    pub fn error(&mut self, rule: &'static str, message: impl fmt::Display) -> &mut Finding {
+        self.finding(Level::Error, rule, message)
+    }
+
+    pub fn warning(&mut self, rule: &'static str, message: impl fmt::Display) -> &mut Finding {
+        self.finding(Level::Warning, rule, message)
+    }
+
+    pub fn note(&mut self, rule: &'static str, message: impl fmt::Display) -> &mut Finding {
+        self.finding(Level::Note, rule, message)
+    }
+
+    fn finding(
+        &mut self,
+        level: Level,
+        rule: &'static str,
+        message: impl fmt::Display,
+    ) -> &mut Finding {
+        self.entries.push(Entry::Finding(self.findings.len()));
+        self.findings.push(Finding {
+            level,
+            rule,
+            message: message.to_string(),
+            path: None,
+            location: None,
+            in_text: true,
+        });
+        self.findings.last_mut().expect("a finding was pushed")
+    }
+
+    /// The value of `result`, or `None` after reporting its error as one of
+    /// the command line.
+    pub fn usage<T>(&mut self, result: Result<T, String>) -> Option<T> {
+        result.map_err(|error| self.error("usage", error)).ok()
+    }
+
+    /// The configuration path of `args`, or `None` after reporting why there
+    /// is none.
+    pub fn config_path(&mut self, args: &mut Args) -> Option<PathBuf> {
+        match args.config_path() {
+            Ok(path) => Some(path),
+            Err(ConfigPathError::Invalid(error)) => {
+                self.error("usage", error);
+                None
+            }
+            Err(ConfigPathError::NotFound(error)) => {
+                self.not_found = true;
+                self.error("config-not-found", error);
+                None
+            }
+        }
+    }
+
+    /// The configuration at `path`, or `None` after reporting why it cannot
+    /// be loaded.
+    pub fn load(&mut self, path: &Path) -> Option<GraphQLConfiguration> {
+        args::load(path)
+            .map_err(|error| self.load_error(path, &error))
+            .ok()
+    }
+
+    fn load_error(&mut self, path: &Path, error: &LoadError) {
+        let location = match *error {
+            LoadError::Json(ref error) if error.line() > 0 => Some((error.line(), error.column())),
+            LoadError::Yaml(ref error) => error
+                .location()
+                .map(|location| (location.line(), location.column())),
+            _ => None,
+        };
+        // The location is printed first rather than at the end of the message.
+        let mut message = error.to_string();
+        if let Some((line, column)) = location {
+            let suffix = format!(" at line {} column {}", line, column);
+            if message.ends_with(&suffix) {
+                let end = message.len() - suffix.len();
+                message.truncate(end);
+            }
+        }
+        let rule = match *error {
+            LoadError::Io(ref error) if error.kind() == ErrorKind::NotFound => {
+                self.not_found = true;
+                "config-not-found"
+            }
+            _ => "invalid-config",
+        };
+        let finding = self.error(rule, message);
+        finding.at(path).location = location;
+    }
+
+    fn code(&self, strict: bool) -> i32 {
+        let any = |level| self.findings.iter().any(|finding| finding.level == level);
+        if self.not_found {
+            NOT_FOUND
+        } else if any(Level::Error) {
+            ERRORS
+        } else if strict && any(Level::Warning) {
+            WARNINGS
+        } else {
+            OK
+        }
+    }
+
+    fn print_text(&self) {
+        for entry in &self.entries {
+            match *entry {
+                Entry::Stdout(ref text) => print!("{}", text),
+                Entry::Stderr(ref text) => eprint!("{}", text),
+                Entry::Finding(index) if !self.findings[index].in_text => {}
+                Entry::Finding(index) if self.findings_on_stdout => {
+                    println!("{}", self.findings[index])
+                }
+                Entry::Finding(index) => eprintln!("{}", self.findings[index]),
+            }
+        }
+    }
+
+    /// `{ "command", "exitCode", "findings": [{ "level", "rule", "message",
+    /// "path", "line", "column" }], "result" }`.
+    fn json(&self, code: i32) -> Value {
+        let findings: Vec<Value> = self.findings.iter().map(Finding::json).collect();
+        serde_json::json!({
+            "command": self.command,
+            "exitCode": code,
+            "findings": findings,
+            "result": self.result,
+        })
+    }
+
+    /// A SARIF 2.1.0 log of one run, with a result per finding.
+    fn sarif(&self) -> Value {
+        let results: Vec<Value> = self.findings.iter().map(Finding::sarif).collect();
+        serde_json::json!({
+            "version": "2.1.0",
+            "$schema": SARIF_SCHEMA,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "graphql-config",
+                        "version": env!("CARGO_PKG_VERSION"),
+                    }
+                },
+                "automationDetails": { "id": format!("graphql-config/{}/", self.command) },
+                "results": results,
+            }],
+        })
+    }
+}
@@ -0,0 +1,80 @@
+//! `graphql-config show`: prints a project as the tools see it, with the
+//! top-level settings merged in and the environment variables replaced.
+//!
+//! `--project` can be left out for configurations with one project, and
+//! configurations without any fail. The project is printed as JSON, or as
+//! YAML with `--format yaml`; with `--format json` it is the `result` of the
+//! report.
+
+use graphql_config::resolved::ResolvedGraphQLConfig;
+
+use args::Args;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let (output, yaml) = match args.option("format") {
+        Ok(Some(ref format)) if format == "yaml" => (Output::new(None, args.flag("strict")), true),
+        Ok(format) => (Output::new(format.as_deref(), args.flag("strict")), false),
+        Err(error) => (Err(error), false),
+    };
+    let output = match output {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("show");
+    show(args, yaml, &mut report);
+    output.finish(report)
+}
+
+fn show(mut args: Args, yaml: bool, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    let project = report.usage(args.option("project"))?;
+    report.usage(args.positional())?;
+    let config = ResolvedGraphQLConfig::new(report.load(&path)?, &path);
+
+    let names: Vec<&str> = config.projects().map(|(name, _)| name.as_str()).collect();
+    if names.is_empty() {
+        report.error("unknown-project", "the configuration defines no projects");
+        return None;
+    }
+    let project = match project {
+        Some(name) => config.project(&name),
+        None if names.len() == 1 => config.project(names[0]),
+        None => {
+            report.error(
+                "usage",
+                format!("`--project` is needed to choose among {}", names.join(", ")),
+            );
+            return None;
+        }
+    };
+    let project = match project {
+        Some(project) => project,
+        None => {
+            report.error(
+                "unknown-project",
+                format!(
+                    "there is no such project, the projects are {}",
+                    names.join(", ")
+                ),
+            );
+            return None;
+        }
+    };
+
+    let text = if yaml {
+        serde_yaml::to_string(&project).map_err(|error| error.to_string())
+    } else {
+        serde_json::to_string_pretty(&project)
+            .map(|json| json + "\n")
+            .map_err(|error| error.to_string())
+    };
+    match text {
+        Ok(text) => report.print(text),
+        Err(error) => {
+            report.error("io", error);
+        }
+    }
+    report.set_result(serde_json::to_value(&project).unwrap_or_default());
+    Some(())
+}
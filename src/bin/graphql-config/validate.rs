@@ -0,0 +1,66 @@
+//! `graphql-config validate`: checks the configuration, for CI.
+//!
+//! Reports a diagnostic per problem, printed as `path:line:column: error:
+//! message` where the problem has a location. Fields the normalizer would fix
+//! are reported as warnings, which fail the check with `--strict`.
+
+use std::path::Path;
+
+use graphql_config::normalize::Normalization;
+use graphql_config::resolved::ResolvedGraphQLConfig;
+use graphql_config::schema::SchemaPointer;
+
+use args::Args;
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("validate");
+    report.findings_on_stdout();
+    validate(args, &mut report);
+    output.finish(report)
+}
+
+fn validate(mut args: Args, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    report.usage(args.positional())?;
+    diagnose(&path, report);
+    Some(())
+}
+
+fn diagnose(path: &Path, report: &mut Report) -> Option<()> {
+    let config = report.load(path)?;
+
+    for change in config.clone().normalize().changes {
+        if let Normalization::FilledProjectName { .. } = change {
+            continue;
+        }
+        report.warning("normalization", change).at(path);
+    }
+
+    let resolved = ResolvedGraphQLConfig::new(config, path);
+    for project in resolved.project_handles() {
+        let schema_path = match project.config().schema_path {
+            Some(ref schema_path) => schema_path.to_string_lossy(),
+            None => continue,
+        };
+        if let SchemaPointer::File(schema) = SchemaPointer::classify(&schema_path, resolved.dir()) {
+            if !schema.is_file() {
+                report
+                    .error(
+                        "missing-schema",
+                        format!(
+                            "project `{}`: the schema `{}` does not exist",
+                            project.name(),
+                            schema.display()
+                        ),
+                    )
+                    .at(path);
+            }
+        }
+    }
+    Some(())
+}
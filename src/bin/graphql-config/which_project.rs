@@ -0,0 +1,100 @@
+//! `graphql-config which-project <file>`: prints the project the file
+//! belongs to, the one editors apply the schema of.
+//!
+//! When several projects include the file the first one wins, and the others
+//! are listed on stderr. When none does, the reason of every project is, and
+//! the command fails.
+
+use serde_json::Value;
+
+use graphql_config::index::FileIndex;
+use graphql_config::matcher::GlobSet;
+use graphql_config::resolved::ResolvedGraphQLConfig;
+
+use args::{self, Args};
+use report::{self, Output, Report};
+
+pub fn run(mut args: Args) -> i32 {
+    let output = match Output::take(&mut args) {
+        Ok(output) => output,
+        Err(error) => return report::usage_error(&error),
+    };
+    let mut report = Report::new("which-project");
+    which_project(args, &mut report);
+    output.finish(report)
+}
+
+fn which_project(mut args: Args, report: &mut Report) -> Option<()> {
+    let path = report.config_path(&mut args)?;
+    let file = match report.usage(args.positional())? {
+        ref files if files.len() == 1 => files[0].clone(),
+        _ => {
+            report.error("usage", "which-project takes one file");
+            return None;
+        }
+    };
+    let path = report.usage(args::absolute(&path))?;
+    let file = report.usage(args::absolute(file.as_ref()))?;
+    let config = ResolvedGraphQLConfig::new(report.load(&path)?, &path);
+
+    let relative = match file.strip_prefix(config.dir()) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => {
+            report.error(
+                "no-project",
+                format!(
+                    "`{}` is outside of `{}`, which no project includes",
+                    file.display(),
+                    config.dir().display()
+                ),
+            );
+            return None;
+        }
+    };
+    let project = FileIndex::new(&config)
+        .project_for_file(&file)
+        .map(str::to_owned);
+    let mut others = Vec::new();
+    for handle in config.project_handles() {
+        let reason = match project {
+            Some(ref project) if project == handle.name() => {
+                report.println(project);
+                continue;
+            }
+            Some(_) if !handle.include_file(&file) => continue,
+            Some(ref project) => format!("also includes it, but `{}` comes first", project),
+            None => reason(handle.matcher(), &relative),
+        };
+        report.eprintln(format!("project `{}` {}", handle.name(), reason));
+        others.push(serde_json::json!({ "project": handle.name(), "reason": reason }));
+    }
+    if project.is_none() {
+        report
+            .error("no-project", "no project includes the file")
+            .at(&file)
+            .hidden_in_text();
+    }
+    report.set_result(serde_json::json!({
+        "file": file,
+        "project": project.map_or(Value::Null, Value::from),
+        "others": others,
+    }));
+    Some(())
+}
+
+/// Why `matcher` does not match `path`.
+fn reason(matcher: &GlobSet, path: &str) -> String {
+    if let Some(exclude) = matcher
+        .excludes()
+        .iter()
+        .find(|exclude| exclude.matches(path))
+    {
+        return format!("excludes it with `{}`", exclude.as_str());
+    }
+    let includes: Vec<_> = matcher
+        .includes()
+        .iter()
+        .map(|include| format!("`{}`", include.as_str()))
+        .collect();
+    format!("includes {}, which do not match it", includes.join(", "))
+}
@@ -0,0 +1,207 @@
+//! Borrowed counterparts of the configuration types.
+//!
+//! Tools assembling configurations out of strings they already hold can build
+//! these without copying every name, path and pattern, serialize them exactly
+//! like the owned types, and convert them with `into_owned` when needed.
+//!
+//! They can also be deserialized straight out of the source text: names and
+//! schema paths then borrow from it unless they contain escapes, which saves
+//! allocations when reparsing on every change, as editors do. Project keys,
+//! patterns and extensions are validated or parsed, and always owned.
+
+use std::borrow::Cow;
+use std::path::Path;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+use crate::glob::GlobPattern;
+use crate::map::Map;
+use crate::projects::ProjectName;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// The borrowed counterpart of `GraphQLConfiguration`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::borrowed::*;
+/// # use std::borrow::Cow;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let schema = String::from("./schema.graphql");
+/// let include = "./src/*.graphql".parse()?;
+///
+/// let mut config = GraphQLConfigurationRef::default();
+/// config.root.schema_path = Some(Cow::Borrowed(Path::new(&schema)));
+/// config.root.includes = Some(vec![Cow::Borrowed(&include)]);
+///
+/// assert_eq!(
+///     serde_json::to_value(&config)?,
+///     serde_json::to_value(config.into_owned())?
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct GraphQLConfigurationRef<'a> {
+    /// Project-specific configurations by name.
+    #[serde(borrow)]
+    pub projects: Option<Map<Cow<'a, ProjectName>, GraphQLProjectConfigurationRef<'a>>>,
+    /// The top-level configuration.
+    #[serde(flatten, borrow)]
+    pub root: GraphQLProjectConfigurationRef<'a>,
+}
+
+/// The borrowed counterpart of `GraphQLProjectConfiguration`.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLProjectConfigurationRef<'a> {
+    /// The name of the project.
+    #[serde(borrow, default, deserialize_with = "borrow_str")]
+    pub name: Option<Cow<'a, str>>,
+    /// A file with schema IDL.
+    #[serde(borrow, default, deserialize_with = "borrow_path")]
+    pub schema_path: Option<Cow<'a, Path>>,
+    /// Patterns of the files belonging to the project.
+    pub includes: Option<Vec<Cow<'a, GlobPattern>>>,
+    /// Patterns of the files not belonging to the project.
+    pub excludes: Option<Vec<Cow<'a, GlobPattern>>>,
+    /// Tool-specific configuration.
+    pub extensions: Option<Cow<'a, Map<String, Value>>>,
+}
+
+/// A string borrowed from the input, if it has no escapes.
+#[derive(Deserialize)]
+struct Borrowed<'a>(#[serde(borrow)] Cow<'a, str>);
+
+fn borrow_str<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, str>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<Borrowed<'a>>::deserialize(deserializer)?.map(|string| string.0))
+}
+
+fn borrow_path<'de: 'a, 'a, D>(deserializer: D) -> Result<Option<Cow<'a, Path>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(borrow_str(deserializer)?.map(|path| match path {
+        Cow::Borrowed(path) => Cow::Borrowed(Path::new(path)),
+        Cow::Owned(path) => Cow::Owned(path.into()),
+    }))
+}
+
+impl<'a> GraphQLConfigurationRef<'a> {
+    /// Copies whatever is borrowed into an owned configuration.
+    pub fn into_owned(self) -> GraphQLConfiguration {
+        GraphQLConfiguration {
+            projects: self.projects.map(|projects| {
+                projects
+                    .into_iter()
+                    .map(|(name, project)| (name.into_owned(), project.into_owned()))
+                    .collect()
+            }),
+            root: self.root.into_owned(),
+        }
+    }
+}
+
+impl<'a> GraphQLProjectConfigurationRef<'a> {
+    /// Copies whatever is borrowed into an owned project configuration.
+    pub fn into_owned(self) -> GraphQLProjectConfiguration {
+        fn patterns(patterns: Option<Vec<Cow<'_, GlobPattern>>>) -> Option<Vec<GlobPattern>> {
+            patterns.map(|patterns| patterns.into_iter().map(Cow::into_owned).collect())
+        }
+
+        GraphQLProjectConfiguration {
+            name: self.name.map(Cow::into_owned),
+            schema_path: self.schema_path.map(Cow::into_owned),
+            includes: patterns(self.includes),
+            excludes: patterns(self.excludes),
+            extensions: self.extensions.map(Cow::into_owned),
+        }
+    }
+}
+
+impl<'a> From<&'a GraphQLConfiguration> for GraphQLConfigurationRef<'a> {
+    fn from(config: &'a GraphQLConfiguration) -> GraphQLConfigurationRef<'a> {
+        GraphQLConfigurationRef {
+            projects: config.projects.as_ref().map(|projects| {
+                projects
+                    .iter()
+                    .map(|(name, project)| (Cow::Borrowed(name), project.into()))
+                    .collect()
+            }),
+            root: (&config.root).into(),
+        }
+    }
+}
+
+impl<'a> From<&'a GraphQLProjectConfiguration> for GraphQLProjectConfigurationRef<'a> {
+    fn from(project: &'a GraphQLProjectConfiguration) -> GraphQLProjectConfigurationRef<'a> {
+        fn patterns(patterns: &Option<Vec<GlobPattern>>) -> Option<Vec<Cow<'_, GlobPattern>>> {
+            patterns
+                .as_ref()
+                .map(|patterns| patterns.iter().map(Cow::Borrowed).collect())
+        }
+
+        GraphQLProjectConfigurationRef {
+            name: project
+                .name
+                .as_ref()
+                .map(|name| Cow::Borrowed(name.as_str())),
+            schema_path: project
+                .schema_path
+                .as_ref()
+                .map(|schema_path| Cow::Borrowed(schema_path.as_path())),
+            includes: patterns(&project.includes),
+            excludes: patterns(&project.excludes),
+            extensions: project.extensions.as_ref().map(Cow::Borrowed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_through_borrowed_configurations() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "./schema.graphql",
+            "extensions": { "lastUpdatedAt": 1 },
+            "projects": {
+                "app": { "name": "App", "includes": ["./app/*.graphql"], "excludes": [] }
+            }
+        }))
+        .unwrap();
+
+        let borrowed = GraphQLConfigurationRef::from(&config);
+
+        assert_eq!(
+            ::serde_json::to_value(&borrowed).unwrap(),
+            ::serde_json::to_value(&config).unwrap()
+        );
+        assert_eq!(borrowed.into_owned(), config);
+    }
+
+    #[test]
+    fn it_borrows_from_the_input() {
+        let json = r#"{
+            "name": "Root",
+            "projects": { "app": { "schemaPath": "./app.graphql", "name": "A\u0070p" } }
+        }"#;
+        let config: GraphQLConfigurationRef = ::serde_json::from_str(json).unwrap();
+
+        assert!(matches!(config.root.name, Some(Cow::Borrowed("Root"))));
+        let app = &config.projects.as_ref().unwrap()[&Cow::Owned("app".parse().unwrap())];
+        assert!(matches!(app.schema_path, Some(Cow::Borrowed(_))));
+        assert!(matches!(app.name, Some(Cow::Owned(ref name)) if name == "App"));
+        assert_eq!(
+            config.into_owned(),
+            ::serde_json::from_str::<GraphQLConfiguration>(json).unwrap()
+        );
+    }
+}
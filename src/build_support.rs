@@ -0,0 +1,155 @@
+//! Helpers for build scripts generating code from GraphQL documents.
+//!
+//! Cargo runs a build script again whenever any file of the package changes,
+//! unless the script lists the files it reads with `cargo:rerun-if-changed`.
+//! `emit_cargo_directives` lists the files of the configuration of the
+//! package, so that code generators only run again when they have to.
+//!
+//! ```no_run
+//! # extern crate graphql_config;
+//! // build.rs
+//! fn main() {
+//!     let config = graphql_config::build_support::emit_cargo_directives()
+//!         .expect("the GraphQL configuration loads");
+//!     for project in config.project_handles() {
+//!         // Generate code from `project.document_files()`.
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeSet;
+use std::env;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::discover;
+use crate::env::Env;
+use crate::glob::{self, GlobPattern};
+use crate::load::LoadError;
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::schema::SchemaPointer;
+
+/// Loads the configuration of the package being built, the first one found
+/// from `CARGO_MANIFEST_DIR` upwards, and prints its `cargo_directives`.
+/// The configuration is returned for the build script to generate code from.
+pub fn emit_cargo_directives() -> Result<ResolvedGraphQLConfig, LoadError> {
+    let dir = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::current_dir()?,
+    };
+    let path = discover::find_config_file(&dir)?;
+    let config = ResolvedGraphQLConfig::from_path(path)?;
+
+    for directive in cargo_directives(&config)? {
+        println!("{}", directive);
+    }
+    Ok(config)
+}
+
+/// The directives of a build script reading `config`:
+///
+/// - `cargo:rerun-if-changed` for the configuration file, the schema files of
+///   every project and the documents they include, and for the directories
+///   their `includes` start with, so that new documents are noticed,
+/// - `cargo:rerun-if-env-changed` for the variables of `${env:NAME}`
+///   placeholders.
+///
+/// The configuration file comes first, followed by the other directives in
+/// order.
+pub fn cargo_directives(config: &ResolvedGraphQLConfig) -> io::Result<Vec<String>> {
+    // The variables are recorded as the projects are resolved.
+    let variables = Arc::new(Mutex::new(BTreeSet::new()));
+    let env = config.env().clone();
+    let recorded = Arc::clone(&variables);
+    let recording = ResolvedGraphQLConfig::new(config.raw().clone(), config.path()).with_env(
+        Env::from_fn(move |name| {
+            recorded
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .insert(name.to_owned());
+            env.var(name)
+        }),
+    );
+
+    let mut files = BTreeSet::new();
+    for project in recording.project_handles() {
+        if let Some(schema_path) = project.config().schema_path.as_ref() {
+            let schema_path = schema_path.to_string_lossy();
+            match SchemaPointer::classify(&schema_path, project.dir()) {
+                SchemaPointer::File(schema) => {
+                    files.insert(schema);
+                }
+                SchemaPointer::Glob(_) => {
+                    if let Ok(pattern) = GlobPattern::new(schema_path.as_ref()) {
+                        files.extend(glob::find_files(project.dir(), &pattern)?);
+                    }
+                }
+                SchemaPointer::Url(_) => {}
+            }
+        }
+
+        for include in &project.config().includes {
+            let dir = include.literal_dir();
+            if !dir.is_empty() {
+                files.insert(project.dir().join(dir));
+            }
+        }
+        files.extend(project.document_files()?);
+    }
+
+    let mut directives = vec![format!(
+        "cargo:rerun-if-changed={}",
+        config.path().display()
+    )];
+    directives.extend(
+        files
+            .iter()
+            .map(|file| format!("cargo:rerun-if-changed={}", file.display())),
+    );
+    let variables = variables.lock().unwrap_or_else(|error| error.into_inner());
+    directives.extend(
+        variables
+            .iter()
+            .map(|name| format!("cargo:rerun-if-env-changed={}", name)),
+    );
+    Ok(directives)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+    use std::fs;
+
+    #[test]
+    fn it_lists_what_the_build_reads() {
+        let dir = TestDir::new("it_lists_what_the_build_reads");
+        fs::create_dir_all(dir.join("src/graphql")).unwrap();
+        fs::write(dir.join("schema.graphql"), "").unwrap();
+        fs::write(dir.join("src/graphql/query.graphql"), "").unwrap();
+        fs::write(dir.join("src/main.rs"), "").unwrap();
+
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "schema.graphql",
+            "includes": ["src/graphql/**/*.graphql"],
+            "extensions": { "endpoints": { "default": "${env:GRAPHQL_ENDPOINT}" } }
+        }))
+        .unwrap();
+        let config = ResolvedGraphQLConfig::new(config, dir.join(".graphqlconfig"))
+            .with_env(Env::from_map(Vec::<(String, String)>::new()));
+
+        let rerun = |path: &str| format!("cargo:rerun-if-changed={}", dir.join(path).display());
+        assert_eq!(
+            cargo_directives(&config).unwrap(),
+            vec![
+                rerun(".graphqlconfig"),
+                rerun("schema.graphql"),
+                rerun("src/graphql"),
+                rerun("src/graphql/query.graphql"),
+                "cargo:rerun-if-env-changed=GRAPHQL_ENDPOINT".to_owned(),
+            ]
+        );
+    }
+}
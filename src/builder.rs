@@ -0,0 +1,406 @@
+//! Fluent construction of configurations.
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::extension::Extension;
+use crate::glob::{GlobPattern, PatternError};
+use crate::map::Map;
+use crate::projects::{ProjectName, ProjectNameError};
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// The error returned when a builder is given an invalid configuration.
+#[derive(Clone, PartialEq, Debug)]
+pub enum BuildError {
+    /// A project was given a name which is not a valid `ProjectName`.
+    InvalidProjectName(ProjectNameError),
+    /// Two projects were given the same name.
+    DuplicateProject(String),
+    /// A project was given an empty schema path. `None` stands for the
+    /// top-level configuration.
+    EmptySchemaPath(Option<String>),
+    /// A project was given an empty include or exclude pattern. `None` stands
+    /// for the top-level configuration.
+    EmptyPattern(Option<String>),
+    /// An include or exclude pattern is malformed.
+    InvalidPattern(PatternError),
+    /// A typed extension could not be serialized. Holds the name of the
+    /// extension and the serialization error message.
+    InvalidExtension(String, String),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn scope(project: &Option<String>) -> String {
+            match *project {
+                Some(ref project) => format!("project `{}`", project),
+                None => "the top-level configuration".to_owned(),
+            }
+        }
+
+        match *self {
+            BuildError::InvalidProjectName(ref error) => error.fmt(f),
+            BuildError::DuplicateProject(ref name) => {
+                write!(f, "project `{}` is defined more than once", name)
+            }
+            BuildError::EmptySchemaPath(ref project) => {
+                write!(f, "{} has an empty schema path", scope(project))
+            }
+            BuildError::EmptyPattern(ref project) => {
+                write!(f, "{} has an empty pattern", scope(project))
+            }
+            BuildError::InvalidPattern(ref error) => error.fmt(f),
+            BuildError::InvalidExtension(ref name, ref message) => {
+                write!(f, "extension `{}` cannot be serialized: {}", name, message)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {}
+
+/// Builds a `GraphQLConfiguration`, see `GraphQLConfiguration::builder`.
+#[derive(Debug, Default)]
+pub struct GraphQLConfigurationBuilder {
+    root: GraphQLProjectConfigurationBuilder,
+    projects: Vec<(String, GraphQLProjectConfigurationBuilder)>,
+}
+
+/// Builds a `GraphQLProjectConfiguration`, see
+/// `GraphQLProjectConfiguration::builder`. Also passed to the closure given
+/// to `GraphQLConfigurationBuilder::project`.
+#[derive(Debug, Default)]
+pub struct GraphQLProjectConfigurationBuilder {
+    config: GraphQLProjectConfiguration,
+    error: Option<BuildError>,
+}
+
+impl GraphQLConfiguration {
+    /// Starts building a configuration.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), builder::BuildError> {
+    /// let config = GraphQLConfiguration::builder()
+    ///     .schema_path("./schema.graphql")
+    ///     .include("./graphql/*.graphql")
+    ///     .project("amazingLibrary", |project| {
+    ///         project.schema_path("./amazingLibrary.schema.graphql")
+    ///     })
+    ///     .build()?;
+    ///
+    /// assert_eq!(config.root.includes, Some(vec!["./graphql/*.graphql".parse().unwrap()]));
+    /// assert_eq!(
+    ///     config.projects.unwrap()["amazingLibrary"].schema_path,
+    ///     Some("./amazingLibrary.schema.graphql".into())
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> GraphQLConfigurationBuilder {
+        GraphQLConfigurationBuilder::default()
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// Starts building a project configuration.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_derive;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::extension::Extension;
+    /// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    /// struct Codegen {
+    ///     output: String,
+    /// }
+    ///
+    /// impl Extension for Codegen {
+    ///     const NAME: &'static str = "codegen";
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let project = GraphQLProjectConfiguration::builder()
+    ///     .schema_path("./schema.graphql")
+    ///     .typed_extension(&Codegen { output: "./generated".to_owned() })
+    ///     .build()?;
+    ///
+    /// assert_eq!(project.extension::<Codegen>().unwrap()?.output, "./generated");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> GraphQLProjectConfigurationBuilder {
+        GraphQLProjectConfigurationBuilder::default()
+    }
+}
+
+impl GraphQLConfigurationBuilder {
+    /// Sets the `name` of the top-level configuration.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.root = self.root.name(name);
+        self
+    }
+
+    /// Sets the `schemaPath` of the top-level configuration.
+    pub fn schema_path(mut self, schema_path: impl Into<PathBuf>) -> Self {
+        self.root = self.root.schema_path(schema_path);
+        self
+    }
+
+    /// Adds a pattern to the `includes` of the top-level configuration.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.root = self.root.include(pattern);
+        self
+    }
+
+    /// Adds a pattern to the `excludes` of the top-level configuration.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.root = self.root.exclude(pattern);
+        self
+    }
+
+    /// Sets an extension of the top-level configuration.
+    pub fn extension(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.root = self.root.extension(name, value);
+        self
+    }
+
+    /// Sets a typed extension of the top-level configuration.
+    pub fn typed_extension<E: Extension>(mut self, extension: &E) -> Self {
+        self.root = self.root.typed_extension(extension);
+        self
+    }
+
+    /// Adds a project, configured by `configure`.
+    pub fn project<F>(mut self, name: impl Into<String>, configure: F) -> Self
+    where
+        F: FnOnce(GraphQLProjectConfigurationBuilder) -> GraphQLProjectConfigurationBuilder,
+    {
+        let project = configure(GraphQLProjectConfigurationBuilder::default());
+        self.projects.push((name.into(), project));
+        self
+    }
+
+    /// Validates the configuration and builds it.
+    pub fn build(self) -> Result<GraphQLConfiguration, BuildError> {
+        let root = self.root.validate(None)?;
+
+        let projects = if self.projects.is_empty() {
+            None
+        } else {
+            let mut projects = Map::new();
+            for (name, project) in self.projects {
+                let name = ProjectName::new(name).map_err(BuildError::InvalidProjectName)?;
+                let project = project.validate(Some(&name))?;
+                if projects.contains_key(&name) {
+                    return Err(BuildError::DuplicateProject(name.into_string()));
+                }
+                projects.insert(name, project);
+            }
+            Some(projects)
+        };
+
+        Ok(GraphQLConfiguration { projects, root })
+    }
+}
+
+impl GraphQLProjectConfigurationBuilder {
+    /// Sets `name`.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.config.name = Some(name.into());
+        self
+    }
+
+    /// Sets `schemaPath`.
+    pub fn schema_path(mut self, schema_path: impl Into<PathBuf>) -> Self {
+        self.config.schema_path = Some(schema_path.into());
+        self
+    }
+
+    /// Adds a pattern to `includes`. Malformed patterns are reported by
+    /// `build`.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        match GlobPattern::new(pattern) {
+            Ok(pattern) => self
+                .config
+                .includes
+                .get_or_insert_with(Vec::new)
+                .push(pattern),
+            Err(error) => self.fail(BuildError::InvalidPattern(error)),
+        }
+        self
+    }
+
+    /// Adds a pattern to `excludes`. Malformed patterns are reported by
+    /// `build`.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        match GlobPattern::new(pattern) {
+            Ok(pattern) => self
+                .config
+                .excludes
+                .get_or_insert_with(Vec::new)
+                .push(pattern),
+            Err(error) => self.fail(BuildError::InvalidPattern(error)),
+        }
+        self
+    }
+
+    /// Sets an extension, replacing any previous value.
+    pub fn extension(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.config
+            .extensions
+            .get_or_insert_with(Map::new)
+            .insert(name.into(), value);
+        self
+    }
+
+    /// Sets a typed extension, replacing any previous value. Serialization
+    /// errors are reported by `build`.
+    pub fn typed_extension<E: Extension>(mut self, extension: &E) -> Self {
+        match serde_json::to_value(extension) {
+            Ok(value) => self.extension(E::NAME, value),
+            Err(error) => {
+                self.fail(BuildError::InvalidExtension(
+                    E::NAME.to_owned(),
+                    error.to_string(),
+                ));
+                self
+            }
+        }
+    }
+
+    /// Records `error` unless an earlier one was recorded.
+    fn fail(&mut self, error: BuildError) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    /// Validates the project configuration and builds it.
+    pub fn build(self) -> Result<GraphQLProjectConfiguration, BuildError> {
+        self.validate(None)
+    }
+
+    fn validate(self, project: Option<&str>) -> Result<GraphQLProjectConfiguration, BuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let project = || project.map(str::to_owned);
+        let config = self.config;
+
+        if config
+            .schema_path
+            .as_ref()
+            .is_some_and(|path| path.as_os_str().is_empty())
+        {
+            return Err(BuildError::EmptySchemaPath(project()));
+        }
+
+        let patterns = config.includes.iter().chain(config.excludes.iter());
+        if patterns.flatten().any(|pattern| pattern.trim().is_empty()) {
+            return Err(BuildError::EmptyPattern(project()));
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_the_same_config_as_deserialization() {
+        let built = GraphQLConfiguration::builder()
+            .schema_path("./schema.graphql")
+            .extension("lastUpdatedAt", json!(1532367255884u64))
+            .project("app", |project| {
+                project
+                    .name("App")
+                    .include("./app/*.graphql")
+                    .exclude("./app/generated/*")
+            })
+            .project("lib", |project| project)
+            .build()
+            .unwrap();
+
+        let deserialized: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "./schema.graphql",
+            "extensions": { "lastUpdatedAt": 1532367255884u64 },
+            "projects": {
+                "app": {
+                    "name": "App",
+                    "includes": ["./app/*.graphql"],
+                    "excludes": ["./app/generated/*"]
+                },
+                "lib": {}
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(built, deserialized);
+    }
+
+    #[test]
+    fn it_validates_at_build_time() {
+        assert_eq!(
+            GraphQLConfiguration::builder()
+                .project("app", |p| p)
+                .project("app", |p| p)
+                .build(),
+            Err(BuildError::DuplicateProject("app".to_owned()))
+        );
+        assert_eq!(
+            GraphQLConfiguration::builder().project("", |p| p).build(),
+            Err(BuildError::InvalidProjectName(ProjectNameError::Empty))
+        );
+        assert_eq!(
+            GraphQLConfiguration::builder().project(" ", |p| p).build(),
+            Err(BuildError::InvalidProjectName(
+                ProjectNameError::InvalidCharacter(" ".to_owned(), ' ')
+            ))
+        );
+        assert_eq!(
+            GraphQLConfiguration::builder()
+                .project("app", |p| p.include(""))
+                .build(),
+            Err(BuildError::EmptyPattern(Some("app".to_owned())))
+        );
+        assert_eq!(
+            GraphQLConfiguration::builder().exclude("src/{a,b").build(),
+            Err(BuildError::InvalidPattern(
+                PatternError::UnclosedAlternation("src/{a,b".to_owned())
+            ))
+        );
+        assert_eq!(
+            GraphQLConfiguration::builder().schema_path("").build(),
+            Err(BuildError::EmptySchemaPath(None))
+        );
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Scalars(::std::collections::BTreeMap<(u8, u8), String>);
+
+    impl Extension for Scalars {
+        const NAME: &'static str = "scalars";
+    }
+
+    #[test]
+    fn it_reports_unserializable_typed_extensions() {
+        let scalars = Scalars(btreemap! { (0, 0) => "Date".to_owned() });
+
+        match GraphQLProjectConfiguration::builder()
+            .typed_extension(&scalars)
+            .build()
+        {
+            Err(BuildError::InvalidExtension(ref name, _)) if name == "scalars" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,571 @@
+//! Parsed schemas and documents for editors, enabled by the `graphql-parser`
+//! feature.
+//!
+//! Completion and hover need the schema of a project and the fragments
+//! defined across its documents on every keystroke. A `GraphQLCache` parses
+//! them on first use and keeps them until they are invalidated, like the
+//! `GraphQLCache` of the JavaScript `graphql-language-service`. Editors
+//! invalidate what changed, for instance from the `WorkspaceChange`s of an
+//! `lsp::Workspace`, and register hooks to learn of invalidations made
+//! elsewhere.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use graphql_parser::query::{self, Definition, FragmentDefinition};
+use graphql_parser::schema;
+
+use crate::glob::{self, GlobPattern};
+use crate::introspection;
+use crate::observer::{CacheKind, Observer};
+use crate::schema::SchemaPointer;
+use crate::schema_cache::SchemaCache;
+use crate::shared::SharedConfig;
+use crate::GraphQLProjectConfiguration;
+
+/// A parsed schema.
+pub type SchemaDocument = schema::Document<'static, String>;
+
+/// A parsed executable document.
+pub type QueryDocument = query::Document<'static, String>;
+
+/// A fragment defined in a document of a project.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FragmentInfo {
+    /// The document the fragment is defined in.
+    pub path: PathBuf,
+    /// The definition.
+    pub definition: FragmentDefinition<'static, String>,
+}
+
+/// What was dropped from a `GraphQLCache`, as passed to its hooks.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Invalidation {
+    /// The schema of the project.
+    Schema(String),
+    /// The document at the path, replaced with the editor's contents if it
+    /// was invalidated by `update_document`.
+    Document(PathBuf),
+    /// Everything, by `clear`.
+    All,
+}
+
+/// The error returned when a schema or document cannot be loaded.
+#[derive(Debug)]
+pub enum CacheError {
+    /// The configuration has no such project.
+    UnknownProject(String),
+    /// The project has no `schemaPath`. The name is `None` when the project
+    /// was loaded on its own by `GraphQLProjectConfiguration::load_schema`
+    /// and has no `name`, since its key is not known then.
+    NoSchema(Option<String>),
+    /// The schema is served by the endpoint with this URL, and is not in the
+    /// `SchemaCache` of the cache.
+    Uncached(String),
+    /// A file could not be read.
+    Io(PathBuf, io::Error),
+    /// A schema or document is not valid GraphQL, or a JSON schema is not an
+    /// introspection result.
+    Parse(PathBuf, String),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CacheError::UnknownProject(ref project) => {
+                write!(f, "there is no project `{}`", project)
+            }
+            CacheError::NoSchema(Some(ref project)) => {
+                write!(f, "project `{}` has no schemaPath", project)
+            }
+            CacheError::NoSchema(None) => write!(f, "the project has no schemaPath"),
+            CacheError::Uncached(ref url) => write!(f, "the schema of `{}` is not cached", url),
+            CacheError::Io(ref path, ref error) => write!(f, "{}: {}", path.display(), error),
+            CacheError::Parse(ref path, ref error) => write!(f, "{}: {}", path.display(), error),
+        }
+    }
+}
+
+impl Error for CacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            CacheError::Io(_, ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+type Hook = Box<dyn Fn(&Invalidation) + Send + Sync>;
+
+/// The schemas and documents of the projects of a configuration, parsed on
+/// first use.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::cache::{GraphQLCache, Invalidation};
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = Path::new("/repo/.graphqlconfig");
+/// let cache = GraphQLCache::new(ResolvedGraphQLConfig::from_path(path)?);
+///
+/// cache.on_invalidate(|invalidation| {
+///     if let Invalidation::Schema(project) = invalidation {
+///         println!("revalidating the documents of {}", project);
+///     }
+/// });
+/// let schema = cache.schema("default")?;
+/// let fragments = cache.fragments("default")?;
+/// println!("{} definitions, {} fragments", schema.definitions.len(), fragments.len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct GraphQLCache {
+    config: SharedConfig,
+    schema_cache: Option<Box<dyn SchemaCache + Send + Sync>>,
+    observer: Option<Arc<dyn Observer>>,
+    schemas: Mutex<HashMap<String, Arc<SchemaDocument>>>,
+    documents: Mutex<HashMap<PathBuf, Arc<QueryDocument>>>,
+    hooks: Mutex<Vec<Hook>>,
+}
+
+impl GraphQLCache {
+    /// An empty cache of the projects of `config`.
+    pub fn new(config: impl Into<SharedConfig>) -> GraphQLCache {
+        GraphQLCache {
+            config: config.into(),
+            schema_cache: None,
+            observer: None,
+            schemas: Mutex::new(HashMap::new()),
+            documents: Mutex::new(HashMap::new()),
+            hooks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Reads the schemas of endpoints from `schema_cache`, by
+    /// `SchemaPointer::cache_key`, rather than failing with
+    /// `CacheError::Uncached`.
+    pub fn with_schema_cache(
+        mut self,
+        schema_cache: impl SchemaCache + Send + Sync + 'static,
+    ) -> GraphQLCache {
+        self.schema_cache = Some(Box::new(schema_cache));
+        self
+    }
+
+    /// Reports the lookups of schemas and documents to `observer`.
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> GraphQLCache {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// The configuration of the projects.
+    pub fn config(&self) -> &SharedConfig {
+        &self.config
+    }
+
+    /// Calls `hook` with every invalidation from now on. Hooks are called
+    /// while the cache is locked against other hooks being registered.
+    pub fn on_invalidate(&self, hook: impl Fn(&Invalidation) + Send + Sync + 'static) {
+        lock(&self.hooks).push(Box::new(hook));
+    }
+
+    /// The schema of `project`, from its `schemaPath`: a file of SDL or of
+    /// an introspection result if it ends in `.json`, the files a glob
+    /// matches, or an endpoint whose schema is in the `SchemaCache`.
+    pub fn schema(&self, project: &str) -> Result<Arc<SchemaDocument>, CacheError> {
+        let cached = lock(&self.schemas).get(project).cloned();
+        self.observe(CacheKind::Schema, project, cached.is_some());
+        if let Some(schema) = cached {
+            return Ok(schema);
+        }
+
+        let schema_path = self
+            .config
+            .project(project)
+            .ok_or_else(|| CacheError::UnknownProject(project.to_owned()))?
+            .schema_path
+            .ok_or_else(|| CacheError::NoSchema(Some(project.to_owned())))?;
+        let pointer = SchemaPointer::classify(&schema_path.to_string_lossy(), self.config.dir());
+        let schema = load_schema(&pointer, self.config.dir(), self.schema_cache.as_deref())?;
+
+        let schema = Arc::new(schema);
+        lock(&self.schemas).insert(project.to_owned(), Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    /// The document at `path`, as last updated or read from disk.
+    pub fn document(&self, path: impl AsRef<Path>) -> Result<Arc<QueryDocument>, CacheError> {
+        let path = self.config.dir().join(path);
+        let cached = lock(&self.documents).get(&path).cloned();
+        self.observe(
+            CacheKind::Document,
+            &path.to_string_lossy(),
+            cached.is_some(),
+        );
+        if let Some(document) = cached {
+            return Ok(document);
+        }
+
+        let contents =
+            fs::read_to_string(&path).map_err(|error| CacheError::Io(path.clone(), error))?;
+        let document = Arc::new(parse_document(&path, &contents)?);
+        lock(&self.documents).insert(path, Arc::clone(&document));
+        Ok(document)
+    }
+
+    /// Replaces the document at `path` with `contents`, the unsaved text of
+    /// an editor. The previous document is kept if `contents` do not parse,
+    /// as they often do not while being typed.
+    pub fn update_document(
+        &self,
+        path: impl AsRef<Path>,
+        contents: &str,
+    ) -> Result<Arc<QueryDocument>, CacheError> {
+        let path = self.config.dir().join(path);
+        let document = Arc::new(parse_document(&path, contents)?);
+        lock(&self.documents).insert(path.clone(), Arc::clone(&document));
+        self.notify(&Invalidation::Document(path));
+        Ok(document)
+    }
+
+    /// The fragments defined in the `.graphql` and `.gql` documents of
+    /// `project`, ordered by name. Documents which do not parse are skipped.
+    pub fn fragments(&self, project: &str) -> Result<Vec<FragmentInfo>, CacheError> {
+        let handle = self
+            .config
+            .project_handle(project)
+            .ok_or_else(|| CacheError::UnknownProject(project.to_owned()))?;
+        let files = handle
+            .document_files()
+            .map_err(|error| CacheError::Io(handle.dir().to_owned(), error))?;
+
+        let mut fragments = Vec::new();
+        for file in files {
+            let is_graphql = file
+                .extension()
+                .is_some_and(|extension| extension == "graphql" || extension == "gql");
+            if !is_graphql {
+                continue;
+            }
+            let document = match self.document(&file) {
+                Ok(document) => document,
+                Err(CacheError::Parse(..)) => continue,
+                Err(error) => return Err(error),
+            };
+            for definition in &document.definitions {
+                if let Definition::Fragment(ref definition) = *definition {
+                    fragments.push(FragmentInfo {
+                        path: file.clone(),
+                        definition: definition.clone(),
+                    });
+                }
+            }
+        }
+        fragments.sort_by(|a, b| a.definition.name.cmp(&b.definition.name));
+        Ok(fragments)
+    }
+
+    /// Drops the schema of `project`, to be read again on next use.
+    pub fn invalidate_schema(&self, project: &str) {
+        lock(&self.schemas).remove(project);
+        self.notify(&Invalidation::Schema(project.to_owned()));
+    }
+
+    /// Drops the document at `path`, to be read again on next use.
+    pub fn invalidate_document(&self, path: impl AsRef<Path>) {
+        let path = self.config.dir().join(path);
+        lock(&self.documents).remove(&path);
+        self.notify(&Invalidation::Document(path));
+    }
+
+    /// Drops every schema and document.
+    pub fn clear(&self) {
+        lock(&self.schemas).clear();
+        lock(&self.documents).clear();
+        self.notify(&Invalidation::All);
+    }
+
+    fn observe(&self, kind: CacheKind, key: &str, hit: bool) {
+        if let Some(ref observer) = self.observer {
+            observer.cache_lookup(kind, key, hit);
+        }
+    }
+
+    fn notify(&self, invalidation: &Invalidation) {
+        for hook in lock(&self.hooks).iter() {
+            hook(invalidation);
+        }
+    }
+}
+
+impl fmt::Debug for GraphQLCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GraphQLCache")
+            .field("config", &self.config)
+            .field("schemas", &lock(&self.schemas).len())
+            .field("documents", &lock(&self.documents).len())
+            .finish()
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// Reads and parses the schema of the project, without caching it. The
+    /// `schemaPath` is resolved against `config_dir`, the directory of the
+    /// configuration file, and read like `GraphQLCache::schema` reads it,
+    /// except that endpoints are never cached and fail with `Uncached`.
+    ///
+    /// ```no_run
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let config = GraphQLConfiguration::from_path("/repo/.graphqlconfig")?;
+    ///
+    /// let schema = config.root.load_schema("/repo")?;
+    /// println!("{} definitions", schema.definitions.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_schema(&self, config_dir: impl AsRef<Path>) -> Result<SchemaDocument, CacheError> {
+        let config_dir = config_dir.as_ref();
+        let schema_path = self
+            .schema_path
+            .as_ref()
+            .ok_or_else(|| CacheError::NoSchema(self.name.clone()))?;
+        let pointer = SchemaPointer::classify(&schema_path.to_string_lossy(), config_dir);
+        load_schema(&pointer, config_dir, None)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> ::std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Reads and parses the schema of `pointer`, finding the files of globs
+/// under `dir`.
+fn load_schema(
+    pointer: &SchemaPointer,
+    dir: &Path,
+    schema_cache: Option<&(dyn SchemaCache + Send + Sync)>,
+) -> Result<SchemaDocument, CacheError> {
+    let (path, sdl) = read_schema(pointer, dir, schema_cache)?;
+    schema::parse_schema::<String>(&sdl)
+        .map(schema::Document::into_static)
+        .map_err(|error| CacheError::Parse(path, error.to_string()))
+}
+
+/// The SDL of `pointer`, and the path it is reported under.
+fn read_schema(
+    pointer: &SchemaPointer,
+    dir: &Path,
+    schema_cache: Option<&(dyn SchemaCache + Send + Sync)>,
+) -> Result<(PathBuf, String), CacheError> {
+    match *pointer {
+        SchemaPointer::File(ref path) => {
+            let contents =
+                fs::read_to_string(path).map_err(|error| CacheError::Io(path.clone(), error))?;
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let sdl = serde_json::from_str(&contents)
+                    .map_err(|error| error.to_string())
+                    .and_then(|result| {
+                        introspection::to_sdl(&result).map_err(|error| error.to_string())
+                    })
+                    .map_err(|error| CacheError::Parse(path.clone(), error))?;
+                Ok((path.clone(), sdl))
+            } else {
+                Ok((path.clone(), contents))
+            }
+        }
+        SchemaPointer::Glob(ref pattern) => {
+            let relative = Path::new(pattern)
+                .strip_prefix(dir)
+                .unwrap_or(Path::new(pattern));
+            let pattern = glob::to_slash(relative)
+                .and_then(|relative| GlobPattern::new(relative).ok())
+                .ok_or_else(|| {
+                    CacheError::Parse(pattern.into(), "invalid schema pattern".to_owned())
+                })?;
+            let files = glob::find_files(dir, &pattern)
+                .map_err(|error| CacheError::Io(dir.to_owned(), error))?;
+            let mut sdl = String::new();
+            for file in &files {
+                let contents = fs::read_to_string(file)
+                    .map_err(|error| CacheError::Io(file.clone(), error))?;
+                sdl.push_str(&contents);
+                sdl.push('\n');
+            }
+            Ok((pattern.as_str().into(), sdl))
+        }
+        SchemaPointer::Url(ref url) => {
+            let cached = match schema_cache {
+                Some(schema_cache) => schema_cache
+                    .get(&pointer.cache_key())
+                    .map_err(|error| CacheError::Io(url.into(), error))?,
+                None => None,
+            };
+            cached
+                .map(|sdl| (url.into(), sdl))
+                .ok_or_else(|| CacheError::Uncached(url.clone()))
+        }
+    }
+}
+
+fn parse_document(path: &Path, contents: &str) -> Result<QueryDocument, CacheError> {
+    query::parse_query::<String>(contents)
+        .map(query::Document::into_static)
+        .map_err(|error| CacheError::Parse(path.to_owned(), error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolved::ResolvedGraphQLConfig;
+    use crate::schema_cache::MemoryCache;
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+
+    /// A cache of `config`, written with `files` to the directory of the test
+    /// `name`, which lives as long as the `TestDir` returned.
+    fn cache(
+        name: &str,
+        config: ::serde_json::Value,
+        files: &[(&str, &str)],
+    ) -> (TestDir, GraphQLCache) {
+        let dir = TestDir::new(name);
+        for &(path, contents) in files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        let config: GraphQLConfiguration = ::serde_json::from_value(config).unwrap();
+        let cache = GraphQLCache::new(ResolvedGraphQLConfig::new(
+            config,
+            dir.join(".graphqlconfig"),
+        ));
+        (dir, cache)
+    }
+
+    #[test]
+    fn it_caches_until_invalidated() {
+        let (_dir, cache) = cache(
+            "it_caches_until_invalidated",
+            json!({ "schemaPath": "schema/*.graphql", "includes": ["src/**"] }),
+            &[
+                ("schema/a.graphql", "type Query { user: User }"),
+                ("schema/b.graphql", "type User { id: ID }"),
+                ("src/user.graphql", "fragment User on User { id }"),
+                ("src/broken.graphql", "fragment {"),
+                ("src/index.js", "fragment"),
+            ],
+        );
+        let invalidations = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&invalidations);
+        cache.on_invalidate(move |invalidation| lock(&seen).push(invalidation.clone()));
+
+        let schema = cache.schema("default").unwrap();
+        assert_eq!(schema.definitions.len(), 2);
+        assert!(Arc::ptr_eq(&schema, &cache.schema("default").unwrap()));
+        cache.invalidate_schema("default");
+        assert!(!Arc::ptr_eq(&schema, &cache.schema("default").unwrap()));
+
+        let fragments = cache.fragments("default").unwrap();
+        assert_eq!(fragments.len(), 1);
+        assert_eq!(fragments[0].definition.name, "User");
+
+        let user = cache.config().dir().join("src/user.graphql");
+        cache
+            .update_document(&user, "fragment Viewer on User { id }")
+            .unwrap();
+        assert!(cache.update_document(&user, "fragment {").is_err());
+        assert_eq!(
+            cache.fragments("default").unwrap()[0].definition.name,
+            "Viewer"
+        );
+
+        assert_eq!(
+            *lock(&invalidations),
+            vec![
+                Invalidation::Schema("default".to_owned()),
+                Invalidation::Document(user)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_loads_the_schema_of_a_project() {
+        let (_dir, cache) = cache(
+            "it_loads_the_schema_of_a_project",
+            json!({
+                "schemaPath": "schema.graphql",
+                "projects": {
+                    "app": { "schemaPath": "app/*.graphql" },
+                    "lib": { "name": "Lib" },
+                    "api": { "schemaPath": "http://localhost:4000/graphql" }
+                }
+            }),
+            &[
+                ("schema.graphql", "type Query { id: ID }"),
+                ("app/a.graphql", "type Query { user: User }"),
+                ("app/b.graphql", "type User { id: ID }"),
+            ],
+        );
+        let config = cache.config().raw();
+        let dir = cache.config().dir();
+
+        assert_eq!(config.root.load_schema(dir).unwrap().definitions.len(), 1);
+        let app = config.project("app").unwrap().load_schema(dir).unwrap();
+        assert_eq!(app.definitions.len(), 2);
+        match config.project("lib").unwrap().load_schema(dir) {
+            Err(CacheError::NoSchema(Some(ref name))) if name == "Lib" => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        let unnamed = GraphQLProjectConfiguration::default();
+        match unnamed.load_schema(dir) {
+            Err(error @ CacheError::NoSchema(None)) => {
+                assert_eq!(error.to_string(), "the project has no schemaPath");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        match config.project("api").unwrap().load_schema(dir) {
+            Err(CacheError::Uncached(_)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match config.root.load_schema(dir.join("missing")) {
+            Err(CacheError::Io(..)) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_reads_endpoint_schemas_from_the_schema_cache() {
+        let url = "http://localhost:4000/graphql";
+        let (_dir, cache) = cache(
+            "it_reads_endpoint_schemas_from_the_schema_cache",
+            json!({ "schemaPath": url }),
+            &[],
+        );
+        match cache.schema("default") {
+            Err(CacheError::Uncached(ref uncached)) => assert_eq!(uncached, url),
+            ref other => panic!("unexpected {:?}", other),
+        }
+
+        let schemas = MemoryCache::new();
+        schemas
+            .put(
+                &SchemaPointer::Url(url.to_owned()).cache_key(),
+                "type Query { a: Int }",
+            )
+            .unwrap();
+        let cache = cache.with_schema_cache(schemas);
+        assert_eq!(cache.schema("default").unwrap().definitions.len(), 1);
+    }
+}
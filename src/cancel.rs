@@ -0,0 +1,72 @@
+//! Stopping long-running operations from another thread.
+//!
+//! Watchers stop when they are dropped, but services shutting down may not
+//! own them, or may want to stop a batch of fetches midway. A `CancelToken`
+//! is shared by cloning it; once any clone is cancelled, the watchers and
+//! fetches given one stop at their next check, and those waiting for their
+//! next round, such as a `SchemaPoller`, are woken.
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # use graphql_config::cancel::CancelToken;
+//! let token = CancelToken::new();
+//! let shutdown = token.clone();
+//!
+//! shutdown.cancel();
+//! assert!(token.is_cancelled());
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A flag shared by its clones, raised once by `cancel`.
+#[derive(Clone, Default, Debug)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default, Debug)]
+struct Inner {
+    cancelled: AtomicBool,
+    waiters: Mutex<Vec<Sender<()>>>,
+}
+
+impl CancelToken {
+    /// A token which is not cancelled.
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Cancels the operations given this token or one of its clones.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        let waiters = self.waiters().drain(..).collect::<Vec<_>>();
+        for waiter in waiters {
+            let _ = waiter.send(());
+        }
+    }
+
+    /// Whether `cancel` was called on this token or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Sends to `waiter` once the token is cancelled, right away if it
+    /// already is, so that a thread waiting on its receiver wakes.
+    pub(crate) fn notify(&self, waiter: Sender<()>) {
+        let mut waiters = self.waiters();
+        if self.is_cancelled() {
+            let _ = waiter.send(());
+        } else {
+            waiters.push(waiter);
+        }
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, Vec<Sender<()>>> {
+        self.inner
+            .waiters
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
@@ -0,0 +1,206 @@
+//! A manifest of what code generators need to know about each project.
+//!
+//! Code generators in other languages should not have to reimplement the
+//! resolution rules of graphql-config. `ResolvedGraphQLConfig::emit_codegen_manifest`
+//! resolves them once, into a `CodegenManifest` serializing to JSON such as:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "configPath": "/repo/.graphqlconfig",
+//!   "projects": {
+//!     "app": {
+//!       "schema": { "kind": "file", "path": "/repo/schema.graphql" },
+//!       "endpoints": { "dev": "http://localhost:4000/graphql" },
+//!       "documents": ["/repo/src/query.graphql"],
+//!       "extensions": { "codegen": { "output": "./generated" } }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Extensions are those of the resolved project, with placeholders
+//! replaced, except `endpoints`, whose URLs are listed on their own.
+
+use std::io;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::endpoints::GraphQLConfigEndpointsData;
+use crate::extension::Extension;
+use crate::glob::{self, GlobPattern};
+use crate::map::{self, Map};
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::schema::SchemaPointer;
+
+/// The version of the manifest format, increased on incompatible changes.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// The projects of a configuration, resolved for code generators.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CodegenManifest {
+    /// `MANIFEST_VERSION`.
+    pub version: u32,
+    /// The configuration file.
+    pub config_path: PathBuf,
+    /// The projects, by name.
+    pub projects: Map<String, ProjectManifest>,
+}
+
+/// A project of a `CodegenManifest`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ProjectManifest {
+    /// Where the schema is loaded from, if the project has a `schemaPath`.
+    pub schema: Option<SchemaManifest>,
+    /// The URLs of the `endpoints` extension, by endpoint name.
+    pub endpoints: Map<String, String>,
+    /// The document files of the project, sorted.
+    pub documents: Vec<PathBuf>,
+    /// The other extensions of the project.
+    pub extensions: Map<String, Value>,
+}
+
+/// The `schemaPath` of a project, see `SchemaPointer`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SchemaManifest {
+    /// A file of SDL or of an introspection result.
+    File {
+        /// The file.
+        path: PathBuf,
+    },
+    /// A glob, with the files it matches.
+    Glob {
+        /// The glob as written.
+        pattern: String,
+        /// The files it matches, sorted.
+        files: Vec<PathBuf>,
+    },
+    /// An endpoint to introspect.
+    Url {
+        /// The URL of the endpoint.
+        url: String,
+    },
+}
+
+impl ResolvedGraphQLConfig {
+    /// Resolves the schema, endpoints, documents and extensions of every
+    /// project, listing the files they refer to.
+    pub fn emit_codegen_manifest(&self) -> io::Result<CodegenManifest> {
+        let mut projects = Map::new();
+        for project in self.project_handles() {
+            let config = project.config();
+            let schema = match config.schema_path.as_ref() {
+                Some(schema_path) => {
+                    let schema_path = schema_path.to_string_lossy();
+                    Some(match SchemaPointer::classify(&schema_path, project.dir()) {
+                        SchemaPointer::File(path) => SchemaManifest::File { path },
+                        SchemaPointer::Glob(pattern) => {
+                            let files = match GlobPattern::new(schema_path.as_ref()) {
+                                Ok(glob) => glob::find_files(project.dir(), &glob)?,
+                                Err(_) => Vec::new(),
+                            };
+                            SchemaManifest::Glob { pattern, files }
+                        }
+                        SchemaPointer::Url(url) => SchemaManifest::Url {
+                            url: self.host_map().remap(&url),
+                        },
+                    })
+                }
+                None => None,
+            };
+
+            let mut extensions = config.extensions.clone();
+            map::remove(&mut extensions, GraphQLConfigEndpointsData::NAME);
+            let endpoints = match config.endpoints() {
+                Some(Ok(endpoints)) => endpoints
+                    .endpoints
+                    .into_iter()
+                    .map(|(endpoint, config)| (endpoint, self.host_map().remap(&config.url)))
+                    .collect(),
+                _ => Map::new(),
+            };
+
+            projects.insert(
+                project.name().to_owned(),
+                ProjectManifest {
+                    schema,
+                    endpoints,
+                    documents: project.document_files()?,
+                    extensions,
+                },
+            );
+        }
+
+        Ok(CodegenManifest {
+            version: MANIFEST_VERSION,
+            config_path: self.path().to_owned(),
+            projects,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::Env;
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+    use std::fs;
+
+    #[test]
+    fn it_resolves_what_code_generators_read() {
+        let dir = TestDir::new("it_resolves_what_code_generators_read");
+        fs::create_dir_all(dir.join("api/schema")).unwrap();
+        fs::create_dir_all(dir.join("app")).unwrap();
+        fs::write(dir.join("api/schema/a.graphql"), "").unwrap();
+        fs::write(dir.join("app/query.graphql"), "").unwrap();
+
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "api": { "schemaPath": "api/schema/*.graphql", "includes": ["api/**/*.ts"] },
+                "app": {
+                    "schemaPath": "${env:SCHEMA}",
+                    "includes": ["app/**"],
+                    "extensions": {
+                        "endpoints": { "dev": { "url": "http://localhost:4000/graphql" } },
+                        "codegen": { "output": "./app/generated" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let config = ResolvedGraphQLConfig::new(config, dir.join(".graphqlconfig")).with_env(
+            Env::from_map(vec![("SCHEMA", "https://example.com/graphql")]),
+        );
+
+        let manifest = ::serde_json::to_value(config.emit_codegen_manifest().unwrap()).unwrap();
+        assert_eq!(
+            manifest,
+            json!({
+                "version": 1,
+                "configPath": dir.join(".graphqlconfig"),
+                "projects": {
+                    "api": {
+                        "schema": {
+                            "kind": "glob",
+                            "pattern": dir.join("api/schema/*.graphql"),
+                            "files": [dir.join("api/schema/a.graphql")]
+                        },
+                        "endpoints": {},
+                        "documents": [],
+                        "extensions": {}
+                    },
+                    "app": {
+                        "schema": { "kind": "url", "url": "https://example.com/graphql" },
+                        "endpoints": { "dev": "http://localhost:4000/graphql" },
+                        "documents": [dir.join("app/query.graphql")],
+                        "extensions": { "codegen": { "output": "./app/generated" } }
+                    }
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! A description of every configuration key, for editor plugins.
+//!
+//! The JSON Schema of `json_schema` validates configuration files; editors
+//! offering completion and hover want a flatter view: every key with its
+//! path, type, default and documentation. `KEYS` lists them, including the
+//! keys of version 3 files and the extensions this crate reads itself, and
+//! `completion_data` serializes them:
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # use graphql_config::completion::{self, KeyKind};
+//! # fn main() {
+//! let schema_path = completion::key("schemaPath").unwrap();
+//! assert_eq!(schema_path.kind, KeyKind::Path);
+//!
+//! let data = completion::completion_data();
+//! assert_eq!(data["keys"][0]["path"], "name");
+//! # }
+//! ```
+//!
+//! Paths are `.`-separated, with `*` standing for a key chosen by the user,
+//! such as the name of a project or of an endpoint.
+
+use serde_json::Value;
+
+/// The version of the format of `completion_data`, increased on
+/// incompatible changes.
+pub const COMPLETION_DATA_VERSION: u32 = 1;
+
+/// A configuration key.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigKey {
+    /// The path of the key from the top-level object of the file.
+    pub path: &'static str,
+    /// The type of its values.
+    pub kind: KeyKind,
+    /// Where the key may be written.
+    pub scope: KeyScope,
+    /// The JSON of its value when it is absent, if there is a fixed one.
+    pub default: Option<&'static str>,
+    /// Its documentation, for hovers.
+    pub description: &'static str,
+}
+
+/// The type of the values of a key.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyKind {
+    /// A string.
+    String,
+    /// A path relative to the configuration file, or a URL.
+    Path,
+    /// A list of glob patterns.
+    Patterns,
+    /// A list of strings.
+    Strings,
+    /// A version 3 pointer: a path, glob or URL, a list of them, or an
+    /// object of them with their options.
+    Pointers,
+    /// An object of arbitrary keys, described by the keys under `*`.
+    Map,
+    /// An object with the keys described under its path.
+    Object,
+    /// A string or an object with the keys described under its path.
+    StringOrObject,
+}
+
+/// Where a key may be written.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyScope {
+    /// In the top-level object only.
+    Root,
+    /// In the top-level object and in projects, where the project overrides
+    /// the top-level value.
+    RootAndProjects,
+}
+
+/// Every key, top-level keys first, in the order of the types they
+/// deserialize into, version 2 ones before version 3 ones.
+pub const KEYS: &[ConfigKey] = &[
+    ConfigKey {
+        path: "name",
+        kind: KeyKind::String,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "The name of the project, defaulting to its key in `projects`.",
+    },
+    ConfigKey {
+        path: "schemaPath",
+        kind: KeyKind::Path,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "A file with schema IDL, a glob of such files, or the URL of an endpoint.",
+    },
+    ConfigKey {
+        path: "includes",
+        kind: KeyKind::Patterns,
+        scope: KeyScope::RootAndProjects,
+        default: Some("[]"),
+        description: "Glob patterns of the files belonging to the project. Empty means all files.",
+    },
+    ConfigKey {
+        path: "excludes",
+        kind: KeyKind::Patterns,
+        scope: KeyScope::RootAndProjects,
+        default: Some("[]"),
+        description: "Glob patterns of the files not belonging to the project.",
+    },
+    ConfigKey {
+        path: "extensions",
+        kind: KeyKind::Map,
+        scope: KeyScope::RootAndProjects,
+        default: Some("{}"),
+        description: "Reserved namespace for tool-specific configuration, deep-merged into projects.",
+    },
+    ConfigKey {
+        path: "projects",
+        kind: KeyKind::Map,
+        scope: KeyScope::Root,
+        default: None,
+        description: "Project-specific configurations, keyed by project name. Without projects, the top-level configuration is the project `default`.",
+    },
+    ConfigKey {
+        path: "schema",
+        kind: KeyKind::Pointers,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "Version 3: the files, globs or URLs of endpoints to load the schema from.",
+    },
+    ConfigKey {
+        path: "documents",
+        kind: KeyKind::Pointers,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "Version 3: the files or globs of the operations and fragments of the project.",
+    },
+    ConfigKey {
+        path: "include",
+        kind: KeyKind::Pointers,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "Version 3: glob patterns of the files belonging to the project.",
+    },
+    ConfigKey {
+        path: "exclude",
+        kind: KeyKind::Pointers,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "Version 3: glob patterns of the files not belonging to the project.",
+    },
+    ConfigKey {
+        path: "extensions.endpoints",
+        kind: KeyKind::Map,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "GraphQL endpoints of the project, keyed by endpoint name.",
+    },
+    ConfigKey {
+        path: "extensions.endpoints.*",
+        kind: KeyKind::StringOrObject,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "The URL of the endpoint, or an object with its `url` and `headers`.",
+    },
+    ConfigKey {
+        path: "extensions.endpoints.*.url",
+        kind: KeyKind::String,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "The URL of the endpoint. May refer to environment variables as `${env:NAME}`.",
+    },
+    ConfigKey {
+        path: "extensions.endpoints.*.headers",
+        kind: KeyKind::Map,
+        scope: KeyScope::RootAndProjects,
+        default: Some("{}"),
+        description: "HTTP headers sent to the endpoint, such as `Authorization`.",
+    },
+    ConfigKey {
+        path: "extensions.endpoints.*.subscription",
+        kind: KeyKind::Object,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "Where subscriptions are sent, if not to `url`.",
+    },
+    ConfigKey {
+        path: "extensions.endpoints.*.subscription.url",
+        kind: KeyKind::String,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "The URL of the subscription endpoint, such as a `wss://` one.",
+    },
+    ConfigKey {
+        path: "extensions.graphqlClient",
+        kind: KeyKind::Object,
+        scope: KeyScope::RootAndProjects,
+        default: None,
+        description: "Options of `graphql_client` code generation.",
+    },
+    ConfigKey {
+        path: "extensions.graphqlClient.customScalars",
+        kind: KeyKind::Map,
+        scope: KeyScope::RootAndProjects,
+        default: Some("{}"),
+        description: "The Rust types of custom scalars, keyed by scalar name.",
+    },
+    ConfigKey {
+        path: "extensions.graphqlClient.responseDerives",
+        kind: KeyKind::Strings,
+        scope: KeyScope::RootAndProjects,
+        default: Some("[]"),
+        description: "The traits to derive on response types, besides `Deserialize`.",
+    },
+    ConfigKey {
+        path: "extensions.graphqlClient.variablesDerives",
+        kind: KeyKind::Strings,
+        scope: KeyScope::RootAndProjects,
+        default: Some("[]"),
+        description: "The traits to derive on variables types, besides `Serialize`.",
+    },
+];
+
+/// The key at `path`, as written in `KEYS`.
+pub fn key(path: &str) -> Option<&'static ConfigKey> {
+    KEYS.iter().find(|key| key.path == path)
+}
+
+/// `KEYS` as JSON, with their defaults parsed:
+/// `{ "version": 1, "keys": [{ "path", "kind", "scope", "default", "description" }] }`.
+pub fn completion_data() -> Value {
+    let keys: Vec<Value> = KEYS
+        .iter()
+        .map(|key| {
+            let mut value = serde_json::to_value(key).expect("keys serialize");
+            value["default"] = key.default.map_or(Value::Null, |default| {
+                serde_json::from_str(default).expect("defaults are JSON")
+            });
+            value
+        })
+        .collect();
+    serde_json::json!({ "version": COMPLETION_DATA_VERSION, "keys": keys })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::v3;
+    use crate::GraphQLConfiguration;
+
+    #[test]
+    fn it_describes_every_field_of_the_configuration() {
+        let pointer = || Some(v3::Pointer::One(String::new()));
+        let modern = v3::GraphQLConfiguration {
+            root: v3::GraphQLProjectConfiguration {
+                schema: pointer(),
+                documents: pointer(),
+                include: pointer(),
+                exclude: pointer(),
+                extensions: Some(Map::new()),
+            },
+            projects: Some(Map::new()),
+        };
+        let mut fields = Vec::new();
+        for value in [
+            ::serde_json::to_value(GraphQLConfiguration::new()).unwrap(),
+            ::serde_json::to_value(modern).unwrap(),
+        ] {
+            match value {
+                Value::Object(object) => fields.extend(object.keys().cloned()),
+                other => panic!("unexpected configuration: {}", other),
+            }
+        }
+        let mut fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+        let mut top_level: Vec<&str> = KEYS
+            .iter()
+            .map(|key| key.path)
+            .filter(|path| !path.contains('.'))
+            .collect();
+        fields.sort_unstable();
+        fields.dedup();
+        top_level.sort_unstable();
+        assert_eq!(top_level, fields);
+
+        let data = completion_data();
+        assert_eq!(data["keys"][2]["default"], json!([]));
+        assert_eq!(data["keys"][5]["scope"], "root");
+    }
+}
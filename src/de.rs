@@ -0,0 +1,172 @@
+//! Deserialization of `GraphQLConfiguration` in a single pass.
+//!
+//! The projects and the top-level fields share the document, which a derived
+//! implementation could only express with `#[serde(flatten)]`. That buffers
+//! the whole document before looking at any field, and errors then point at
+//! the end of the document rather than at the offending value. Reading the
+//! fields directly avoids both. Unknown fields are ignored, as before.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_json::Value;
+
+use crate::glob::GlobPattern;
+use crate::map::Map;
+use crate::projects::ProjectName;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "camelCase")]
+enum Field {
+    Projects,
+    Name,
+    SchemaPath,
+    Includes,
+    Excludes,
+    Extensions,
+    #[serde(other)]
+    Other,
+}
+
+impl<'de> Deserialize<'de> for GraphQLConfiguration {
+    fn deserialize<D>(deserializer: D) -> Result<GraphQLConfiguration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ConfigurationVisitor)
+    }
+}
+
+struct ConfigurationVisitor;
+
+impl<'de> Visitor<'de> for ConfigurationVisitor {
+    type Value = GraphQLConfiguration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a GraphQL configuration")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<GraphQLConfiguration, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let fields: Fields<GraphQLProjectConfiguration, Value> = read_fields(map)?;
+        Ok(GraphQLConfiguration {
+            projects: fields.projects,
+            root: GraphQLProjectConfiguration {
+                name: fields.name,
+                schema_path: fields.schema_path,
+                includes: fields.includes,
+                excludes: fields.excludes,
+                extensions: fields.extensions,
+            },
+        })
+    }
+}
+
+/// The fields of a configuration document, with projects of type `P` and
+/// extensions of type `E`.
+pub(crate) struct Fields<P, E> {
+    pub(crate) projects: Option<Map<ProjectName, P>>,
+    pub(crate) name: Option<String>,
+    pub(crate) schema_path: Option<PathBuf>,
+    pub(crate) includes: Option<Vec<GlobPattern>>,
+    pub(crate) excludes: Option<Vec<GlobPattern>>,
+    pub(crate) extensions: Option<Map<String, E>>,
+}
+
+/// Reads the fields of a configuration document from `map`, ignoring unknown
+/// ones.
+pub(crate) fn read_fields<'de, A, P, E>(mut map: A) -> Result<Fields<P, E>, A::Error>
+where
+    A: MapAccess<'de>,
+    P: Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    // The outer `Option` is whether the field was read, the inner one its
+    // value, which may be `null`.
+    let mut projects: Option<Option<Map<ProjectName, P>>> = None;
+    let mut name: Option<Option<String>> = None;
+    let mut schema_path: Option<Option<PathBuf>> = None;
+    let mut includes: Option<Option<Vec<GlobPattern>>> = None;
+    let mut excludes: Option<Option<Vec<GlobPattern>>> = None;
+    let mut extensions: Option<Option<Map<String, E>>> = None;
+
+    while let Some(field) = map.next_key()? {
+        match field {
+            Field::Projects => next_value(&mut map, &mut projects, "projects")?,
+            Field::Name => next_value(&mut map, &mut name, "name")?,
+            Field::SchemaPath => next_value(&mut map, &mut schema_path, "schemaPath")?,
+            Field::Includes => next_value(&mut map, &mut includes, "includes")?,
+            Field::Excludes => next_value(&mut map, &mut excludes, "excludes")?,
+            Field::Extensions => next_value(&mut map, &mut extensions, "extensions")?,
+            Field::Other => {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+    }
+
+    Ok(Fields {
+        projects: projects.unwrap_or_default(),
+        name: name.unwrap_or_default(),
+        schema_path: schema_path.unwrap_or_default(),
+        includes: includes.unwrap_or_default(),
+        excludes: excludes.unwrap_or_default(),
+        extensions: extensions.unwrap_or_default(),
+    })
+}
+
+/// Reads the value of the field `name` into `slot`, unless it was read
+/// already.
+fn next_value<'de, A, T>(
+    map: &mut A,
+    slot: &mut Option<T>,
+    name: &'static str,
+) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+    T: Deserialize<'de>,
+{
+    if slot.is_some() {
+        return Err(de::Error::duplicate_field(name));
+    }
+    *slot = Some(map.next_value()?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reports_errors_where_they_are() {
+        let error = ::serde_json::from_str::<GraphQLConfiguration>(
+            "{\n  \"name\": \"app\",\n  \"includes\": [\"src/[a-z\"],\n  \"excludes\": []\n}",
+        )
+        .unwrap_err();
+        assert_eq!(error.line(), 3);
+
+        let error =
+            ::serde_json::from_str::<GraphQLConfiguration>(r#"{ "name": "app", "name": "lib" }"#)
+                .unwrap_err();
+        assert!(error.to_string().contains("duplicate field `name`"));
+    }
+
+    #[test]
+    fn it_ignores_unknown_fields_and_nulls() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": null,
+            "$schema": "https://example.com/schema.json",
+            "projects": { "app": { "name": "App", "unknown": true } }
+        }))
+        .unwrap();
+
+        let mut expected = GraphQLConfiguration::new();
+        let mut app = GraphQLProjectConfiguration::new();
+        app.name = Some("App".to_owned());
+        expected.insert_project("app".parse().unwrap(), app);
+        assert_eq!(config, expected);
+    }
+}
@@ -0,0 +1,486 @@
+//! Structural comparison of configurations.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde_json::Value;
+
+use crate::glob::GlobPattern;
+use crate::projects::ProjectName;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// The changes needed to turn one configuration into another, as returned by
+/// `GraphQLConfiguration::diff`.
+#[derive(Serialize, Clone, PartialEq, Debug, Default)]
+pub struct ConfigDiff {
+    /// The changes, top-level configuration first and then project by project
+    /// in order of their names, with the extensions of each in order of their
+    /// names too.
+    pub changes: Vec<ConfigChange>,
+}
+
+/// A single difference between two configurations.
+///
+/// `project` is `None` for changes to the top-level configuration.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(tag = "change", rename_all = "camelCase")]
+pub enum ConfigChange {
+    /// A project was added.
+    ProjectAdded {
+        /// The name of the added project.
+        project: ProjectName,
+    },
+    /// A project was removed.
+    ProjectRemoved {
+        /// The name of the removed project.
+        project: ProjectName,
+    },
+    /// The `name` field changed.
+    NameChanged {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The previous value.
+        from: Option<String>,
+        /// The new value.
+        to: Option<String>,
+    },
+    /// The `schemaPath` field changed.
+    SchemaPathChanged {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The previous value.
+        from: Option<PathBuf>,
+        /// The new value.
+        to: Option<PathBuf>,
+    },
+    /// A pattern was added to `includes`.
+    IncludeAdded {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The pattern.
+        pattern: String,
+    },
+    /// A pattern was removed from `includes`.
+    IncludeRemoved {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The pattern.
+        pattern: String,
+    },
+    /// A pattern was added to `excludes`.
+    ExcludeAdded {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The pattern.
+        pattern: String,
+    },
+    /// A pattern was removed from `excludes`.
+    ExcludeRemoved {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The pattern.
+        pattern: String,
+    },
+    /// An extension was added.
+    ExtensionAdded {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The name of the extension.
+        extension: String,
+        /// The value of the extension.
+        value: Value,
+    },
+    /// An extension was removed.
+    ExtensionRemoved {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The name of the extension.
+        extension: String,
+        /// The value of the extension.
+        value: Value,
+    },
+    /// The value of an extension changed.
+    ExtensionModified {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<ProjectName>,
+        /// The name of the extension.
+        extension: String,
+        /// The previous value.
+        from: Value,
+        /// The new value.
+        to: Value,
+    },
+}
+
+impl GraphQLConfiguration {
+    /// Lists the changes turning `self` into `other`.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::diff::ConfigChange;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let before: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "includes": ["./src/*.graphql"]
+    /// }))?;
+    /// let after: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "includes": ["./src/**/*.graphql"]
+    /// }))?;
+    ///
+    /// assert_eq!(
+    ///     before.diff(&after).changes,
+    ///     vec![
+    ///         ConfigChange::IncludeAdded { project: None, pattern: "./src/**/*.graphql".into() },
+    ///         ConfigChange::IncludeRemoved { project: None, pattern: "./src/*.graphql".into() },
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &GraphQLConfiguration) -> ConfigDiff {
+        let mut changes = Vec::new();
+        diff_project(None, &self.root, &other.root, &mut changes);
+
+        let empty = Default::default();
+        let before = self.projects.as_ref().unwrap_or(&empty);
+        let after = other.projects.as_ref().unwrap_or(&empty);
+
+        let mut names: Vec<&ProjectName> = before.keys().chain(after.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        for name in names {
+            match (before.get(name), after.get(name)) {
+                (Some(before), Some(after)) => {
+                    diff_project(Some(name), before, after, &mut changes)
+                }
+                (None, Some(_)) => changes.push(ConfigChange::ProjectAdded {
+                    project: name.clone(),
+                }),
+                (Some(_), None) => changes.push(ConfigChange::ProjectRemoved {
+                    project: name.clone(),
+                }),
+                (None, None) => unreachable!("names come from either map"),
+            }
+        }
+
+        ConfigDiff { changes }
+    }
+}
+
+impl ConfigDiff {
+    /// Whether the two configurations are structurally equal.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn scope(project: &Option<ProjectName>) -> String {
+            match *project {
+                Some(ref project) => format!("project `{}`", project),
+                None => "root".to_owned(),
+            }
+        }
+        fn optional<T: fmt::Debug>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map_or_else(|| "(none)".to_owned(), |value| format!("{:?}", value))
+        }
+
+        match *self {
+            ConfigChange::ProjectAdded { ref project } => {
+                write!(f, "+ project `{}` added", project)
+            }
+            ConfigChange::ProjectRemoved { ref project } => {
+                write!(f, "- project `{}` removed", project)
+            }
+            ConfigChange::NameChanged {
+                ref project,
+                ref from,
+                ref to,
+            } => write!(
+                f,
+                "~ {}: name changed from {} to {}",
+                scope(project),
+                optional(from),
+                optional(to)
+            ),
+            ConfigChange::SchemaPathChanged {
+                ref project,
+                ref from,
+                ref to,
+            } => write!(
+                f,
+                "~ {}: schemaPath changed from {} to {}",
+                scope(project),
+                optional(from),
+                optional(to)
+            ),
+            ConfigChange::IncludeAdded {
+                ref project,
+                ref pattern,
+            } => write!(f, "+ {}: include {:?}", scope(project), pattern),
+            ConfigChange::IncludeRemoved {
+                ref project,
+                ref pattern,
+            } => write!(f, "- {}: include {:?}", scope(project), pattern),
+            ConfigChange::ExcludeAdded {
+                ref project,
+                ref pattern,
+            } => write!(f, "+ {}: exclude {:?}", scope(project), pattern),
+            ConfigChange::ExcludeRemoved {
+                ref project,
+                ref pattern,
+            } => write!(f, "- {}: exclude {:?}", scope(project), pattern),
+            ConfigChange::ExtensionAdded {
+                ref project,
+                ref extension,
+                ref value,
+            } => write!(
+                f,
+                "+ {}: extension `{}` = {}",
+                scope(project),
+                extension,
+                value
+            ),
+            ConfigChange::ExtensionRemoved {
+                ref project,
+                ref extension,
+                ..
+            } => write!(f, "- {}: extension `{}`", scope(project), extension),
+            ConfigChange::ExtensionModified {
+                ref project,
+                ref extension,
+                ref from,
+                ref to,
+            } => write!(
+                f,
+                "~ {}: extension `{}` changed from {} to {}",
+                scope(project),
+                extension,
+                from,
+                to
+            ),
+        }
+    }
+}
+
+fn diff_project(
+    project: Option<&ProjectName>,
+    before: &GraphQLProjectConfiguration,
+    after: &GraphQLProjectConfiguration,
+    changes: &mut Vec<ConfigChange>,
+) {
+    let project = || project.cloned();
+
+    if before.name != after.name {
+        changes.push(ConfigChange::NameChanged {
+            project: project(),
+            from: before.name.clone(),
+            to: after.name.clone(),
+        });
+    }
+    if before.schema_path != after.schema_path {
+        changes.push(ConfigChange::SchemaPathChanged {
+            project: project(),
+            from: before.schema_path.clone(),
+            to: after.schema_path.clone(),
+        });
+    }
+
+    let (added, removed) = diff_patterns(&before.includes, &after.includes);
+    changes.extend(added.map(|pattern| ConfigChange::IncludeAdded {
+        project: project(),
+        pattern: pattern.to_string(),
+    }));
+    changes.extend(removed.map(|pattern| ConfigChange::IncludeRemoved {
+        project: project(),
+        pattern: pattern.to_string(),
+    }));
+
+    let (added, removed) = diff_patterns(&before.excludes, &after.excludes);
+    changes.extend(added.map(|pattern| ConfigChange::ExcludeAdded {
+        project: project(),
+        pattern: pattern.to_string(),
+    }));
+    changes.extend(removed.map(|pattern| ConfigChange::ExcludeRemoved {
+        project: project(),
+        pattern: pattern.to_string(),
+    }));
+
+    let empty = Default::default();
+    let before = before.extensions.as_ref().unwrap_or(&empty);
+    let after = after.extensions.as_ref().unwrap_or(&empty);
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    for extension in names {
+        match (before.get(extension), after.get(extension)) {
+            (None, Some(to)) => changes.push(ConfigChange::ExtensionAdded {
+                project: project(),
+                extension: extension.clone(),
+                value: to.clone(),
+            }),
+            (Some(from), Some(to)) if from != to => changes.push(ConfigChange::ExtensionModified {
+                project: project(),
+                extension: extension.clone(),
+                from: from.clone(),
+                to: to.clone(),
+            }),
+            (Some(value), None) => changes.push(ConfigChange::ExtensionRemoved {
+                project: project(),
+                extension: extension.clone(),
+                value: value.clone(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+/// The patterns only present in `after` and those only present in `before`.
+fn diff_patterns<'a>(
+    before: &'a Option<Vec<GlobPattern>>,
+    after: &'a Option<Vec<GlobPattern>>,
+) -> (
+    impl Iterator<Item = &'a GlobPattern>,
+    impl Iterator<Item = &'a GlobPattern>,
+) {
+    let before = before.as_ref().map_or(&[][..], |patterns| &patterns[..]);
+    let after = after.as_ref().map_or(&[][..], |patterns| &patterns[..]);
+
+    (
+        after
+            .iter()
+            .filter(move |pattern| !before.contains(pattern)),
+        before
+            .iter()
+            .filter(move |pattern| !after.contains(pattern)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: Value) -> GraphQLConfiguration {
+        ::serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn it_finds_no_changes_between_equal_configs() {
+        let json = json!({
+            "schemaPath": "./schema.graphql",
+            "projects": { "app": { "includes": ["*.graphql"] } }
+        });
+
+        assert!(config(json.clone()).diff(&config(json)).is_empty());
+    }
+
+    #[test]
+    fn it_lists_project_and_extension_changes() {
+        let before = config(json!({
+            "projects": {
+                "app": {
+                    "schemaPath": "./app.graphql",
+                    "extensions": { "endpoint": "http://localhost", "stale": true }
+                },
+                "legacy": {}
+            }
+        }));
+        let after = config(json!({
+            "name": "workspace",
+            "projects": {
+                "admin": {},
+                "app": {
+                    "schemaPath": "./app.schema.graphql",
+                    "excludes": ["./gen/*"],
+                    "extensions": { "endpoint": "http://localhost:4000" }
+                }
+            }
+        }));
+
+        let diff = before.diff(&after);
+
+        let app = Some(ProjectName::new("app").unwrap());
+        assert_eq!(
+            diff.changes,
+            vec![
+                ConfigChange::NameChanged {
+                    project: None,
+                    from: None,
+                    to: Some("workspace".to_owned()),
+                },
+                ConfigChange::ProjectAdded {
+                    project: ProjectName::new("admin").unwrap(),
+                },
+                ConfigChange::SchemaPathChanged {
+                    project: app.clone(),
+                    from: Some("./app.graphql".into()),
+                    to: Some("./app.schema.graphql".into()),
+                },
+                ConfigChange::ExcludeAdded {
+                    project: app.clone(),
+                    pattern: "./gen/*".to_owned(),
+                },
+                ConfigChange::ExtensionModified {
+                    project: app.clone(),
+                    extension: "endpoint".to_owned(),
+                    from: json!("http://localhost"),
+                    to: json!("http://localhost:4000"),
+                },
+                ConfigChange::ExtensionRemoved {
+                    project: app,
+                    extension: "stale".to_owned(),
+                    value: json!(true),
+                },
+                ConfigChange::ProjectRemoved {
+                    project: ProjectName::new("legacy").unwrap(),
+                },
+            ]
+        );
+        assert_eq!(
+            ::serde_json::to_value(&diff.changes[1]).unwrap(),
+            json!({ "change": "projectAdded", "project": "admin" })
+        );
+        assert_eq!(
+            diff.changes[2].to_string(),
+            "~ project `app`: schemaPath changed from \"./app.graphql\" to \"./app.schema.graphql\""
+        );
+    }
+
+    #[test]
+    fn it_lists_extension_changes_by_key() {
+        let before = config(json!({ "extensions": { "c": 1, "b": 1 } }));
+        let after = config(json!({ "extensions": { "d": 1, "a": 1, "b": 2 } }));
+
+        let extensions: Vec<String> = before
+            .diff(&after)
+            .changes
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            extensions,
+            vec![
+                "+ root: extension `a` = 1",
+                "~ root: extension `b` changed from 1 to 2",
+                "- root: extension `c`",
+                "+ root: extension `d` = 1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Finding the configuration files of a workspace.
+
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::io;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::load::{self, LoadError};
+use crate::GraphQLConfiguration;
+
+/// The names of configuration files, by precedence: a directory with several
+/// of them is configured by the first one.
+pub const CONFIG_FILE_NAMES: &[&str] = &[
+    ".graphqlrc",
+    ".graphqlrc.json",
+    ".graphqlrc.yaml",
+    ".graphqlrc.yml",
+    "graphql.config.json",
+    "graphql.config.yaml",
+    "graphql.config.yml",
+    ".graphqlconfig",
+    ".graphqlconfig.json",
+    ".graphqlconfig.yaml",
+    ".graphqlconfig.yml",
+];
+
+/// The configuration file of `dir`, if it has one: the first of
+/// `CONFIG_FILE_NAMES`, or else a `package.json` file with a `graphql` key.
+pub fn config_file(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+        .or_else(|| {
+            let package = dir.join("package.json");
+            let contents = fs::read_to_string(&package).ok()?;
+            match load::from_package_json(&contents) {
+                Ok(None) => None,
+                _ => Some(package),
+            }
+        })
+}
+
+/// The configuration file of `start_dir` or of its closest ancestor with
+/// one, like the lookup of the JavaScript implementation. A relative
+/// `start_dir` is relative to the working directory, whose ancestors are
+/// searched too.
+///
+/// Fails with `io::ErrorKind::NotFound` when no ancestor has one.
+pub fn find_config_file(start_dir: impl AsRef<Path>) -> io::Result<PathBuf> {
+    let start_dir = start_dir.as_ref();
+    let start_dir = if start_dir.is_absolute() {
+        start_dir.to_owned()
+    } else {
+        env::current_dir()?.join(start_dir)
+    };
+    start_dir
+        .ancestors()
+        .filter_map(config_file)
+        .next()
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "no configuration file in `{}` or its ancestors",
+                    start_dir.display()
+                ),
+            )
+        })
+}
+
+impl GraphQLConfiguration {
+    /// Loads the configuration file `discover::find_config_file` finds from
+    /// `start_dir`, returning its path with it, which the paths in the
+    /// configuration are relative to.
+    ///
+    /// ```no_run
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), graphql_config::load::LoadError> {
+    /// let (path, config) = GraphQLConfiguration::find("/repo/packages/web/src")?;
+    /// println!("{}: {} projects", path.display(), config.iter_projects().count());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find(start_dir: impl AsRef<Path>) -> Result<(PathBuf, GraphQLConfiguration), LoadError> {
+        let path = find_config_file(start_dir)?;
+        let config = GraphQLConfiguration::try_from(path.as_path())?;
+        Ok((path, config))
+    }
+}
+
+/// Loads every configuration file under `root`, sorted by path, such as the
+/// ones of every package of a monorepo. Hidden directories, whose names start
+/// with `.`, `node_modules` and symbolic links are skipped.
+///
+/// The files are loaded and parsed concurrently. A file which fails to load
+/// does not prevent the others from loading, its error is returned in its
+/// place.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::discover::discover_all;
+/// # fn main() -> std::io::Result<()> {
+/// for (path, config) in discover_all("/repo")? {
+///     match config {
+///         Ok(config) => println!("{}: {} projects", path.display(), config.iter_projects().count()),
+///         Err(error) => eprintln!("{}: {}", path.display(), error),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn discover_all(
+    root: impl AsRef<Path>,
+) -> io::Result<Vec<(PathBuf, Result<GraphQLConfiguration, LoadError>)>> {
+    let mut paths = Vec::new();
+    walk(root.as_ref(), &mut paths)?;
+    paths.sort();
+
+    let configs = load_all(&paths);
+    Ok(paths.into_iter().zip(configs).collect())
+}
+
+fn walk(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    paths.extend(config_file(dir));
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            walk(&entry.path(), paths)?;
+        }
+    }
+    Ok(())
+}
+
+/// Loads the files at `paths` on as many threads as there are processors.
+fn load_all(paths: &[PathBuf]) -> Vec<Result<GraphQLConfiguration, LoadError>> {
+    let workers = thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(paths.len());
+    let next = AtomicUsize::new(0);
+
+    let mut loaded: Vec<(usize, Result<GraphQLConfiguration, LoadError>)> =
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut loaded = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            match paths.get(index) {
+                                Some(path) => loaded
+                                    .push((index, GraphQLConfiguration::try_from(path.as_path()))),
+                                None => return loaded,
+                            }
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("loading does not panic"))
+                .collect()
+        });
+
+    loaded.sort_by_key(|&(index, _)| index);
+    loaded.into_iter().map(|(_, config)| config).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn it_loads_every_package() {
+        let dir = TestDir::new("it_loads_every_package");
+        for package in &["web", "admin", "broken", "node_modules/dep"] {
+            fs::create_dir_all(dir.join("packages").join(package)).unwrap();
+        }
+        fs::write(dir.join(".graphqlconfig"), r#"{ "name": "root" }"#).unwrap();
+        for package in &["web", "admin", "node_modules/dep"] {
+            let config = format!(r#"{{ "name": "{}" }}"#, package);
+            fs::write(
+                dir.join("packages").join(package).join(".graphqlrc"),
+                config,
+            )
+            .unwrap();
+        }
+        fs::write(dir.join("packages/broken/.graphqlconfig.json"), "{").unwrap();
+        fs::create_dir_all(dir.join("packages/docs")).unwrap();
+        fs::write(
+            dir.join("packages/docs/package.json"),
+            r#"{ "name": "docs" }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(dir.join("packages/mobile")).unwrap();
+        fs::write(
+            dir.join("packages/mobile/package.json"),
+            r#"{ "graphql": { "name": "mobile" } }"#,
+        )
+        .unwrap();
+
+        let discovered = discover_all(&dir).unwrap();
+
+        let paths: Vec<&Path> = discovered.iter().map(|(path, _)| path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                dir.join(".graphqlconfig"),
+                dir.join("packages/admin/.graphqlrc"),
+                dir.join("packages/broken/.graphqlconfig.json"),
+                dir.join("packages/mobile/package.json"),
+                dir.join("packages/web/.graphqlrc"),
+            ]
+        );
+        let names: Vec<Option<String>> = discovered
+            .into_iter()
+            .map(|(_, config)| config.ok().and_then(|config| config.root.name))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                Some("root".to_owned()),
+                Some("admin".to_owned()),
+                None,
+                Some("mobile".to_owned()),
+                Some("web".to_owned()),
+            ]
+        );
+
+        fs::create_dir_all(dir.join("packages/api/src")).unwrap();
+        fs::write(
+            dir.join("packages/api/graphql.config.json"),
+            r#"{ "schema": "./schema.graphql", "documents": "./src/*.graphql" }"#,
+        )
+        .unwrap();
+        let (path, config) = GraphQLConfiguration::find(dir.join("packages/api/src")).unwrap();
+        assert_eq!(path, dir.join("packages/api/graphql.config.json"));
+        assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+        assert_eq!(
+            config.root.includes,
+            Some(vec!["./src/*.graphql".parse().unwrap()])
+        );
+
+        let (path, config) = GraphQLConfiguration::find(dir.join("packages/web")).unwrap();
+        assert_eq!(path, dir.join("packages/web/.graphqlrc"));
+        assert_eq!(config.root.name, Some("web".to_owned()));
+        fs::create_dir_all(dir.join("packages/docs/src")).unwrap();
+        let found = find_config_file(dir.join("packages/docs/src")).unwrap();
+        assert_eq!(found, dir.join(".graphqlconfig"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Whether the schema file of a project has drifted from the one committed
+//! to git and from the one its endpoint serves, for CI to fail on.
+//!
+//! The committed schema is read with `git show HEAD:<file>`, so the `git`
+//! command must be installed. The crate does not fetch schemas itself; the
+//! one of the endpoint is given to `SchemaDrift::with_remote`, such as the
+//! introspection result of `graphql-config get-schema` converted with
+//! `introspection::to_sdl`.
+//!
+//! Schemas are compared line by line, ignoring trailing whitespace and
+//! blank lines.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::schema::SchemaPointer;
+
+/// The schema of a project in the working tree, in `HEAD` and at its
+/// endpoint.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SchemaDrift {
+    /// The name of the project.
+    pub project: String,
+    /// The schema file of the project.
+    pub schema_path: PathBuf,
+    /// The schema file in the working tree.
+    pub working: String,
+    /// The schema file in `HEAD`, if it is committed.
+    pub head: Option<String>,
+    /// The schema served by the endpoint, if given.
+    pub remote: Option<String>,
+}
+
+/// Why the drift of a schema could not be checked.
+#[derive(Debug)]
+pub enum DriftError {
+    /// There is no project of that name.
+    UnknownProject(String),
+    /// The `schemaPath` of the project, named by the variant, is missing or
+    /// is not a file.
+    NoSchemaFile(String),
+    /// The schema file could not be read.
+    Io(PathBuf, io::Error),
+    /// `git` failed, with its error output.
+    Git(String),
+}
+
+impl fmt::Display for DriftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DriftError::UnknownProject(ref project) => {
+                write!(f, "there is no project `{}`", project)
+            }
+            DriftError::NoSchemaFile(ref project) => {
+                write!(f, "project `{}` has no schema file", project)
+            }
+            DriftError::Io(ref path, ref error) => write!(f, "{}: {}", path.display(), error),
+            DriftError::Git(ref error) => write!(f, "git: {}", error),
+        }
+    }
+}
+
+impl Error for DriftError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            DriftError::Io(_, ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl SchemaDrift {
+    /// Reads the schema file of `project` from the working tree and from
+    /// `HEAD`.
+    pub fn check(config: &ResolvedGraphQLConfig, project: &str) -> Result<SchemaDrift, DriftError> {
+        let schema_path = config
+            .project(project)
+            .ok_or_else(|| DriftError::UnknownProject(project.to_owned()))?
+            .schema_path
+            .ok_or_else(|| DriftError::NoSchemaFile(project.to_owned()))?;
+        let schema_path =
+            match SchemaPointer::classify(&schema_path.to_string_lossy(), config.dir()) {
+                SchemaPointer::File(schema_path) => schema_path,
+                _ => return Err(DriftError::NoSchemaFile(project.to_owned())),
+            };
+        let working = fs::read_to_string(&schema_path)
+            .map_err(|error| DriftError::Io(schema_path.clone(), error))?;
+        let dir = schema_path
+            .parent()
+            .map_or_else(|| config.dir().to_owned(), ToOwned::to_owned);
+        let name = schema_path
+            .file_name()
+            .ok_or_else(|| DriftError::NoSchemaFile(project.to_owned()))?
+            .to_string_lossy();
+
+        git(&dir, &["rev-parse", "--is-inside-work-tree"])?;
+        let head = git(&dir, &["show", &format!("HEAD:./{}", name)]).ok();
+
+        Ok(SchemaDrift {
+            project: project.to_owned(),
+            working,
+            head,
+            remote: None,
+            schema_path,
+        })
+    }
+
+    /// Compares the schema with `remote`, the one the endpoint serves.
+    pub fn with_remote(mut self, remote: impl Into<String>) -> SchemaDrift {
+        self.remote = Some(remote.into());
+        self
+    }
+
+    /// Whether the schema file differs from `HEAD`, or is not committed.
+    pub fn uncommitted(&self) -> bool {
+        self.head
+            .as_ref()
+            .is_none_or(|head| !same(head, &self.working))
+    }
+
+    /// Whether the schema file differs from the one of the endpoint.
+    pub fn outdated(&self) -> bool {
+        self.remote
+            .as_ref()
+            .is_some_and(|remote| !same(remote, &self.working))
+    }
+
+    /// Whether the committed schema differs from the one of the endpoint.
+    pub fn outdated_in_head(&self) -> bool {
+        match (self.head.as_ref(), self.remote.as_ref()) {
+            (Some(head), Some(remote)) => !same(head, remote),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        }
+    }
+
+    /// Whether any of the three schemas differ.
+    pub fn is_drifted(&self) -> bool {
+        self.uncommitted() || self.outdated() || self.outdated_in_head()
+    }
+}
+
+/// One line per schema which differs from another, or one saying they are
+/// in sync.
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = format!("project `{}`: {}", self.project, self.schema_path.display());
+        if !self.is_drifted() {
+            return writeln!(f, "{}: in sync", prefix);
+        }
+        if self.head.is_none() {
+            writeln!(f, "{}: not committed", prefix)?;
+        } else if self.uncommitted() {
+            writeln!(f, "{}: differs from HEAD", prefix)?;
+        }
+        if self.outdated() {
+            writeln!(f, "{}: differs from the endpoint", prefix)?;
+        }
+        if self.head.is_some() && self.outdated_in_head() {
+            writeln!(f, "{}: differs from the endpoint in HEAD", prefix)?;
+        }
+        Ok(())
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String, DriftError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|error| DriftError::Git(error.to_string()))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(DriftError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ))
+    }
+}
+
+fn same(a: &str, b: &str) -> bool {
+    let a = a.lines().map(str::trim_end).filter(|line| !line.is_empty());
+    let b = b.lines().map(str::trim_end).filter(|line| !line.is_empty());
+    a.eq(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+
+    #[test]
+    fn it_compares_the_working_tree_head_and_endpoint() {
+        let dir = TestDir::new("it_compares_the_working_tree_head_and_endpoint");
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(&*dir)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        run(&["init", "-q"]);
+        fs::write(dir.join("schema.graphql"), "type Query { a: Int }\n").unwrap();
+        run(&["add", "schema.graphql"]);
+        run(&["commit", "-q", "-m", "schema"]);
+
+        let config: GraphQLConfiguration =
+            ::serde_json::from_value(json!({ "schemaPath": "schema.graphql" })).unwrap();
+        let config = ResolvedGraphQLConfig::new(config, dir.join(".graphqlconfig"));
+
+        let drift = SchemaDrift::check(&config, "default").unwrap();
+        assert!(!drift.is_drifted());
+        let drift = drift.with_remote("type Query { a: Int }  \n\n");
+        assert!(!drift.is_drifted());
+        let drift = drift.with_remote("type Query { a: Int b: Int }");
+        assert!(drift.outdated() && drift.outdated_in_head() && !drift.uncommitted());
+
+        fs::write(dir.join("schema.graphql"), "type Query { a: Int b: Int }\n").unwrap();
+        let drift = SchemaDrift::check(&config, "default").unwrap();
+        assert!(drift.uncommitted() && !drift.outdated());
+        assert_eq!(
+            drift.to_string(),
+            format!(
+                "project `default`: {}: differs from HEAD\n",
+                dir.join("schema.graphql").display()
+            )
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! The typed `endpoints` extension, naming the GraphQL endpoints of a
+//! project as the JavaScript implementation does.
+//!
+//! An endpoint is written either as its URL or as an object with its `url`,
+//! the `headers` to send and the endpoint of its `subscription`s:
+//!
+//! ```
+//! # #[macro_use]
+//! # extern crate serde_json;
+//! # extern crate graphql_config;
+//! # use graphql_config::*;
+//! # fn main() -> Result<(), serde_json::Error> {
+//! let project: GraphQLProjectConfiguration = serde_json::from_value(json!({
+//!     "extensions": {
+//!         "endpoints": {
+//!             "dev": "http://localhost:4000/graphql",
+//!             "prod": {
+//!                 "url": "https://example.com/graphql",
+//!                 "headers": { "Authorization": "Bearer ${env:TOKEN}" },
+//!                 "subscription": { "url": "wss://example.com/graphql" }
+//!             }
+//!         }
+//!     }
+//! }))?;
+//!
+//! let endpoints = project.endpoints().unwrap()?;
+//! assert_eq!(endpoints.get("dev").unwrap().url, "http://localhost:4000/graphql");
+//! let prod = endpoints.get("prod").unwrap();
+//! assert_eq!(prod.headers["Authorization"], "Bearer ${env:TOKEN}");
+//! assert_eq!(prod.subscription.as_ref().unwrap().url, "wss://example.com/graphql");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Placeholders are left as written; `ResolvedGraphQLConfig::endpoint`
+//! resolves them. Everything reading endpoints goes through these types, so
+//! an extension which does not deserialize, such as one with a header which
+//! is not a string, has no endpoints for any of them.
+
+use serde_json::Value;
+
+use crate::extension::Extension;
+use crate::map::Map;
+use crate::resolved::ResolvedProjectConfig;
+use crate::GraphQLProjectConfiguration;
+
+/// The `endpoints` extension: the endpoints of a project, by name.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(transparent)]
+pub struct GraphQLConfigEndpointsData {
+    /// The endpoints, by name.
+    pub endpoints: Map<String, GraphQLConfigEndpointConfig>,
+}
+
+impl GraphQLConfigEndpointsData {
+    /// The endpoint named `name`.
+    pub fn get(&self, name: &str) -> Option<&GraphQLConfigEndpointConfig> {
+        self.endpoints.get(name)
+    }
+}
+
+impl Extension for GraphQLConfigEndpointsData {
+    const NAME: &'static str = "endpoints";
+}
+
+/// An endpoint of the `endpoints` extension. It serializes as its URL when
+/// it has nothing else.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[non_exhaustive]
+#[serde(from = "EndpointRepr", into = "EndpointRepr")]
+pub struct GraphQLConfigEndpointConfig {
+    /// The URL of the endpoint.
+    pub url: String,
+    /// The HTTP headers sent to the endpoint, by name.
+    pub headers: Map<String, String>,
+    /// Where subscriptions are sent, if not to `url`.
+    pub subscription: Option<GraphQLConfigEndpointSubscription>,
+    /// The other fields of the endpoint, kept for the tools reading them.
+    pub other: Map<String, Value>,
+}
+
+impl GraphQLConfigEndpointConfig {
+    /// An endpoint at `url`, without headers.
+    pub fn new(url: impl Into<String>) -> GraphQLConfigEndpointConfig {
+        GraphQLConfigEndpointConfig {
+            url: url.into(),
+            headers: Map::new(),
+            subscription: None,
+            other: Map::new(),
+        }
+    }
+}
+
+/// The `subscription` of an endpoint.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[non_exhaustive]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLConfigEndpointSubscription {
+    /// The URL of the subscription endpoint, such as a `wss://` one.
+    pub url: String,
+    /// The parameters sent when connecting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_params: Option<Map<String, Value>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum EndpointRepr {
+    Url(String),
+    Config(Box<EndpointFields>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct EndpointFields {
+    url: String,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    headers: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    subscription: Option<GraphQLConfigEndpointSubscription>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+impl From<EndpointRepr> for GraphQLConfigEndpointConfig {
+    fn from(repr: EndpointRepr) -> GraphQLConfigEndpointConfig {
+        match repr {
+            EndpointRepr::Url(url) => GraphQLConfigEndpointConfig::new(url),
+            EndpointRepr::Config(fields) => {
+                let EndpointFields {
+                    url,
+                    headers,
+                    subscription,
+                    other,
+                } = *fields;
+                GraphQLConfigEndpointConfig {
+                    url,
+                    headers,
+                    subscription,
+                    other,
+                }
+            }
+        }
+    }
+}
+
+impl From<GraphQLConfigEndpointConfig> for EndpointRepr {
+    fn from(endpoint: GraphQLConfigEndpointConfig) -> EndpointRepr {
+        if endpoint.headers.is_empty()
+            && endpoint.subscription.is_none()
+            && endpoint.other.is_empty()
+        {
+            return EndpointRepr::Url(endpoint.url);
+        }
+        EndpointRepr::Config(Box::new(EndpointFields {
+            url: endpoint.url,
+            headers: endpoint.headers,
+            subscription: endpoint.subscription,
+            other: endpoint.other,
+        }))
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// Deserializes the `endpoints` extension, returning `None` if it is
+    /// absent.
+    pub fn endpoints(&self) -> Option<Result<GraphQLConfigEndpointsData, serde_json::Error>> {
+        self.extension::<GraphQLConfigEndpointsData>()
+    }
+}
+
+impl ResolvedProjectConfig {
+    /// Deserializes the `endpoints` extension, returning `None` if it is
+    /// absent.
+    pub fn endpoints(&self) -> Option<Result<GraphQLConfigEndpointsData, serde_json::Error>> {
+        self.extension::<GraphQLConfigEndpointsData>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_both_forms_of_endpoints() {
+        let written = json!({
+            "dev": "http://localhost:4000/graphql",
+            "prod": {
+                "url": "https://example.com/graphql",
+                "headers": { "X-Team": "web" },
+                "introspect": false
+            }
+        });
+        let mut project = GraphQLProjectConfiguration::new();
+        project
+            .extensions_mut()
+            .insert("endpoints".to_owned(), written.clone());
+
+        let mut endpoints = project.endpoints().unwrap().unwrap();
+        assert_eq!(
+            endpoints.get("dev"),
+            Some(&GraphQLConfigEndpointConfig::new(
+                "http://localhost:4000/graphql"
+            ))
+        );
+        assert_eq!(
+            endpoints.get("prod").unwrap().other["introspect"],
+            json!(false)
+        );
+        assert_eq!(::serde_json::to_value(&endpoints).unwrap(), written);
+
+        let mut staging = GraphQLConfigEndpointConfig::new("https://staging.example.com/graphql");
+        staging.subscription = Some(GraphQLConfigEndpointSubscription {
+            url: "wss://staging.example.com/graphql".to_owned(),
+            connection_params: None,
+        });
+        endpoints.endpoints.insert("staging".to_owned(), staging);
+        project.set_extension(&endpoints).unwrap();
+        assert_eq!(
+            project.extensions.unwrap()["endpoints"]["staging"],
+            json!({
+                "url": "https://staging.example.com/graphql",
+                "subscription": { "url": "wss://staging.example.com/graphql" }
+            })
+        );
+
+        let malformed: GraphQLProjectConfiguration =
+            ::serde_json::from_value(json!({ "extensions": { "endpoints": { "dev": 1 } } }))
+                .unwrap();
+        assert!(malformed.endpoints().unwrap().is_err());
+    }
+}
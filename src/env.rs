@@ -0,0 +1,475 @@
+//! Environment variables for `${env:NAME}` placeholders.
+//!
+//! The specification allows configurations to refer to environment variables,
+//! typically for endpoint URLs and headers, as `${env:NAME}`. Placeholders are
+//! looked up in an `Env`, which reads the process environment by default but
+//! can be given a fixed set of variables, so tests and hermetic builds do not
+//! depend on the environment they run in.
+//!
+//! `ResolvedGraphQLConfig` replaces the placeholders of set variables as it
+//! resolves projects. Tools which need every variable set replace them all
+//! at once with `GraphQLConfiguration::resolve_env_vars`, which fails with
+//! the names of the unset ones, or with the pattern the values of the
+//! variables make malformed.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::glob::{GlobPattern, PatternError};
+use crate::map::{self, Map};
+use crate::projects::ProjectName;
+use crate::resolved::{ResolvedGraphQLConfig, ResolvedProjectConfig};
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// Where the values of `${env:NAME}` placeholders come from.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::env::Env;
+/// # fn main() {
+/// let env = Env::from_map(vec![("API_HOST", "api.example.com")]);
+///
+/// assert_eq!(
+///     env.interpolate_lossy("https://${env:API_HOST}/graphql"),
+///     "https://api.example.com/graphql"
+/// );
+/// assert_eq!(env.interpolate_lossy("${env:TOKEN}"), "${env:TOKEN}");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Env {
+    lookup: Arc<Lookup>,
+}
+
+type Lookup = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+impl Env {
+    /// Reads the environment of the process.
+    pub fn process() -> Env {
+        Env::from_fn(|name| ::std::env::var(name).ok())
+    }
+
+    /// Reads the given variables only.
+    pub fn from_map<I, K, V>(vars: I) -> Env
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars: BTreeMap<String, String> = vars
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        Env::from_fn(move |name| vars.get(name).cloned())
+    }
+
+    /// Looks variables up with `lookup`.
+    pub fn from_fn<F>(lookup: F) -> Env
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        Env {
+            lookup: Arc::new(lookup),
+        }
+    }
+
+    /// The value of the variable `name`.
+    pub fn var(&self, name: &str) -> Option<String> {
+        (self.lookup)(name)
+    }
+
+    /// Replaces the placeholders in `value`, leaving those of unset variables
+    /// as they are.
+    pub fn interpolate_lossy(&self, value: &str) -> String {
+        self.interpolate_into(value, &mut Vec::new())
+    }
+
+    /// Replaces the placeholders in `value`, pushing the names of unset
+    /// variables to `missing`.
+    pub(crate) fn interpolate_into(&self, value: &str, missing: &mut Vec<String>) -> String {
+        let mut interpolated = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find("${env:") {
+            let end = match rest[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+            let name = &rest[start + "${env:".len()..end];
+
+            interpolated.push_str(&rest[..start]);
+            match self.var(name) {
+                Some(value) => interpolated.push_str(&value),
+                None => {
+                    interpolated.push_str(&rest[start..=end]);
+                    if !missing.iter().any(|missing| missing == name) {
+                        missing.push(name.to_owned());
+                    }
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+
+        interpolated.push_str(rest);
+        interpolated
+    }
+
+    /// Replaces the placeholders in every string inside `value`.
+    pub(crate) fn interpolate_value(&self, value: &mut Value, missing: &mut Vec<String>) {
+        match *value {
+            Value::String(ref mut string) => *string = self.interpolate_into(string, missing),
+            Value::Array(ref mut values) => {
+                for value in values {
+                    self.interpolate_value(value, missing);
+                }
+            }
+            Value::Object(ref mut values) => {
+                for value in values.values_mut() {
+                    self.interpolate_value(value, missing);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The error of `GraphQLConfiguration::resolve_env_vars`: placeholders
+/// refer to variables which are not set.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MissingEnvVars {
+    /// The names of the variables, sorted.
+    pub names: Vec<String>,
+}
+
+impl fmt::Display for MissingEnvVars {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("environment variables are not set: ")?;
+        for (index, name) in self.names.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{}`", name)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for MissingEnvVars {}
+
+/// The error of `GraphQLConfiguration::resolve_env_vars`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum EnvError {
+    /// Placeholders refer to variables which are not set.
+    Missing(MissingEnvVars),
+    /// The values of the variables make a pattern malformed.
+    InvalidPattern {
+        /// The project of the pattern, `None` for the top-level
+        /// configuration.
+        project: Option<ProjectName>,
+        /// The pattern as written.
+        pattern: String,
+        /// What is wrong with the pattern once the variables are replaced.
+        source: PatternError,
+    },
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EnvError::Missing(ref missing) => missing.fmt(f),
+            EnvError::InvalidPattern {
+                ref project,
+                ref pattern,
+                ref source,
+            } => {
+                match *project {
+                    Some(ref project) => write!(f, "project `{}`", project)?,
+                    None => f.write_str("the top-level configuration")?,
+                }
+                write!(
+                    f,
+                    " has the pattern `{}`, which environment variables make malformed: {}",
+                    pattern, source
+                )
+            }
+        }
+    }
+}
+
+impl Error for EnvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            EnvError::Missing(_) => None,
+            EnvError::InvalidPattern { ref source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<MissingEnvVars> for EnvError {
+    fn from(missing: MissingEnvVars) -> EnvError {
+        EnvError::Missing(missing)
+    }
+}
+
+impl GraphQLConfiguration {
+    /// The configuration with the placeholders of every string replaced
+    /// with the variables of `env`: names, schema paths, patterns and the
+    /// strings inside extensions, top-level and in projects. Fails with the
+    /// first pattern the values make malformed, or else with every unset
+    /// variable.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::env::Env;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "${env:SCHEMA}",
+    ///     "extensions": { "endpoints": { "dev": { "headers": { "Authorization": "${env:TOKEN}" } } } }
+    /// }))?;
+    ///
+    /// let resolved = config.resolve_env_vars(&Env::from_map(vec![("SCHEMA", "schema.graphql")]));
+    /// assert_eq!(resolved.unwrap_err().to_string(), "environment variables are not set: `TOKEN`");
+    ///
+    /// let env = Env::from_map(vec![("SCHEMA", "schema.graphql"), ("TOKEN", "secret")]);
+    /// let resolved = config.resolve_env_vars(&env).unwrap();
+    /// assert_eq!(resolved.root.schema_path, Some("schema.graphql".into()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve_env_vars(&self, env: &Env) -> Result<GraphQLConfiguration, EnvError> {
+        let mut config = self.clone();
+        let mut missing = Vec::new();
+        let mut invalid = Vec::new();
+        Placeholders::of_project(&mut config.root).replace(env, None, &mut missing, &mut invalid);
+        if let Some(ref mut projects) = config.projects {
+            for (key, project) in map::ordered_mut(projects) {
+                Placeholders::of_project(project).replace(
+                    env,
+                    Some(key),
+                    &mut missing,
+                    &mut invalid,
+                );
+            }
+        }
+        if let Some(invalid) = invalid.into_iter().next() {
+            return Err(invalid);
+        }
+        if missing.is_empty() {
+            return Ok(config);
+        }
+        missing.sort();
+        missing.dedup();
+        Err(EnvError::Missing(MissingEnvVars { names: missing }))
+    }
+}
+
+impl ResolvedGraphQLConfig {
+    /// `GraphQLConfiguration::resolve_env_vars` of the configuration as
+    /// written, with the variables of `env`.
+    pub fn resolve_env_vars(&self) -> Result<GraphQLConfiguration, EnvError> {
+        self.raw().resolve_env_vars(self.env())
+    }
+}
+
+/// The strings of a project which placeholders are replaced in, whether
+/// the project is written, a `GraphQLProjectConfiguration`, or resolved, a
+/// `ResolvedProjectConfig`.
+pub(crate) struct Placeholders<'a> {
+    name: Option<&'a mut String>,
+    schema_path: Option<&'a mut PathBuf>,
+    patterns: Vec<&'a mut GlobPattern>,
+    extensions: Option<&'a mut Map<String, Value>>,
+}
+
+impl<'a> Placeholders<'a> {
+    fn of_project(project: &'a mut GraphQLProjectConfiguration) -> Placeholders<'a> {
+        Placeholders {
+            name: project.name.as_mut(),
+            schema_path: project.schema_path.as_mut(),
+            patterns: project
+                .includes
+                .iter_mut()
+                .chain(&mut project.excludes)
+                .flatten()
+                .collect(),
+            extensions: project.extensions.as_mut(),
+        }
+    }
+
+    pub(crate) fn of_resolved(project: &'a mut ResolvedProjectConfig) -> Placeholders<'a> {
+        Placeholders {
+            name: Some(&mut project.name),
+            schema_path: project.schema_path.as_mut(),
+            patterns: project
+                .includes
+                .iter_mut()
+                .chain(&mut project.excludes)
+                .collect(),
+            extensions: Some(&mut project.extensions),
+        }
+    }
+
+    /// Replaces the placeholders with the variables of `env`, pushing the
+    /// names of unset variables to `missing`. Patterns which the values make
+    /// malformed are kept as written, and an `EnvError::InvalidPattern` of
+    /// the project `key` pushed to `invalid` for each of them.
+    pub(crate) fn replace(
+        self,
+        env: &Env,
+        key: Option<&ProjectName>,
+        missing: &mut Vec<String>,
+        invalid: &mut Vec<EnvError>,
+    ) {
+        if let Some(name) = self.name {
+            *name = env.interpolate_into(name, missing);
+        }
+        if let Some(schema_path) = self.schema_path {
+            if let Some(interpolated) = schema_path
+                .to_str()
+                .map(|path| env.interpolate_into(path, missing))
+            {
+                *schema_path = interpolated.into();
+            }
+        }
+        for pattern in self.patterns {
+            let interpolated = env.interpolate_into(pattern.as_str(), missing);
+            if interpolated == pattern.as_str() {
+                continue;
+            }
+            match GlobPattern::new(interpolated) {
+                Ok(interpolated) => *pattern = interpolated,
+                Err(source) => invalid.push(EnvError::InvalidPattern {
+                    project: key.cloned(),
+                    pattern: pattern.to_string(),
+                    source,
+                }),
+            }
+        }
+        for value in self
+            .extensions
+            .into_iter()
+            .flat_map(|extensions| extensions.values_mut())
+        {
+            env.interpolate_value(value, missing);
+        }
+    }
+}
+
+/// Reads the environment of the process.
+impl Default for Env {
+    fn default() -> Env {
+        Env::process()
+    }
+}
+
+impl fmt::Debug for Env {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Env { .. }")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_interpolates_placeholders() {
+        let env = Env::from_map(vec![("HOST", "localhost"), ("PORT", "4000")]);
+        let mut missing = Vec::new();
+
+        assert_eq!(
+            env.interpolate_into(
+                "http://${env:HOST}:${env:PORT}/${env:PATH}?${env:PATH}${env:",
+                &mut missing
+            ),
+            "http://localhost:4000/${env:PATH}?${env:PATH}${env:"
+        );
+        assert_eq!(missing, vec!["PATH".to_owned()]);
+    }
+
+    #[test]
+    fn it_interpolates_nested_values() {
+        let env = Env::from_fn(|name| Some(name.to_lowercase()));
+        let mut value = json!({ "headers": { "Authorization": "Bearer ${env:TOKEN}" }, "n": 1 });
+
+        env.interpolate_value(&mut value, &mut Vec::new());
+
+        assert_eq!(
+            value,
+            json!({ "headers": { "Authorization": "Bearer token" }, "n": 1 })
+        );
+    }
+
+    #[test]
+    fn it_resolves_every_placeholder_or_lists_the_missing_ones() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "includes": ["${env:SRC}/**/*.graphql"],
+            "projects": {
+                "app": {
+                    "name": "${env:APP}",
+                    "extensions": { "endpoints": { "dev": "http://${env:HOST}/graphql" } }
+                },
+                "admin": { "schemaPath": "${env:HOST}/${env:SCHEMA}" }
+            }
+        }))
+        .unwrap();
+
+        let missing = match config.resolve_env_vars(&Env::from_map(vec![("SRC", "src")])) {
+            Err(EnvError::Missing(missing)) => missing,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(missing.names, vec!["APP", "HOST", "SCHEMA"]);
+        assert_eq!(
+            missing.to_string(),
+            "environment variables are not set: `APP`, `HOST`, `SCHEMA`"
+        );
+
+        let env = Env::from_fn(|name| Some(name.to_lowercase()));
+        let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig").with_env(env);
+        let resolved = resolved.resolve_env_vars().unwrap();
+        let projects = resolved.projects.as_ref().unwrap();
+        assert_eq!(
+            resolved.root.includes.as_ref().unwrap()[0].as_str(),
+            "src/**/*.graphql"
+        );
+        assert_eq!(projects["app"].name, Some("app".to_owned()));
+        assert_eq!(
+            projects["app"].extensions.as_ref().unwrap()["endpoints"]["dev"],
+            json!("http://host/graphql")
+        );
+        assert_eq!(projects["admin"].schema_path, Some("host/schema".into()));
+    }
+
+    #[test]
+    fn it_fails_on_patterns_the_variables_make_malformed() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": { "app": { "excludes": ["${env:GENERATED}/**"] } }
+        }))
+        .unwrap();
+
+        let error = config
+            .resolve_env_vars(&Env::from_map(vec![("GENERATED", "src/[gen")]))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            EnvError::InvalidPattern {
+                project: Some("app".parse().unwrap()),
+                pattern: "${env:GENERATED}/**".to_owned(),
+                source: PatternError::UnclosedClass("src/[gen/**".to_owned()),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "project `app` has the pattern `${env:GENERATED}/**`, which environment \
+             variables make malformed: invalid pattern `src/[gen/**`: unclosed `[`"
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Typed access to the `extensions` namespace.
+
+use std::ops::{Deref, DerefMut};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{self, Value};
+
+use crate::map::Map;
+use crate::resolved::ResolvedProjectConfig;
+use crate::GraphQLProjectConfiguration;
+
+/// A tool-specific configuration living under a key of `extensions`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::extension::Extension;
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Codegen {
+///     output: String,
+/// }
+///
+/// impl Extension for Codegen {
+///     const NAME: &'static str = "codegen";
+/// }
+///
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "extensions": { "codegen": { "output": "./generated" } }
+/// }))?;
+///
+/// let codegen = config.root.extension::<Codegen>().unwrap()?;
+/// assert_eq!(codegen.output, "./generated");
+/// # Ok(())
+/// # }
+/// ```
+pub trait Extension: Serialize + DeserializeOwned {
+    /// The key of the extension inside `extensions`.
+    const NAME: &'static str;
+
+    /// A JSON Schema describing the extension, included in the schema
+    /// generated by `json_schema::JsonSchemaGenerator` when the extension is
+    /// registered with it. Defaults to a schema accepting anything.
+    fn json_schema() -> Value {
+        Value::Object(Default::default())
+    }
+}
+
+/// Mutable access to `extensions`, see
+/// `GraphQLProjectConfiguration::extensions_mut`. Dereferences to the map,
+/// which is removed again when it is left empty.
+#[derive(Debug)]
+pub struct ExtensionsMut<'a> {
+    extensions: &'a mut Option<Map<String, Value>>,
+}
+
+impl<'a> Deref for ExtensionsMut<'a> {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Map<String, Value> {
+        self.extensions
+            .as_ref()
+            .expect("the map exists while borrowed")
+    }
+}
+
+impl<'a> DerefMut for ExtensionsMut<'a> {
+    fn deref_mut(&mut self) -> &mut Map<String, Value> {
+        self.extensions
+            .as_mut()
+            .expect("the map exists while borrowed")
+    }
+}
+
+impl<'a> Drop for ExtensionsMut<'a> {
+    fn drop(&mut self) {
+        if self.extensions.as_ref().is_some_and(Map::is_empty) {
+            *self.extensions = None;
+        }
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// The `extensions` map, created if absent.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() {
+    /// let mut project = GraphQLProjectConfiguration::new();
+    ///
+    /// project.extensions_mut().entry("codegen".to_owned()).or_insert(json!({}))["output"] =
+    ///     json!("./generated");
+    ///
+    /// assert_eq!(project.extensions.unwrap()["codegen"], json!({ "output": "./generated" }));
+    /// # }
+    /// ```
+    pub fn extensions_mut(&mut self) -> ExtensionsMut<'_> {
+        self.extensions.get_or_insert_with(Map::new);
+        ExtensionsMut {
+            extensions: &mut self.extensions,
+        }
+    }
+
+    /// Deserializes the extension `E`, returning `None` if it is absent.
+    pub fn extension<E: Extension>(&self) -> Option<Result<E, serde_json::Error>> {
+        self.extensions
+            .as_ref()
+            .and_then(|extensions| extensions.get(E::NAME))
+            .map(|value| serde_json::from_value(value.clone()))
+    }
+
+    /// Serializes `extension` into `extensions`, replacing any previous value.
+    pub fn set_extension<E: Extension>(&mut self, extension: &E) -> Result<(), serde_json::Error> {
+        let value = serde_json::to_value(extension)?;
+        self.extensions
+            .get_or_insert_with(Default::default)
+            .insert(E::NAME.to_owned(), value);
+        Ok(())
+    }
+}
+
+impl ResolvedProjectConfig {
+    /// Deserializes the extension `E` from the merged extensions, returning
+    /// `None` if it is absent.
+    pub fn extension<E: Extension>(&self) -> Option<Result<E, serde_json::Error>> {
+        self.extensions
+            .get(E::NAME)
+            .map(|value| serde_json::from_value(value.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct LastUpdatedAt(u64);
+
+    impl Extension for LastUpdatedAt {
+        const NAME: &'static str = "lastUpdatedAt";
+    }
+
+    #[test]
+    fn it_round_trips_typed_extensions() {
+        let mut project: GraphQLProjectConfiguration =
+            serde_json::from_value(json!({ "schemaPath": "./schema.graphql" })).unwrap();
+        assert!(project.extension::<LastUpdatedAt>().is_none());
+
+        project
+            .set_extension(&LastUpdatedAt(1532367255884))
+            .unwrap();
+
+        assert_eq!(
+            project.extensions,
+            Some(map! { "lastUpdatedAt".to_owned() => json!(1532367255884u64) })
+        );
+        assert_eq!(
+            project.extension::<LastUpdatedAt>().unwrap().unwrap(),
+            LastUpdatedAt(1532367255884)
+        );
+    }
+
+    #[test]
+    fn it_removes_extensions_left_empty() {
+        let mut project = GraphQLProjectConfiguration::new();
+
+        assert!(project.extensions_mut().is_empty());
+        assert_eq!(project.extensions, None);
+
+        project
+            .extensions_mut()
+            .insert("lastUpdatedAt".to_owned(), json!(1));
+        crate::map::remove(&mut project.extensions_mut(), "lastUpdatedAt");
+        assert_eq!(project.extensions, None);
+    }
+
+    #[test]
+    fn it_reports_malformed_extensions() {
+        let project: GraphQLProjectConfiguration =
+            serde_json::from_value(json!({ "extensions": { "lastUpdatedAt": "yesterday" } }))
+                .unwrap();
+
+        assert!(project.extension::<LastUpdatedAt>().unwrap().is_err());
+    }
+}
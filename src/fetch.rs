@@ -0,0 +1,484 @@
+//! Introspecting the endpoints of every project at once.
+//!
+//! Fetching the schemas of a workspace one endpoint after the other makes
+//! tools slow to start. `fetch_all_schemas` introspects the projects with an
+//! endpoint of a given name on several threads, at most `concurrency` at a
+//! time. The crate has no HTTP client, so the request itself is made by the
+//! function it is given, such as one posting `INTROSPECTION_QUERY` and
+//! converting the result with `introspection::to_sdl`.
+//!
+//! Long-lived gateways and editor daemons keep the schemas up to date with
+//! a `SchemaPoller`, which fetches them again every interval from a thread
+//! of its own and stores those which differ from the cached ones.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::cancel::CancelToken;
+use crate::projects::ProjectName;
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::schema::SchemaPointer;
+use crate::schema_cache::SchemaCache;
+
+/// How many endpoints are introspected at a time, unless told otherwise.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// How often a `SchemaPoller` fetches the schemas, unless told otherwise.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// An endpoint of a project to introspect.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SchemaRequest {
+    /// The name of the project.
+    pub project: ProjectName,
+    /// The URL of the endpoint, with its host rewritten by the `HostMap`.
+    pub url: String,
+    /// The headers to send, see `ResolvedGraphQLConfig::endpoint_headers`.
+    pub headers: Vec<(String, String)>,
+}
+
+/// The outcome of a `SchemaRequest`.
+#[derive(Debug)]
+pub struct SchemaFetch<T, E> {
+    /// The request.
+    pub request: SchemaRequest,
+    /// What the fetch function returned for it.
+    pub result: Result<T, E>,
+}
+
+/// The requests of the projects with the endpoint `endpoint`, in the order
+/// of `ResolvedGraphQLConfig::projects`.
+pub fn schema_requests(config: &ResolvedGraphQLConfig, endpoint: &str) -> Vec<SchemaRequest> {
+    config
+        .projects()
+        .filter_map(|(name, _)| {
+            Some(SchemaRequest {
+                project: name.clone(),
+                url: config.endpoint(name, endpoint)?,
+                headers: config.endpoint_headers(name, endpoint),
+            })
+        })
+        .collect()
+}
+
+/// Calls `fetch` on the request of every project with the endpoint
+/// `endpoint`, from at most `concurrency` threads at a time, and returns the
+/// outcomes in the order of `schema_requests`. A `concurrency` of 0 counts
+/// as 1.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # use graphql_config::*;
+/// # use graphql_config::fetch::{self, SchemaRequest};
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "projects": {
+///         "admin": { "extensions": { "endpoints": { "default": "http://admin/graphql" } } },
+///         "docs": {},
+///         "web": { "extensions": { "endpoints": { "default": "http://web/graphql" } } }
+///     }
+/// }))?;
+/// let config = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+///
+/// let fetches = fetch::fetch_all_schemas(&config, "default", 2, |request: &SchemaRequest| {
+///     Ok::<_, String>(format!("type Query {{ url: String }} # {}", request.url))
+/// });
+/// assert_eq!(fetches.len(), 2);
+/// assert!(fetches.iter().all(|fetch| fetch.result.is_ok()));
+/// # Ok(())
+/// # }
+/// ```
+pub fn fetch_all_schemas<T, E, F>(
+    config: &ResolvedGraphQLConfig,
+    endpoint: &str,
+    concurrency: usize,
+    fetch: F,
+) -> Vec<SchemaFetch<T, E>>
+where
+    T: Send,
+    E: Send,
+    F: Fn(&SchemaRequest) -> Result<T, E> + Sync,
+{
+    fetch_all_schemas_cancellable(config, endpoint, concurrency, &CancelToken::new(), fetch)
+}
+
+/// `fetch_all_schemas`, starting no more requests once `cancel` is
+/// cancelled. The outcomes of the requests left unstarted are left out;
+/// those already started are waited for.
+pub fn fetch_all_schemas_cancellable<T, E, F>(
+    config: &ResolvedGraphQLConfig,
+    endpoint: &str,
+    concurrency: usize,
+    cancel: &CancelToken,
+    fetch: F,
+) -> Vec<SchemaFetch<T, E>>
+where
+    T: Send,
+    E: Send,
+    F: Fn(&SchemaRequest) -> Result<T, E> + Sync,
+{
+    fetch_requests(
+        schema_requests(config, endpoint),
+        concurrency,
+        cancel,
+        &fetch,
+    )
+}
+
+fn fetch_requests<T, E, F>(
+    requests: Vec<SchemaRequest>,
+    concurrency: usize,
+    cancel: &CancelToken,
+    fetch: &F,
+) -> Vec<SchemaFetch<T, E>>
+where
+    T: Send,
+    E: Send,
+    F: Fn(&SchemaRequest) -> Result<T, E> + Sync,
+{
+    let results: Vec<Mutex<Option<Result<T, E>>>> =
+        requests.iter().map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    let workers = concurrency.clamp(1, requests.len().max(1));
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                if cancel.is_cancelled() {
+                    return;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let request = match requests.get(index) {
+                    Some(request) => request,
+                    None => return,
+                };
+                let result = fetch(request);
+                *results[index]
+                    .lock()
+                    .unwrap_or_else(|error| error.into_inner()) = Some(result);
+            });
+        }
+    });
+
+    requests
+        .into_iter()
+        .zip(results)
+        .filter_map(|(request, result)| {
+            let result = result
+                .into_inner()
+                .unwrap_or_else(|error| error.into_inner())?;
+            Some(SchemaFetch { request, result })
+        })
+        .collect()
+}
+
+/// What a `SchemaPoller` saw of a project.
+#[derive(Debug)]
+pub enum SchemaEvent<E> {
+    /// The schema fetched differs from the cached one, if any, and replaced
+    /// it in the cache.
+    Changed {
+        /// The request of the project.
+        request: SchemaRequest,
+        /// The schema fetched.
+        schema: String,
+    },
+    /// The fetch function failed.
+    FetchFailed {
+        /// The request of the project.
+        request: SchemaRequest,
+        /// What the fetch function returned.
+        error: E,
+    },
+    /// The cache could not be read or written.
+    CacheFailed {
+        /// The request of the project.
+        request: SchemaRequest,
+        /// The error of the cache.
+        error: io::Error,
+    },
+}
+
+/// Fetches the schemas of the projects with an endpoint every interval, and
+/// reports those which changed. The thread stops when the poller is
+/// dropped, or when the `CancelToken` of `SchemaPollerBuilder::cancel_token`
+/// is cancelled, without waiting for the end of the interval.
+///
+/// Schemas are cached by the `SchemaPointer::cache_key` of their URL, so
+/// that a `GraphQLCache` sharing the cache reads the latest ones.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # use graphql_config::*;
+/// # use graphql_config::fetch::{SchemaEvent, SchemaPoller, SchemaRequest};
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use graphql_config::schema_cache::MemoryCache;
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// # fn introspect(request: &SchemaRequest) -> Result<String, String> { Err(format!("no client for {}", request.url)) }
+/// # fn main() -> Result<(), serde_json::Error> {
+/// # let config: GraphQLConfiguration = serde_json::from_value(json!({}))?;
+/// # let config = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+/// let cache = Arc::new(MemoryCache::new());
+///
+/// let _poller = SchemaPoller::builder(&config, "default")
+///     .interval(Duration::from_secs(30))
+///     .spawn(cache.clone(), introspect, |event| match event {
+///         SchemaEvent::Changed { request, .. } => println!("{} changed", request.project),
+///         SchemaEvent::FetchFailed { request, error } => eprintln!("{}: {}", request.url, error),
+///         SchemaEvent::CacheFailed { request, error } => eprintln!("{}: {}", request.url, error),
+///     });
+/// # Ok(())
+/// # }
+/// ```
+pub struct SchemaPoller {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SchemaPoller {
+    /// A poller of the endpoints named `endpoint`, with the requests of
+    /// `schema_requests` as they are now.
+    pub fn builder(config: &ResolvedGraphQLConfig, endpoint: &str) -> SchemaPollerBuilder {
+        SchemaPollerBuilder {
+            requests: schema_requests(config, endpoint),
+            interval: DEFAULT_POLL_INTERVAL,
+            concurrency: DEFAULT_CONCURRENCY,
+            cancel: CancelToken::new(),
+        }
+    }
+}
+
+impl Drop for SchemaPoller {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The options of a `SchemaPoller`.
+#[derive(Debug)]
+pub struct SchemaPollerBuilder {
+    requests: Vec<SchemaRequest>,
+    interval: Duration,
+    concurrency: usize,
+    cancel: CancelToken,
+}
+
+impl SchemaPollerBuilder {
+    /// Fetches the schemas every `interval` rather than every
+    /// `DEFAULT_POLL_INTERVAL`.
+    pub fn interval(mut self, interval: Duration) -> SchemaPollerBuilder {
+        self.interval = interval;
+        self
+    }
+
+    /// Fetches at most `concurrency` schemas at a time rather than
+    /// `DEFAULT_CONCURRENCY`.
+    pub fn concurrency(mut self, concurrency: usize) -> SchemaPollerBuilder {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Stops polling, and starts no more fetches, once `cancel` is
+    /// cancelled.
+    pub fn cancel_token(mut self, cancel: CancelToken) -> SchemaPollerBuilder {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Fetches the schemas with `fetch` right away, then every interval,
+    /// storing those which changed in `cache` and giving what came of each
+    /// fetch but unchanged schemas to `on_event`.
+    pub fn spawn<C, E, F, H>(self, cache: C, fetch: F, mut on_event: H) -> SchemaPoller
+    where
+        C: SchemaCache + Send + 'static,
+        E: Send + 'static,
+        F: Fn(&SchemaRequest) -> Result<String, E> + Send + Sync + 'static,
+        H: FnMut(SchemaEvent<E>) + Send + 'static,
+    {
+        let SchemaPollerBuilder {
+            requests,
+            interval,
+            concurrency,
+            cancel,
+        } = self;
+        let (stop, stopped) = mpsc::channel();
+        cancel.notify(stop.clone());
+        let thread = thread::spawn(move || loop {
+            let fetches = fetch_requests(requests.clone(), concurrency, &cancel, &fetch);
+            for fetch in fetches {
+                if let Some(event) = update(&cache, fetch) {
+                    on_event(event);
+                }
+            }
+            match stopped.recv_timeout(interval) {
+                Err(RecvTimeoutError::Timeout) => {}
+                _ => return,
+            }
+        });
+        SchemaPoller {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+/// Stores the schema of `fetch` in `cache` if it differs from the cached
+/// one, and returns what came of it but an unchanged schema.
+fn update<E>(cache: &impl SchemaCache, fetch: SchemaFetch<String, E>) -> Option<SchemaEvent<E>> {
+    let SchemaFetch { request, result } = fetch;
+    let schema = match result {
+        Ok(schema) => schema,
+        Err(error) => return Some(SchemaEvent::FetchFailed { request, error }),
+    };
+    let key = SchemaPointer::Url(request.url.clone()).cache_key();
+    let stored = cache.get(&key).and_then(|cached| match cached {
+        Some(ref cached) if *cached == schema => Ok(false),
+        _ => cache.put(&key, &schema).map(|()| true),
+    });
+    match stored {
+        Ok(false) => None,
+        Ok(true) => Some(SchemaEvent::Changed { request, schema }),
+        Err(error) => Some(SchemaEvent::CacheFailed { request, error }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_cache::MemoryCache;
+    use crate::GraphQLConfiguration;
+    use std::sync::{Arc, Barrier};
+
+    #[test]
+    fn it_fetches_concurrently_within_the_limit() {
+        let projects: serde_json::Map<String, serde_json::Value> = (0..6)
+            .map(|index| {
+                let endpoint = json!({ "url": format!("http://api{}/graphql", index), "headers": { "X-Index": index.to_string() } });
+                (format!("p{}", index), json!({ "extensions": { "endpoints": { "default": endpoint } } }))
+            })
+            .collect();
+        let config: GraphQLConfiguration =
+            ::serde_json::from_value(json!({ "projects": projects })).unwrap();
+        let config = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+
+        let running = AtomicUsize::new(0);
+        let most = AtomicUsize::new(0);
+        let overlapping = Barrier::new(3);
+        let fetches = fetch_all_schemas(&config, "default", 3, |request| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            most.fetch_max(now, Ordering::SeqCst);
+            overlapping.wait();
+            running.fetch_sub(1, Ordering::SeqCst);
+            match request.headers[0].1.as_str() {
+                "5" => Err("unreachable"),
+                _ => Ok(request.url.clone()),
+            }
+        });
+
+        assert!(most.load(Ordering::SeqCst) <= 3);
+        assert_eq!(fetches.len(), 6);
+        let result = |project: &str| {
+            let fetch = fetches
+                .iter()
+                .find(|fetch| fetch.request.project == project);
+            fetch.unwrap().result.clone()
+        };
+        assert_eq!(result("p0"), Ok("http://api0/graphql".to_owned()));
+        assert_eq!(result("p5"), Err("unreachable"));
+
+        let cancel = CancelToken::new();
+        let fetches = fetch_all_schemas_cancellable(&config, "default", 1, &cancel, |request| {
+            cancel.cancel();
+            Ok::<_, ()>(request.url.clone())
+        });
+        assert_eq!(fetches.len(), 1);
+    }
+
+    #[test]
+    fn it_polls_for_changed_schemas() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "extensions": { "endpoints": { "default": "http://api/graphql" } }
+        }))
+        .unwrap();
+        let config = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+        let cache = Arc::new(MemoryCache::new());
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let (events, received) = mpsc::channel();
+
+        let poller = {
+            let fetches = fetches.clone();
+            SchemaPoller::builder(&config, "default")
+                .interval(Duration::from_millis(10))
+                .spawn(
+                    cache.clone(),
+                    move |_: &SchemaRequest| match fetches.fetch_add(1, Ordering::SeqCst) {
+                        0 | 1 => Ok("type Query { a: Int }".to_owned()),
+                        2 => Err("unreachable"),
+                        _ => Ok("type Query { b: Int }".to_owned()),
+                    },
+                    move |event| {
+                        let _ = events.send(match event {
+                            SchemaEvent::Changed { schema, .. } => Ok(schema),
+                            SchemaEvent::FetchFailed { error, .. } => Err(error.to_owned()),
+                            SchemaEvent::CacheFailed { error, .. } => Err(error.to_string()),
+                        });
+                    },
+                )
+        };
+        let timeout = Duration::from_secs(5);
+        let next = || received.recv_timeout(timeout).unwrap();
+        assert_eq!(next(), Ok("type Query { a: Int }".to_owned()));
+        assert_eq!(next(), Err("unreachable".to_owned()));
+        assert_eq!(next(), Ok("type Query { b: Int }".to_owned()));
+        drop(poller);
+        assert_eq!(
+            cache.get("url:http://api/graphql").unwrap(),
+            Some("type Query { b: Int }".to_owned())
+        );
+    }
+
+    #[test]
+    fn it_stops_polling_once_cancelled() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "extensions": { "endpoints": { "default": "http://api/graphql" } }
+        }))
+        .unwrap();
+        let config = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+        let cancel = CancelToken::new();
+        let (events, received) = mpsc::channel();
+
+        let _poller = SchemaPoller::builder(&config, "default")
+            .cancel_token(cancel.clone())
+            .spawn(
+                MemoryCache::new(),
+                |_: &SchemaRequest| Ok::<_, ()>("type Query { a: Int }".to_owned()),
+                move |_| {
+                    let _ = events.send(());
+                },
+            );
+        let timeout = Duration::from_secs(5);
+        assert_eq!(received.recv_timeout(timeout), Ok(()));
+        cancel.cancel();
+        // The thread drops `on_event` as it stops, long before the interval
+        // of `DEFAULT_POLL_INTERVAL` is over.
+        assert_eq!(
+            received.recv_timeout(timeout),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    }
+}
@@ -0,0 +1,194 @@
+//! A C interface to loading configurations and finding the project of a
+//! file, for editors and tools which are not written in Rust.
+//!
+//! The functions are built into a C library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or
+//! `staticlib`), and declared by `include/graphql_config.h`:
+//!
+//! ```c
+//! GraphQLConfig *config = graphql_config_load("/repo/.graphqlconfig");
+//! if (config == NULL) {
+//!     fprintf(stderr, "%s\n", graphql_config_last_error());
+//!     return 1;
+//! }
+//! char *project = graphql_config_project_for_file(config, "/repo/src/query.graphql");
+//! if (project != NULL) {
+//!     printf("%s\n", project);
+//!     graphql_config_string_free(project);
+//! }
+//! graphql_config_free(config);
+//! ```
+//!
+//! Strings are UTF-8 and nul-terminated. Functions returning a pointer
+//! return null on failure, and the reason is kept for
+//! `graphql_config_last_error` until the next failure on the same thread.
+
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::path::Path;
+use std::ptr;
+
+use crate::index::FileIndex;
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::GraphQLConfiguration;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A loaded configuration, opaque to C.
+#[derive(Debug)]
+pub struct GraphQLConfig {
+    index: FileIndex,
+}
+
+/// Loads the configuration file at `path`, returning null if it cannot be
+/// read or is invalid. The configuration is freed with
+/// `graphql_config_free`.
+///
+/// # Safety
+///
+/// `path` is a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn graphql_config_load(path: *const c_char) -> *mut GraphQLConfig {
+    let path = match string(path) {
+        Some(path) => Path::new(path),
+        None => return ptr::null_mut(),
+    };
+    match GraphQLConfiguration::try_from(path) {
+        Ok(config) => Box::into_raw(Box::new(GraphQLConfig {
+            index: FileIndex::new(&ResolvedGraphQLConfig::new(config, path)),
+        })),
+        Err(error) => {
+            set_last_error(format!("{}: {}", path.display(), error));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The name of the project `path` belongs to, see
+/// `FileIndex::project_for_file`, or null if it belongs to none. The name is
+/// freed with `graphql_config_string_free`.
+///
+/// # Safety
+///
+/// `config` was returned by `graphql_config_load` and not freed yet, and
+/// `path` is a valid nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn graphql_config_project_for_file(
+    config: *const GraphQLConfig,
+    path: *const c_char,
+) -> *mut c_char {
+    let config = match config.as_ref() {
+        Some(config) => config,
+        None => {
+            set_last_error("the configuration is null".to_owned());
+            return ptr::null_mut();
+        }
+    };
+    let path = match string(path) {
+        Some(path) => path,
+        None => return ptr::null_mut(),
+    };
+    match config.index.project_for_file(path) {
+        Some(project) => CString::new(project).map_or(ptr::null_mut(), CString::into_raw),
+        None => {
+            set_last_error(format!("`{}` belongs to no project", path));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Frees a configuration returned by `graphql_config_load`. Null is
+/// ignored.
+///
+/// # Safety
+///
+/// `config` was returned by `graphql_config_load` and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn graphql_config_free(config: *mut GraphQLConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// Frees a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `string` was returned by this library and not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn graphql_config_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Why the last function returning null on this thread failed, or null if
+/// none did. The string belongs to the library and is valid until the next
+/// failure.
+#[no_mangle]
+pub extern "C" fn graphql_config_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| {
+        error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |error| error.as_ptr())
+    })
+}
+
+fn set_last_error(error: String) {
+    let error = CString::new(error.replace('\0', "")).expect("nul bytes are removed");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+}
+
+unsafe fn string<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        set_last_error("a string argument is null".to_owned());
+        return None;
+    }
+    match CStr::from_ptr(string).to_str() {
+        Ok(string) => Some(string),
+        Err(error) => {
+            set_last_error(format!("a string argument is not UTF-8: {}", error));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn it_finds_projects_through_the_c_interface() {
+        let dir = TestDir::new("it_finds_projects_through_the_c_interface");
+        let path = dir.join(".graphqlconfig");
+        fs::write(
+            &path,
+            r#"{ "projects": { "app": { "includes": ["src/**/*.graphql"] } } }"#,
+        )
+        .unwrap();
+
+        let c = |string: &str| CString::new(string).unwrap();
+        unsafe {
+            let missing = graphql_config_load(c("/nonexistent/.graphqlconfig").as_ptr());
+            assert!(missing.is_null());
+            assert!(!graphql_config_last_error().is_null());
+
+            let config = graphql_config_load(c(path.to_str().unwrap()).as_ptr());
+            assert!(!config.is_null());
+
+            let project = graphql_config_project_for_file(config, c("src/query.graphql").as_ptr());
+            assert_eq!(CStr::from_ptr(project).to_str(), Ok("app"));
+            graphql_config_string_free(project);
+            assert!(graphql_config_project_for_file(config, c("README.md").as_ptr()).is_null());
+
+            graphql_config_free(config);
+        }
+    }
+}
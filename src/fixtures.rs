@@ -0,0 +1,221 @@
+//! The example configurations of the specification, for testing tools
+//! against canonical inputs.
+//!
+//! Each `Fixture` holds an example as JSON and as YAML, along with the
+//! `GraphQLConfiguration` both parse into:
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # extern crate serde_json;
+//! # use graphql_config::*;
+//! # use graphql_config::fixtures;
+//! # fn main() -> Result<(), serde_json::Error> {
+//! for fixture in fixtures::FIXTURES {
+//!     let config: GraphQLConfiguration = serde_json::from_str(fixture.json)?;
+//!     assert_eq!(config, fixture.expected(), "{}", fixture.name);
+//! }
+//!
+//! let endpoints = fixtures::fixture("endpoints").unwrap();
+//! assert!(endpoints.json.contains("${env:AUTH_TOKEN_ENV}"));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The examples are those of version 2.0.1 of the specification, with the
+//! paths it leaves as placeholders filled in.
+
+use crate::GraphQLConfiguration;
+
+/// An example configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct Fixture {
+    /// The name of the example, as `fixture` takes it.
+    pub name: &'static str,
+    /// What the example shows.
+    pub description: &'static str,
+    /// The example as a `.graphqlconfig` file.
+    pub json: &'static str,
+    /// The example as a `.graphqlconfig.yaml` file.
+    pub yaml: &'static str,
+    expected: fn() -> GraphQLConfiguration,
+}
+
+impl Fixture {
+    /// The configuration `json` and `yaml` parse into.
+    pub fn expected(&self) -> GraphQLConfiguration {
+        (self.expected)()
+    }
+}
+
+/// Every example, in the order of the specification.
+pub const FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "schema-path",
+        description: "The simplest configuration: the schema of a single project.",
+        json: r#"{
+  "schemaPath": "./schema.graphql"
+}
+"#,
+        yaml: "schemaPath: ./schema.graphql\n",
+        expected: || build(GraphQLConfiguration::builder().schema_path("./schema.graphql")),
+    },
+    Fixture {
+        name: "includes-and-excludes",
+        description: "The files belonging to a project, and those which do not.",
+        json: r#"{
+  "schemaPath": "./schema.graphql",
+  "includes": ["./projectA/graphql/*.graphql"],
+  "excludes": ["./projectA/graphql/*.not_graphql"]
+}
+"#,
+        yaml: r#"schemaPath: ./schema.graphql
+includes:
+  - ./projectA/graphql/*.graphql
+excludes:
+  - ./projectA/graphql/*.not_graphql
+"#,
+        expected: || {
+            build(
+                GraphQLConfiguration::builder()
+                    .schema_path("./schema.graphql")
+                    .include("./projectA/graphql/*.graphql")
+                    .exclude("./projectA/graphql/*.not_graphql"),
+            )
+        },
+    },
+    Fixture {
+        name: "projects",
+        description: "Several projects in one workspace.",
+        json: r#"{
+  "projects": {
+    "projectA": {
+      "schemaPath": "./projectA/schema.graphql",
+      "includes": ["./projectA/graphql/*.graphql"]
+    },
+    "projectB": {
+      "schemaPath": "./projectB/schema.graphql",
+      "includes": ["./projectB/graphql/*.graphql"]
+    }
+  }
+}
+"#,
+        yaml: r#"projects:
+  projectA:
+    schemaPath: ./projectA/schema.graphql
+    includes:
+      - ./projectA/graphql/*.graphql
+  projectB:
+    schemaPath: ./projectB/schema.graphql
+    includes:
+      - ./projectB/graphql/*.graphql
+"#,
+        expected: || {
+            build(
+                GraphQLConfiguration::builder()
+                    .project("projectA", |project| {
+                        project
+                            .schema_path("./projectA/schema.graphql")
+                            .include("./projectA/graphql/*.graphql")
+                    })
+                    .project("projectB", |project| {
+                        project
+                            .schema_path("./projectB/schema.graphql")
+                            .include("./projectB/graphql/*.graphql")
+                    }),
+            )
+        },
+    },
+    Fixture {
+        name: "extensions",
+        description: "Tool-specific configuration in the reserved `extensions` namespace.",
+        json: r#"{
+  "schemaPath": "./schema.graphql",
+  "extensions": {
+    "lastUpdatedAt": 1532367255884
+  }
+}
+"#,
+        yaml: r#"schemaPath: ./schema.graphql
+extensions:
+  lastUpdatedAt: 1532367255884
+"#,
+        expected: || {
+            build(
+                GraphQLConfiguration::builder()
+                    .schema_path("./schema.graphql")
+                    .extension("lastUpdatedAt", serde_json::json!(1532367255884u64)),
+            )
+        },
+    },
+    Fixture {
+        name: "endpoints",
+        description: "The `endpoints` extension, with a URL and with an object.",
+        json: r#"{
+  "schemaPath": "./schema.graphql",
+  "extensions": {
+    "endpoints": {
+      "dev": "http://localhost:3000/graphql",
+      "prod": {
+        "url": "https://your-app.com/graphql",
+        "headers": {
+          "Authorization": "Bearer ${env:AUTH_TOKEN_ENV}"
+        }
+      }
+    }
+  }
+}
+"#,
+        yaml: r#"schemaPath: ./schema.graphql
+extensions:
+  endpoints:
+    dev: http://localhost:3000/graphql
+    prod:
+      url: https://your-app.com/graphql
+      headers:
+        Authorization: Bearer ${env:AUTH_TOKEN_ENV}
+"#,
+        expected: || {
+            let endpoints = serde_json::json!({
+                "dev": "http://localhost:3000/graphql",
+                "prod": {
+                    "url": "https://your-app.com/graphql",
+                    "headers": { "Authorization": "Bearer ${env:AUTH_TOKEN_ENV}" }
+                }
+            });
+            build(
+                GraphQLConfiguration::builder()
+                    .schema_path("./schema.graphql")
+                    .extension("endpoints", endpoints),
+            )
+        },
+    },
+];
+
+/// The example named `name`.
+pub fn fixture(name: &str) -> Option<&'static Fixture> {
+    FIXTURES.iter().find(|fixture| fixture.name == name)
+}
+
+fn build(builder: crate::builder::GraphQLConfigurationBuilder) -> GraphQLConfiguration {
+    builder.build().expect("the examples are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fixture_parses_into_what_it_expects() {
+        for fixture in FIXTURES {
+            let json: GraphQLConfiguration = ::serde_json::from_str(fixture.json).unwrap();
+            assert_eq!(json, fixture.expected(), "{}", fixture.name);
+            #[cfg(feature = "yaml")]
+            {
+                let yaml: GraphQLConfiguration = ::serde_yaml::from_str(fixture.yaml).unwrap();
+                assert_eq!(yaml, fixture.expected(), "{}", fixture.name);
+            }
+        }
+        assert!(fixture("projects").is_some());
+        assert!(fixture("unknown").is_none());
+    }
+}
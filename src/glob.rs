@@ -0,0 +1,641 @@
+//! Matching of the glob patterns used by `includes` and `excludes`.
+//!
+//! Patterns are matched against `/`-separated paths relative to the directory
+//! of the configuration file. The syntax is the one of the glob libraries the
+//! JavaScript tooling uses:
+//!
+//! - `*` matches any characters but `/`,
+//! - `**` as a whole segment matches any number of directories,
+//! - `?` matches a single character but `/`,
+//! - `[abc]`, `[a-z]` and `[!abc]` match a single character of a class,
+//! - `{a,b}` matches either alternative,
+//! - `\` escapes the next character.
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "fs")]
+use std::io;
+use std::ops::Deref;
+#[cfg(feature = "fs")]
+use std::path::PathBuf;
+use std::path::{Component, Path};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A pattern of `includes` or `excludes`.
+///
+/// Patterns are validated on construction, and configurations with malformed
+/// patterns fail to deserialize. The pattern is compiled once into an
+/// automaton with a few states per character, alternations included, so
+/// matching it against many paths does not parse it again. Matching never
+/// backtracks: a path is read once, in time proportional to the product of
+/// the lengths of the pattern and the path. Patterns serialize, compare and
+/// hash as the string they were written as, and clones share the compiled
+/// pattern.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::glob::GlobPattern;
+/// # fn main() -> Result<(), graphql_config::glob::PatternError> {
+/// let pattern = GlobPattern::new("./src/**/*.{graphql,gql}")?;
+///
+/// assert!(pattern.matches("src/components/query.gql"));
+/// assert!(!pattern.matches("src/index.js"));
+/// assert!(GlobPattern::new("./src/[a-z").is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(try_from = "String", into = "String")]
+pub struct GlobPattern {
+    pattern: Arc<str>,
+    /// The states of the automaton, starting with the first one and ending
+    /// with the only `State::Match`.
+    compiled: Arc<[State]>,
+}
+
+/// A state of a compiled pattern.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum State {
+    /// Reads a character the `Read` accepts and moves to the state.
+    Read(Read, usize),
+    /// Moves to every one of the states without reading a character, such as
+    /// into each alternative of an alternation.
+    Split(Vec<usize>),
+    /// The path matched.
+    Match,
+}
+
+/// The characters a `State::Read` accepts.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Read {
+    /// This character.
+    Char(char),
+    /// Any character but `/`, for `?` and `*`.
+    NotSlash,
+    /// Any character, for `**`.
+    Any,
+    /// `[...]`, a character of the ranges, or of none of them if negated,
+    /// but `/`.
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+/// The error returned for a malformed pattern, which is carried by every
+/// variant.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PatternError {
+    /// A `[` is never closed.
+    UnclosedClass(String),
+    /// A `{` is never closed.
+    UnclosedAlternation(String),
+    /// A `}` closes no `{`.
+    UnmatchedBrace(String),
+    /// The pattern ends with a `\` escaping nothing.
+    TrailingEscape(String),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (pattern, reason) = match *self {
+            PatternError::UnclosedClass(ref pattern) => (pattern, "unclosed `[`"),
+            PatternError::UnclosedAlternation(ref pattern) => (pattern, "unclosed `{`"),
+            PatternError::UnmatchedBrace(ref pattern) => (pattern, "unmatched `}`"),
+            PatternError::TrailingEscape(ref pattern) => (pattern, "trailing `\\`"),
+        };
+        write!(f, "invalid pattern `{}`: {}", pattern, reason)
+    }
+}
+
+impl Error for PatternError {}
+
+impl GlobPattern {
+    /// Validates and compiles `pattern`.
+    pub fn new(pattern: impl Into<String>) -> Result<GlobPattern, PatternError> {
+        let pattern = pattern.into();
+        validate(&pattern)?;
+        let chars: Vec<char> = strip_current_dir(&pattern).chars().collect();
+        let compiled = compile(&chars).into();
+        Ok(GlobPattern {
+            pattern: pattern.into(),
+            compiled,
+        })
+    }
+
+    /// The pattern as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Unwraps the pattern.
+    pub fn into_string(self) -> String {
+        self.pattern.to_string()
+    }
+
+    /// Whether the `/`-separated `path` matches the pattern. A leading `./`
+    /// of either is ignored.
+    pub fn matches(&self, path: &str) -> bool {
+        match_states(&self.compiled, strip_current_dir(path))
+    }
+
+    /// Whether some file under the `/`-separated directory `dir` may match
+    /// the pattern, judging only by the directories the pattern starts with
+    /// before any wildcard. A `false` is certain, a `true` is not.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::glob::GlobPattern;
+    /// # fn main() -> Result<(), graphql_config::glob::PatternError> {
+    /// let pattern = GlobPattern::new("./src/components/**/*.graphql")?;
+    ///
+    /// assert!(pattern.may_match_under("src"));
+    /// assert!(pattern.may_match_under("src/components/button"));
+    /// assert!(!pattern.may_match_under("lib"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn may_match_under(&self, dir: &str) -> bool {
+        let mut literal = strip_current_dir(&self.pattern).split('/');
+        // The last segment names files, not directories.
+        literal.next_back();
+        literal
+            .take_while(|segment| !segment.contains(|c| "*?[{\\".contains(c)))
+            .zip(
+                strip_current_dir(dir)
+                    .split('/')
+                    .filter(|segment| !segment.is_empty()),
+            )
+            .all(|(literal, segment)| literal == segment)
+    }
+
+    /// The directories the pattern starts with before any wildcard, which
+    /// every file it matches is under, `""` if there are none.
+    #[cfg(feature = "fs")]
+    pub(crate) fn literal_dir(&self) -> &str {
+        let pattern = strip_current_dir(&self.pattern);
+        let mut end = 0;
+        for (index, _) in pattern.match_indices('/') {
+            if pattern[..index].contains(|c| "*?[{\\".contains(c)) {
+                break;
+            }
+            end = index;
+        }
+        &pattern[..end]
+    }
+}
+
+impl Deref for GlobPattern {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl AsRef<str> for GlobPattern {
+    fn as_ref(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl Borrow<str> for GlobPattern {
+    fn borrow(&self) -> &str {
+        &self.pattern
+    }
+}
+
+impl fmt::Display for GlobPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+impl fmt::Debug for GlobPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("GlobPattern").field(&self.pattern).finish()
+    }
+}
+
+impl PartialEq for GlobPattern {
+    fn eq(&self, other: &GlobPattern) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+impl Eq for GlobPattern {}
+
+impl PartialEq<str> for GlobPattern {
+    fn eq(&self, other: &str) -> bool {
+        *self.pattern == *other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for GlobPattern {
+    fn eq(&self, other: &&'a str) -> bool {
+        *self.pattern == **other
+    }
+}
+
+impl PartialOrd for GlobPattern {
+    fn partial_cmp(&self, other: &GlobPattern) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GlobPattern {
+    fn cmp(&self, other: &GlobPattern) -> Ordering {
+        self.pattern.cmp(&other.pattern)
+    }
+}
+
+impl Hash for GlobPattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.pattern.hash(state);
+    }
+}
+
+impl FromStr for GlobPattern {
+    type Err = PatternError;
+
+    fn from_str(pattern: &str) -> Result<GlobPattern, PatternError> {
+        GlobPattern::new(pattern)
+    }
+}
+
+impl TryFrom<String> for GlobPattern {
+    type Error = PatternError;
+
+    fn try_from(pattern: String) -> Result<GlobPattern, PatternError> {
+        GlobPattern::new(pattern)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for GlobPattern {
+    type Error = PatternError;
+
+    fn try_from(pattern: &'a str) -> Result<GlobPattern, PatternError> {
+        GlobPattern::new(pattern)
+    }
+}
+
+impl From<GlobPattern> for String {
+    fn from(pattern: GlobPattern) -> String {
+        pattern.into_string()
+    }
+}
+
+/// Checks that all classes and alternations of `pattern` are closed and that
+/// it does not end with a lone `\`.
+pub(crate) fn validate(pattern: &str) -> Result<(), PatternError> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 == chars.len() => {
+                return Err(PatternError::TrailingEscape(pattern.to_owned()))
+            }
+            '\\' => i += 1,
+            '[' => match class_end(&chars, i) {
+                Some(end) => i = end,
+                None => return Err(PatternError::UnclosedClass(pattern.to_owned())),
+            },
+            '{' => depth += 1,
+            '}' if depth == 0 => return Err(PatternError::UnmatchedBrace(pattern.to_owned())),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    if depth > 0 {
+        return Err(PatternError::UnclosedAlternation(pattern.to_owned()));
+    }
+    Ok(())
+}
+
+/// Whether `path` matches `pattern`. A leading `./` of the pattern is ignored.
+#[cfg(test)]
+fn matches(pattern: &str, path: &str) -> bool {
+    GlobPattern::new(pattern).unwrap().matches(path)
+}
+
+/// Joins the normal components of `path` with `/`, or returns `None` if the
+/// path leaves its base directory or is not valid UTF-8.
+pub(crate) fn to_slash(path: &Path) -> Option<String> {
+    let mut segments = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => segments.push(segment.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(segments.join("/"))
+}
+
+/// The files under `dir` whose paths relative to it match `pattern`, sorted.
+/// Hidden files and directories, whose names start with `.`, and symbolic
+/// links are skipped.
+#[cfg(feature = "fs")]
+pub(crate) fn find_files(dir: &Path, pattern: &GlobPattern) -> io::Result<Vec<PathBuf>> {
+    fn walk(
+        base: &Path,
+        dir: &Path,
+        pattern: &GlobPattern,
+        files: &mut Vec<PathBuf>,
+    ) -> io::Result<()> {
+        for entry in ::std::fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            let relative = match path.strip_prefix(base).ok().and_then(to_slash) {
+                Some(relative) => relative,
+                None => continue,
+            };
+            if file_type.is_dir() {
+                if pattern.may_match_under(&relative) {
+                    walk(base, &path, pattern, files)?;
+                }
+            } else if file_type.is_file() && pattern.matches(&relative) {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    walk(dir, dir, pattern, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn strip_current_dir(mut value: &str) -> &str {
+    while value.starts_with("./") {
+        value = value[2..].trim_start_matches('/');
+    }
+    value
+}
+
+/// Compiles a validated pattern into the states of its automaton.
+///
+/// An alternation becomes a `State::Split` into its alternatives, each of
+/// which ends with a `State::Split` to the state after the alternation, so
+/// that the automaton grows with the length of the pattern rather than with
+/// the number of combinations of its alternatives. Nested alternations are
+/// tracked on a stack rather than by recursion.
+fn compile(pattern: &[char]) -> Vec<State> {
+    /// An alternation not closed yet: its `State::Split` and the states
+    /// ending its alternatives so far.
+    struct Alternation {
+        split: usize,
+        ends: Vec<usize>,
+    }
+
+    fn end_alternative(states: &mut Vec<State>, alternation: &mut Alternation) {
+        alternation.ends.push(states.len());
+        states.push(State::Split(Vec::new()));
+    }
+
+    let mut states = Vec::new();
+    let mut alternations: Vec<Alternation> = Vec::new();
+    let mut i = 0;
+    while i < pattern.len() {
+        let next = states.len() + 1;
+        match pattern[i] {
+            '*' if pattern.get(i + 1) == Some(&'*') => {
+                i += 1;
+                if pattern.get(i + 1) == Some(&'/') {
+                    i += 1;
+                    // Zero directories, or any characters up to a `/`, and
+                    // again.
+                    let after = next + 3;
+                    states.push(State::Split(vec![next, after]));
+                    states.push(State::Split(vec![next + 1, next + 2]));
+                    states.push(State::Read(Read::Any, next));
+                    states.push(State::Read(Read::Char('/'), after));
+                } else {
+                    states.push(State::Split(vec![next, next + 1]));
+                    states.push(State::Read(Read::Any, next - 1));
+                }
+            }
+            '*' => {
+                states.push(State::Split(vec![next, next + 1]));
+                states.push(State::Read(Read::NotSlash, next - 1));
+            }
+            '?' => states.push(State::Read(Read::NotSlash, next)),
+            '[' => match class_end(pattern, i) {
+                Some(end) => {
+                    states.push(State::Read(compile_class(&pattern[i + 1..end]), next));
+                    i = end;
+                }
+                None => states.push(State::Read(Read::Char('['), next)),
+            },
+            '{' => {
+                alternations.push(Alternation {
+                    split: states.len(),
+                    ends: Vec::new(),
+                });
+                states.push(State::Split(vec![next]));
+            }
+            ',' if !alternations.is_empty() => {
+                let mut alternation = alternations.pop().expect("an open alternation");
+                end_alternative(&mut states, &mut alternation);
+                let start = states.len();
+                if let State::Split(ref mut starts) = states[alternation.split] {
+                    starts.push(start);
+                }
+                alternations.push(alternation);
+            }
+            '}' if !alternations.is_empty() => {
+                let mut alternation = alternations.pop().expect("an open alternation");
+                end_alternative(&mut states, &mut alternation);
+                let after = states.len();
+                for end in alternation.ends {
+                    states[end] = State::Split(vec![after]);
+                }
+            }
+            '\\' if i + 1 < pattern.len() => {
+                i += 1;
+                states.push(State::Read(Read::Char(pattern[i]), next));
+            }
+            c => states.push(State::Read(Read::Char(c), next)),
+        }
+        i += 1;
+    }
+    states.push(State::Match);
+    states
+}
+
+fn compile_class(class: &[char]) -> Read {
+    let (negated, class) = match class.split_first() {
+        Some((&'!', class)) | Some((&'^', class)) => (true, class),
+        _ => (false, class),
+    };
+
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            ranges.push((class[i], class[i + 2]));
+            i += 3;
+        } else {
+            ranges.push((class[i], class[i]));
+            i += 1;
+        }
+    }
+    Read::Class { negated, ranges }
+}
+
+impl Read {
+    fn accepts(&self, c: char) -> bool {
+        match *self {
+            Read::Char(expected) => c == expected,
+            Read::NotSlash => c != '/',
+            Read::Any => true,
+            Read::Class {
+                negated,
+                ref ranges,
+            } => c != '/' && ranges.iter().any(|&(from, to)| from <= c && c <= to) != negated,
+        }
+    }
+}
+
+/// Whether `path` matches the automaton `states`, tracking every state the
+/// characters read so far can lead to rather than backtracking.
+fn match_states(states: &[State], path: &str) -> bool {
+    let mut current = vec![false; states.len()];
+    let mut next = current.clone();
+    let mut pending = Vec::new();
+    enter(states, 0, &mut current, &mut pending);
+
+    for c in path.chars() {
+        for active in next.iter_mut() {
+            *active = false;
+        }
+        for (state, _) in current.iter().enumerate().filter(|&(_, &active)| active) {
+            if let State::Read(ref read, target) = states[state] {
+                if read.accepts(c) {
+                    enter(states, target, &mut next, &mut pending);
+                }
+            }
+        }
+        ::std::mem::swap(&mut current, &mut next);
+        if !current.contains(&true) {
+            return false;
+        }
+    }
+    current[states.len() - 1]
+}
+
+/// Activates `state` and the states reached from it without reading a
+/// character.
+fn enter(states: &[State], state: usize, active: &mut [bool], pending: &mut Vec<usize>) {
+    pending.push(state);
+    while let Some(state) = pending.pop() {
+        if active[state] {
+            continue;
+        }
+        active[state] = true;
+        if let State::Split(ref targets) = states[state] {
+            pending.extend(targets.iter().filter(|&&target| !active[target]));
+        }
+    }
+}
+
+/// The index of the `]` closing the class opened at `start`.
+fn class_end(pattern: &[char], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    if i < pattern.len() && (pattern[i] == '!' || pattern[i] == '^') {
+        i += 1;
+    }
+    // A `]` right after the opening bracket is part of the class.
+    if i < pattern.len() && pattern[i] == ']' {
+        i += 1;
+    }
+    (i..pattern.len()).find(|&i| pattern[i] == ']')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_wildcards() {
+        assert!(matches("./src/*.graphql", "src/query.graphql"));
+        assert!(!matches("./src/*.graphql", "src/nested/query.graphql"));
+        assert!(matches("src/**/*.graphql", "src/query.graphql"));
+        assert!(matches("src/**/*.graphql", "src/a/b/query.graphql"));
+        assert!(matches("**", "anything/at/all"));
+        assert!(matches("src/quer?.graphql", "src/query.graphql"));
+        assert!(!matches("src/?", "src//"));
+    }
+
+    #[test]
+    fn it_matches_classes_and_alternatives() {
+        assert!(matches("[a-c].graphql", "b.graphql"));
+        assert!(!matches("[!a-c].graphql", "b.graphql"));
+        assert!(matches("*.{graphql,gql}", "query.gql"));
+        assert!(matches("{app,lib/{a,b}}/*.js", "lib/b/index.js"));
+        assert!(!matches("*.{graphql,gql}", "query.js"));
+        assert!(matches("\\*.graphql", "*.graphql"));
+        assert!(!matches("\\*.graphql", "a.graphql"));
+    }
+
+    #[test]
+    fn it_matches_long_runs_of_wildcards_quickly() {
+        let pattern = format!("{}b", "*".repeat(30));
+        assert!(!matches(&pattern, &"a".repeat(200)));
+        let pattern = format!("{}b", "**/".repeat(30));
+        assert!(!matches(&pattern, &"a/".repeat(200)));
+        assert!(matches("**/**/**/*.graphql", "a/b/c/d/query.graphql"));
+        assert!(matches("src/**", "src/"));
+        assert!(matches("{a,b}/{c,d}/x", "b/d/x"));
+    }
+
+    #[test]
+    fn it_compiles_many_alternations_into_few_states() {
+        let pattern = GlobPattern::new("{a,b}".repeat(40)).unwrap();
+        assert!(pattern.compiled.len() < 40 * 8);
+        assert!(pattern.matches(&"ab".repeat(20)));
+        assert!(!pattern.matches(&"ab".repeat(21)));
+        assert!(!pattern.matches(&"c".repeat(40)));
+
+        let nested = format!("{}x{}", "{a,".repeat(1000), "}".repeat(1000));
+        let nested = GlobPattern::new(nested).unwrap();
+        assert!(nested.matches("a") && nested.matches("x") && !nested.matches("ax"));
+        assert!(matches("{,src/}*.graphql", "src/query.graphql"));
+        assert!(matches("{src/**/,}*.graphql", "query.graphql"));
+    }
+
+    #[test]
+    fn it_validates_patterns() {
+        assert_eq!(validate("src/**/*.{graphql,gql}"), Ok(()));
+        assert_eq!(validate("src/[}]"), Ok(()));
+        assert_eq!(
+            validate("src/[a-z"),
+            Err(PatternError::UnclosedClass("src/[a-z".to_owned()))
+        );
+        assert!(validate("src/{a,b").is_err());
+        assert!(validate("src/a}").is_err());
+        assert!(validate("src\\").is_err());
+    }
+
+    #[test]
+    fn it_rejects_malformed_patterns_when_deserializing() {
+        let config = ::serde_json::from_value::<crate::GraphQLConfiguration>(json!({
+            "includes": ["./src/*.graphql"],
+            "excludes": ["./src/[generated"]
+        }));
+
+        assert!(config.is_err());
+    }
+}
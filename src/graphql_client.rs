@@ -0,0 +1,292 @@
+//! Inputs for [`graphql_client`](https://crates.io/crates/graphql_client)
+//! code generation.
+//!
+//! `graphql_client` generates Rust code from a schema file and query files,
+//! given to its derive as `schema_path` and `query_path` or to its CLI as
+//! arguments. The conversions here take these from a project, so the
+//! configuration used by the rest of the GraphQL tooling drives Rust code
+//! generation too.
+//!
+//! The options of the derive are read from the `graphqlClient` extension:
+//!
+//! ```json
+//! {
+//!   "schemaPath": "schema.graphql",
+//!   "includes": ["src/**/*.graphql"],
+//!   "extensions": {
+//!     "graphqlClient": {
+//!       "customScalars": { "DateTime": "chrono::DateTime<chrono::Utc>" },
+//!       "responseDerives": ["Debug", "Clone"],
+//!       "variablesDerives": ["Debug"]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! `graphql_client` expects a type named after every custom scalar in scope
+//! of the derive, which `GraphQLClientInputs::scalar_aliases` declares.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::handle::ProjectHandle;
+
+/// The name of the extension holding the options of the derive.
+pub const EXTENSION: &str = "graphqlClient";
+
+/// The paths and options `graphql_client` generates code from.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::graphql_client::GraphQLClientInputs;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use std::convert::TryInto;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = Path::new(".graphqlconfig");
+/// let config = ResolvedGraphQLConfig::from_path(path)?;
+///
+/// let inputs: GraphQLClientInputs = (&config.project_handle("default").unwrap()).try_into()?;
+/// for query_path in &inputs.query_paths {
+///     println!("{} {}", inputs.schema_path.display(), query_path.display());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct GraphQLClientInputs {
+    /// The schema file of the project.
+    pub schema_path: PathBuf,
+    /// The `.graphql` and `.gql` files of the project, sorted.
+    pub query_paths: Vec<PathBuf>,
+    /// The Rust types of custom scalars, by scalar name.
+    pub custom_scalars: BTreeMap<String, String>,
+    /// The traits to derive on response types, besides `Deserialize`.
+    pub response_derives: Vec<String>,
+    /// The traits to derive on variables types, besides `Serialize`.
+    pub variables_derives: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+struct Options {
+    custom_scalars: BTreeMap<String, String>,
+    response_derives: Vec<String>,
+    variables_derives: Vec<String>,
+}
+
+impl GraphQLClientInputs {
+    /// Type aliases naming the Rust type of every custom scalar after it,
+    /// one per line, to be generated next to the derives.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::graphql_client::GraphQLClientInputs;
+    /// # fn main() {
+    /// let mut inputs = GraphQLClientInputs::default();
+    /// inputs.custom_scalars.insert("DateTime".to_owned(), "chrono::DateTime<chrono::Utc>".to_owned());
+    /// assert_eq!(inputs.scalar_aliases(), "type DateTime = chrono::DateTime<chrono::Utc>;\n");
+    /// # }
+    /// ```
+    pub fn scalar_aliases(&self) -> String {
+        self.custom_scalars
+            .iter()
+            .map(|(scalar, rust_type)| format!("type {} = {};\n", scalar, rust_type))
+            .collect()
+    }
+
+    /// The `#[graphql(...)]` attribute of a derive generating code for
+    /// `query_path`, one of `query_paths`.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::graphql_client::GraphQLClientInputs;
+    /// # use std::path::Path;
+    /// # fn main() {
+    /// let mut inputs = GraphQLClientInputs::default();
+    /// inputs.schema_path = "schema.graphql".into();
+    /// inputs.response_derives = vec!["Debug".to_owned(), "Clone".to_owned()];
+    /// assert_eq!(
+    ///     inputs.derive_attribute(Path::new("src/query.graphql")),
+    ///     r#"#[graphql(schema_path = "schema.graphql", query_path = "src/query.graphql", response_derives = "Debug, Clone")]"#
+    /// );
+    /// # }
+    /// ```
+    pub fn derive_attribute(&self, query_path: &Path) -> String {
+        let mut arguments = vec![
+            format!("schema_path = {:?}", self.schema_path.to_string_lossy()),
+            format!("query_path = {:?}", query_path.to_string_lossy()),
+        ];
+        if !self.response_derives.is_empty() {
+            arguments.push(format!(
+                "response_derives = {:?}",
+                self.response_derives.join(", ")
+            ));
+        }
+        if !self.variables_derives.is_empty() {
+            arguments.push(format!(
+                "variables_derives = {:?}",
+                self.variables_derives.join(", ")
+            ));
+        }
+        format!("#[graphql({})]", arguments.join(", "))
+    }
+}
+
+/// The error returned when a project cannot provide `GraphQLClientInputs`.
+#[derive(Debug)]
+pub enum GraphQLClientError {
+    /// The project, named by the variant, has no schema.
+    MissingSchema(String),
+    /// The files of the project could not be listed.
+    Io(io::Error),
+    /// The `graphqlClient` extension of the project, named by the variant,
+    /// is malformed.
+    InvalidExtension(String, serde_json::Error),
+}
+
+impl fmt::Display for GraphQLClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphQLClientError::MissingSchema(ref project) => {
+                write!(f, "project `{}` has no schemaPath", project)
+            }
+            GraphQLClientError::Io(ref error) => {
+                write!(f, "cannot list the query files: {}", error)
+            }
+            GraphQLClientError::InvalidExtension(ref project, ref error) => write!(
+                f,
+                "project `{}`: invalid `{}` extension: {}",
+                project, EXTENSION, error
+            ),
+        }
+    }
+}
+
+impl Error for GraphQLClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            GraphQLClientError::MissingSchema(_) => None,
+            GraphQLClientError::Io(ref error) => Some(error),
+            GraphQLClientError::InvalidExtension(_, ref error) => Some(error),
+        }
+    }
+}
+
+impl<'a, 'b> TryFrom<&'b ProjectHandle<'a>> for GraphQLClientInputs {
+    type Error = GraphQLClientError;
+
+    fn try_from(project: &'b ProjectHandle<'a>) -> Result<GraphQLClientInputs, GraphQLClientError> {
+        let schema_path = project
+            .schema_path()
+            .ok_or_else(|| GraphQLClientError::MissingSchema(project.name().to_owned()))?;
+
+        let query_paths = project
+            .document_files()
+            .map_err(GraphQLClientError::Io)?
+            .into_iter()
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "graphql" || extension == "gql")
+            })
+            .collect();
+
+        let options = match project.config().extensions.get(EXTENSION) {
+            Some(options) => Options::deserialize(options).map_err(|error| {
+                GraphQLClientError::InvalidExtension(project.name().to_owned(), error)
+            })?,
+            None => Options::default(),
+        };
+
+        Ok(GraphQLClientInputs {
+            schema_path,
+            query_paths,
+            custom_scalars: options.custom_scalars,
+            response_derives: options.response_derives,
+            variables_derives: options.variables_derives,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolved::ResolvedGraphQLConfig;
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+    use std::fs;
+
+    #[test]
+    fn it_collects_the_schema_and_query_files() {
+        let dir = TestDir::new("it_collects_the_schema_and_query_files");
+        fs::create_dir_all(dir.join("queries")).unwrap();
+        for file in &[
+            "schema.graphql",
+            "queries/a.gql",
+            "queries/b.graphql",
+            "queries/c.rs",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": {
+                    "schemaPath": "schema.graphql",
+                    "includes": ["queries/**"],
+                    "extensions": { "graphqlClient": {
+                        "customScalars": { "URL": "url::Url" },
+                        "responseDerives": ["Debug"]
+                    } }
+                },
+                "lib": {}
+            }
+        }))
+        .unwrap();
+        let resolved = ResolvedGraphQLConfig::new(config, dir.join(".graphqlconfig"));
+
+        let app = resolved.project_handle("app").unwrap();
+        assert_eq!(
+            GraphQLClientInputs::try_from(&app).unwrap(),
+            GraphQLClientInputs {
+                schema_path: dir.join("schema.graphql"),
+                query_paths: vec![dir.join("queries/a.gql"), dir.join("queries/b.graphql")],
+                custom_scalars: btreemap! { "URL".to_owned() => "url::Url".to_owned() },
+                response_derives: vec!["Debug".to_owned()],
+                variables_derives: Vec::new(),
+            }
+        );
+
+        let lib = resolved.project_handle("lib").unwrap();
+        match GraphQLClientInputs::try_from(&lib) {
+            Err(GraphQLClientError::MissingSchema(ref project)) if project == "lib" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let mut invalid = resolved.raw().clone();
+        invalid
+            .projects
+            .as_mut()
+            .unwrap()
+            .values_mut()
+            .for_each(|project| {
+                project.extensions = Some(
+                    vec![(EXTENSION.to_owned(), json!({ "customScalar": {} }))]
+                        .into_iter()
+                        .collect(),
+                )
+            });
+        let invalid = ResolvedGraphQLConfig::new(invalid, dir.join(".graphqlconfig"));
+        match GraphQLClientInputs::try_from(&invalid.project_handle("app").unwrap()) {
+            Err(GraphQLClientError::InvalidExtension(ref project, _)) if project == "app" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Projects bundled with the directory their paths are relative to.
+
+#[cfg(feature = "fs")]
+use std::fs;
+#[cfg(feature = "fs")]
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::glob;
+use crate::matcher::GlobSet;
+use crate::resolved::{ResolvedGraphQLConfig, ResolvedProjectConfig};
+
+/// A project of a `ResolvedGraphQLConfig`, with the top-level settings
+/// applied, carrying everything needed to work with its files.
+#[derive(Clone, Debug)]
+pub struct ProjectHandle<'a> {
+    name: &'a str,
+    config: ResolvedProjectConfig,
+    dir: &'a Path,
+    matcher: Arc<GlobSet>,
+}
+
+impl<'a> ProjectHandle<'a> {
+    /// The key of the project, or `DEFAULT_PROJECT_NAME` for the top-level
+    /// configuration of a configuration without projects.
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// The configuration of the project.
+    pub fn config(&self) -> &ResolvedProjectConfig {
+        &self.config
+    }
+
+    /// The directory of the configuration file.
+    pub fn dir(&self) -> &'a Path {
+        self.dir
+    }
+
+    /// The patterns `include_file` matches with, shared with the handles of
+    /// projects with the same patterns.
+    pub fn matcher(&self) -> &Arc<GlobSet> {
+        &self.matcher
+    }
+
+    /// The schema file of the project, joined to `dir`.
+    pub fn schema_path(&self) -> Option<PathBuf> {
+        self.config
+            .schema_path
+            .as_ref()
+            .map(|schema_path| self.dir.join(schema_path))
+    }
+
+    /// Reads the schema file of the project.
+    ///
+    /// ```no_run
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::resolved::ResolvedGraphQLConfig;
+    /// # use std::convert::TryFrom;
+    /// # use std::path::Path;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = Path::new("/repo/.graphqlconfig");
+    /// let config = ResolvedGraphQLConfig::new(GraphQLConfiguration::try_from(path)?, path);
+    ///
+    /// for project in config.project_handles() {
+    ///     if project.include_file("/repo/src/query.graphql") {
+    ///         println!("{}", project.read_schema()?);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "fs")]
+    pub fn read_schema(&self) -> io::Result<String> {
+        match self.schema_path() {
+            Some(schema_path) => fs::read_to_string(schema_path),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("project `{}` has no schemaPath", self.name),
+            )),
+        }
+    }
+
+    /// Whether `path` belongs to the project: it is matched by one of the
+    /// `includes`, or there are none, and by none of the `excludes`.
+    ///
+    /// Relative paths are taken as relative to `dir`. Paths outside of `dir`
+    /// never belong to the project.
+    pub fn include_file(&self, path: impl AsRef<Path>) -> bool {
+        relative(self.dir, path.as_ref()).is_some_and(|relative| self.matcher.matches(&relative))
+    }
+
+    /// The files under `dir` belonging to the project, sorted. Hidden files
+    /// and directories, whose names start with `.`, and symbolic links are
+    /// skipped.
+    #[cfg(feature = "fs")]
+    pub fn document_files(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        self.walk(self.dir, &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+
+    #[cfg(feature = "fs")]
+    fn walk(&self, dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                self.walk(&path, files)?;
+            } else if file_type.is_file() && self.include_file(&path) {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `path` relative to `dir` and `/`-separated, if it is under `dir`.
+/// Relative paths are taken as relative to `dir` already.
+pub(crate) fn relative(dir: &Path, path: &Path) -> Option<String> {
+    let relative = if path.is_absolute() {
+        path.strip_prefix(dir).ok()?
+    } else {
+        path
+    };
+    glob::to_slash(relative)
+}
+
+impl ResolvedGraphQLConfig {
+    /// The handle of the project `name`.
+    pub fn project_handle<'a>(&'a self, name: &str) -> Option<ProjectHandle<'a>> {
+        self.projects()
+            .find(|&(key, _)| key == name)
+            .map(|(key, _)| self.handle(key))
+    }
+
+    /// The handles of all projects, as listed by `projects`.
+    pub fn project_handles(&self) -> Vec<ProjectHandle<'_>> {
+        self.projects().map(|(key, _)| self.handle(key)).collect()
+    }
+
+    fn handle<'a>(&'a self, key: &'a str) -> ProjectHandle<'a> {
+        let config = self.project(key).expect("the key was listed by `projects`");
+        ProjectHandle {
+            name: key,
+            matcher: self.matchers().get(&config.includes, &config.excludes),
+            config,
+            dir: self.dir(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "fs")]
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+
+    fn resolved(json: ::serde_json::Value) -> ResolvedGraphQLConfig {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json).unwrap();
+        ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig")
+    }
+
+    #[test]
+    fn it_matches_files_of_the_project() {
+        let config = resolved(json!({
+            "excludes": ["./src/generated/**"],
+            "projects": {
+                "app": { "includes": ["./src/**/*.graphql"] },
+                "lib": {}
+            }
+        }));
+
+        let app = config.project_handle("app").unwrap();
+        assert_eq!(app.name(), "app");
+        assert!(app.include_file("/repo/src/query.graphql"));
+        assert!(app.include_file("src/nested/query.graphql"));
+        assert!(!app.include_file("/repo/src/generated/query.graphql"));
+        assert!(!app.include_file("/elsewhere/src/query.graphql"));
+        assert!(!app.include_file("../src/query.graphql"));
+
+        let lib = config.project_handle("lib").unwrap();
+        assert!(lib.include_file("anything.js"));
+        assert!(Arc::ptr_eq(
+            app.matcher(),
+            config.project_handle("app").unwrap().matcher()
+        ));
+        assert!(config.project_handle("default").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn it_lists_document_files() {
+        let dir = TestDir::new("it_lists_document_files");
+        fs::create_dir_all(dir.join("src/generated")).unwrap();
+        fs::create_dir_all(dir.join(".cache")).unwrap();
+        for file in &[
+            "src/b.graphql",
+            "src/a.graphql",
+            "src/index.js",
+            "src/generated/c.graphql",
+            ".cache/d.graphql",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "includes": ["**/*.graphql"],
+            "excludes": ["src/generated/**"]
+        }))
+        .unwrap();
+        let resolved = ResolvedGraphQLConfig::new(config, dir.join(".graphqlconfig"));
+
+        assert_eq!(
+            resolved
+                .project_handle("default")
+                .unwrap()
+                .document_files()
+                .unwrap(),
+            vec![dir.join("src/a.graphql"), dir.join("src/b.graphql")]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn it_reports_a_missing_schema() {
+        let config = resolved(json!({}));
+        let handles = config.project_handles();
+        assert!(handles.is_empty());
+
+        let config = resolved(json!({ "name": "root" }));
+        let default = config.project_handle("default").unwrap();
+        assert_eq!(default.schema_path(), None);
+        assert_eq!(
+            default.read_schema().unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+}
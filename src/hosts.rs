@@ -0,0 +1,180 @@
+//! Rewriting the hostnames of endpoints.
+//!
+//! Inside a container, `localhost` is the container itself rather than the
+//! machine running the GraphQL server the configuration points at. A
+//! `HostMap` given to `ResolvedGraphQLConfig::with_host_map` rewrites the
+//! hosts of the URLs `ResolvedGraphQLConfig::endpoint` returns, so the same
+//! configuration works on the host and in a devcontainer.
+//!
+//! `HostMap::from_env` reads the map from the `GRAPHQL_CONFIG_HOSTS`
+//! variable, so each environment can set its own: a comma-separated list of
+//! `from=to` pairs, or `docker` for `HostMap::docker`.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::env::Env;
+
+/// The variable `HostMap::from_env` reads.
+pub const HOSTS_VAR: &str = "GRAPHQL_CONFIG_HOSTS";
+
+/// Hostnames to replace, by the hostname they replace.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::hosts::HostMap;
+/// # fn main() -> Result<(), graphql_config::hosts::HostMapError> {
+/// let hosts = HostMap::parse("localhost=host.docker.internal, api=10.0.0.2")?;
+///
+/// assert_eq!(
+///     hosts.remap("http://localhost:4000/graphql"),
+///     "http://host.docker.internal:4000/graphql"
+/// );
+/// assert_eq!(hosts.remap("https://user@api/graphql"), "https://user@10.0.0.2/graphql");
+/// assert_eq!(hosts.remap("https://example.com/graphql"), "https://example.com/graphql");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct HostMap {
+    hosts: BTreeMap<String, String>,
+}
+
+/// A `from=to` pair of a host map is malformed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct HostMapError(pub String);
+
+impl fmt::Display for HostMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` is not a `from=to` pair of hosts", self.0)
+    }
+}
+
+impl Error for HostMapError {}
+
+impl HostMap {
+    /// A map rewriting nothing.
+    pub fn new() -> HostMap {
+        HostMap::default()
+    }
+
+    /// The map of Docker containers, rewriting the loopback hosts to
+    /// `host.docker.internal`.
+    pub fn docker() -> HostMap {
+        HostMap::new()
+            .with("localhost", "host.docker.internal")
+            .with("127.0.0.1", "host.docker.internal")
+            .with("::1", "host.docker.internal")
+    }
+
+    /// Parses a comma-separated list of `from=to` pairs, or `docker`.
+    pub fn parse(spec: &str) -> Result<HostMap, HostMapError> {
+        if spec.trim() == "docker" {
+            return Ok(HostMap::docker());
+        }
+        let mut hosts = HostMap::new();
+        for pair in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            match pair.split_once('=') {
+                Some((from, to)) if !from.trim().is_empty() && !to.trim().is_empty() => {
+                    hosts = hosts.with(from.trim(), to.trim());
+                }
+                _ => return Err(HostMapError(pair.to_owned())),
+            }
+        }
+        Ok(hosts)
+    }
+
+    /// The map of the `GRAPHQL_CONFIG_HOSTS` variable of `env`, rewriting
+    /// nothing if it is not set.
+    pub fn from_env(env: &Env) -> Result<HostMap, HostMapError> {
+        env.var(HOSTS_VAR)
+            .map_or_else(|| Ok(HostMap::new()), |spec| HostMap::parse(&spec))
+    }
+
+    /// Rewrites `from` to `to`, replacing any previous rewrite of `from`.
+    /// Hosts are compared ignoring case, and IPv6 addresses are written
+    /// without brackets.
+    pub fn with(mut self, from: impl Into<String>, to: impl Into<String>) -> HostMap {
+        self.hosts
+            .insert(from.into().to_ascii_lowercase(), to.into());
+        self
+    }
+
+    /// Whether the map rewrites nothing.
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// `url` with its host rewritten, or as it is if the map does not
+    /// rewrite its host or it has none.
+    pub fn remap(&self, url: &str) -> String {
+        let start = match url.find("://") {
+            Some(scheme) => scheme + "://".len(),
+            None => return url.to_owned(),
+        };
+        let end = url[start..]
+            .find(['/', '?', '#'])
+            .map_or(url.len(), |end| start + end);
+        let host_start = url[start..end]
+            .rfind('@')
+            .map_or(start, |at| start + at + 1);
+        let host_end = if url[host_start..end].starts_with('[') {
+            url[host_start..end]
+                .find(']')
+                .map_or(end, |bracket| host_start + bracket + 1)
+        } else {
+            url[host_start..end]
+                .find(':')
+                .map_or(end, |colon| host_start + colon)
+        };
+
+        let host = url[host_start..host_end]
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        match self.hosts.get(&host) {
+            Some(to) if to.contains(':') => {
+                format!("{}[{}]{}", &url[..host_start], to, &url[host_end..])
+            }
+            Some(to) => format!("{}{}{}", &url[..host_start], to, &url[host_end..]),
+            None => url.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_remaps_hosts_from_the_environment() {
+        let env = Env::from_map(vec![(HOSTS_VAR, "docker")]);
+        let hosts = HostMap::from_env(&env).unwrap();
+        assert_eq!(
+            hosts.remap("http://[::1]:4000/graphql?x=1"),
+            "http://host.docker.internal:4000/graphql?x=1"
+        );
+        assert_eq!(
+            hosts.remap("http://LOCALHOST"),
+            "http://host.docker.internal"
+        );
+
+        let hosts = HostMap::new().with("localhost", "fd00::2");
+        assert_eq!(hosts.remap("http://localhost:80/"), "http://[fd00::2]:80/");
+
+        assert!(
+            HostMap::from_env(&Env::from_map(Vec::<(String, String)>::new()))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            HostMap::parse("localhost"),
+            Err(HostMapError("localhost".to_owned()))
+        );
+    }
+}
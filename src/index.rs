@@ -0,0 +1,148 @@
+//! Finding the project of a file among many.
+//!
+//! A `FileIndex` answers which project a file belongs to without matching it
+//! against the patterns of every project. It remembers, for every directory
+//! it was asked about, the projects whose patterns may match files under it,
+//! narrowing down the ones of the parent directory. Files are then only
+//! matched against those, which in a large repository is a handful.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::handle;
+use crate::matcher::GlobSet;
+use crate::resolved::ResolvedGraphQLConfig;
+
+/// The projects of a configuration, indexed by directory.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::index::FileIndex;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "projects": {
+///         "web": { "includes": ["packages/web/**/*.graphql"] },
+///         "admin": { "includes": ["packages/admin/**/*.graphql"] }
+///     }
+/// }))?;
+/// let index = FileIndex::new(&ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig"));
+///
+/// assert_eq!(index.project_for_file("/repo/packages/web/src/query.graphql"), Some("web"));
+/// assert_eq!(index.project_for_file("packages/admin/query.graphql"), Some("admin"));
+/// assert_eq!(index.project_for_file("/repo/README.md"), None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FileIndex {
+    dir: PathBuf,
+    projects: Vec<(String, Arc<GlobSet>)>,
+    /// The indexes into `projects` which may match files under a directory,
+    /// by directory.
+    candidates: Mutex<HashMap<String, Arc<[usize]>>>,
+}
+
+impl FileIndex {
+    /// Indexes the projects of `config`.
+    pub fn new(config: &ResolvedGraphQLConfig) -> FileIndex {
+        FileIndex {
+            dir: config.dir().to_owned(),
+            projects: config
+                .project_handles()
+                .into_iter()
+                .map(|handle| (handle.name().to_owned(), Arc::clone(handle.matcher())))
+                .collect(),
+            candidates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The directory of the configuration file.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The first project, as listed by `ResolvedGraphQLConfig::projects`,
+    /// which `path` belongs to, see `ProjectHandle::include_file`.
+    pub fn project_for_file(&self, path: impl AsRef<Path>) -> Option<&str> {
+        let relative = handle::relative(&self.dir, path.as_ref())?;
+        let dir = relative.rfind('/').map_or("", |end| &relative[..end]);
+
+        self.candidates(dir)
+            .iter()
+            .map(|&candidate| &self.projects[candidate])
+            .find(|(_, matcher)| matcher.matches(&relative))
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn candidates(&self, dir: &str) -> Arc<[usize]> {
+        let cached = {
+            let candidates = self
+                .candidates
+                .lock()
+                .unwrap_or_else(|error| error.into_inner());
+            candidates.get(dir).cloned()
+        };
+        if let Some(candidates) = cached {
+            return candidates;
+        }
+
+        let found: Arc<[usize]> = if dir.is_empty() {
+            (0..self.projects.len()).collect()
+        } else {
+            let parent = dir.rfind('/').map_or("", |end| &dir[..end]);
+            self.candidates(parent)
+                .iter()
+                .cloned()
+                .filter(|&candidate| self.projects[candidate].1.may_match_under(dir))
+                .collect()
+        };
+        self.candidates
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .insert(dir.to_owned(), Arc::clone(&found));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphQLConfiguration;
+
+    #[test]
+    fn it_agrees_with_matching_every_project() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "a": { "includes": ["src/a/**"], "excludes": ["src/a/gen/**"] },
+                "b": { "includes": ["src/**/*.graphql"] },
+                "c": { "includes": ["{lib,src}/c/*"] }
+            }
+        }))
+        .unwrap();
+        let config = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+        let index = FileIndex::new(&config);
+
+        for file in &[
+            "src/a/query.graphql",
+            "src/a/gen/query.graphql",
+            "src/c/query.graphql",
+            "src/c/query.js",
+            "lib/c/query.js",
+            "lib/query.graphql",
+            "query.graphql",
+        ] {
+            let expected = config
+                .project_handles()
+                .into_iter()
+                .find(|handle| handle.include_file(file))
+                .map(|handle| handle.name());
+            assert_eq!(index.project_for_file(file), expected, "{}", file);
+        }
+        assert_eq!(index.project_for_file("/elsewhere/src/a/x"), None);
+    }
+}
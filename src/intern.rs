@@ -0,0 +1,103 @@
+//! Sharing repeated patterns between configurations.
+//!
+//! The configurations of a workspace tend to repeat the same patterns, such as
+//! `**/node_modules/**` or `src/**/*.graphql`, in every package. An `Interner`
+//! keeps one copy of every pattern it has seen and makes the configurations
+//! passed to it share that copy, so that memory grows with the number of
+//! distinct patterns rather than with the number of projects.
+//!
+//! Schema paths are owned by every project and are not shared.
+
+use std::collections::HashSet;
+
+use crate::glob::GlobPattern;
+use crate::normalize::PatternList;
+use crate::visit::ConfigVisitorMut;
+use crate::GraphQLConfiguration;
+
+/// The patterns shared between configurations.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::intern::Interner;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let mut interner = Interner::new();
+/// for package in &["web", "admin"] {
+///     let mut config: GraphQLConfiguration = serde_json::from_value(json!({
+///         "name": package,
+///         "excludes": ["**/node_modules/**"]
+///     }))?;
+///     interner.intern(&mut config);
+/// }
+///
+/// assert_eq!(interner.len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default, Debug)]
+pub struct Interner {
+    patterns: HashSet<GlobPattern>,
+}
+
+impl Interner {
+    /// An interner without patterns.
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// The shared copy of `pattern`, which is added if it was not seen yet.
+    pub fn pattern(&mut self, pattern: &GlobPattern) -> GlobPattern {
+        if let Some(shared) = self.patterns.get(pattern) {
+            return shared.clone();
+        }
+        self.patterns.insert(pattern.clone());
+        pattern.clone()
+    }
+
+    /// Replaces every pattern of `config` with its shared copy.
+    pub fn intern(&mut self, config: &mut GraphQLConfiguration) {
+        config.visit_mut(&mut Intern(self));
+    }
+
+    /// The number of distinct patterns seen.
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Whether no pattern was seen yet.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+struct Intern<'a>(&'a mut Interner);
+
+impl<'a> ConfigVisitorMut for Intern<'a> {
+    fn visit_pattern(&mut self, _: Option<&str>, _: PatternList, pattern: &mut GlobPattern) {
+        *pattern = self.0.pattern(pattern);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_shares_equal_patterns() {
+        let config = |json| -> GraphQLConfiguration { ::serde_json::from_value(json).unwrap() };
+        let mut web = config(json!({ "projects": { "web": { "includes": ["src/*.graphql"] } } }));
+        let mut admin = config(json!({ "includes": ["src/*.graphql", "admin/*.graphql"] }));
+
+        let mut interner = Interner::new();
+        interner.intern(&mut web);
+        interner.intern(&mut admin);
+
+        assert_eq!(interner.len(), 2);
+        let web = &web.project("web").unwrap().includes.as_ref().unwrap()[0];
+        let admin = &admin.root.includes.as_ref().unwrap()[0];
+        assert_eq!(web.as_str().as_ptr(), admin.as_str().as_ptr());
+    }
+}
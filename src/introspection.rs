@@ -0,0 +1,561 @@
+//! Schemas from introspection results.
+//!
+//! Endpoints describe their schema in answer to `INTROSPECTION_QUERY`, and
+//! schema files sometimes hold such an answer rather than SDL. `to_sdl` prints
+//! the SDL of the schema an answer describes, without its built-in scalars,
+//! directives and introspection types.
+
+use std::error::Error;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+/// The query asking an endpoint for its schema.
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+    directives {
+      name
+      description
+      locations
+      isRepeatable
+      args { ...InputValue }
+    }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  specifiedByURL
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }
+  possibleTypes { ...TypeRef }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType { kind name ofType { kind name } }
+          }
+        }
+      }
+    }
+  }
+}
+";
+
+/// The error returned for an answer which is not an introspection result.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IntrospectionError {
+    /// There is no `__schema`, either at the top or under `data`.
+    MissingSchema,
+    /// The field at the path in the variant is missing or of the wrong type.
+    Malformed(String),
+}
+
+impl fmt::Display for IntrospectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IntrospectionError::MissingSchema => f.write_str("the result has no `__schema`"),
+            IntrospectionError::Malformed(ref path) => {
+                write!(f, "malformed introspection result at `{}`", path)
+            }
+        }
+    }
+}
+
+impl Error for IntrospectionError {}
+
+const BUILT_IN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+const BUILT_IN_DIRECTIVES: &[&str] = &["include", "skip", "deprecated", "specifiedBy", "oneOf"];
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+/// Prints the SDL of the schema described by `result`, the answer to
+/// `INTROSPECTION_QUERY`, with or without its `data` envelope.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::introspection::to_sdl;
+/// # fn main() -> Result<(), graphql_config::introspection::IntrospectionError> {
+/// let result = json!({ "data": { "__schema": {
+///     "queryType": { "name": "Query" },
+///     "directives": [],
+///     "types": [{
+///         "kind": "OBJECT",
+///         "name": "Query",
+///         "description": "The entry point.",
+///         "interfaces": [],
+///         "fields": [{
+///             "name": "hello",
+///             "args": [],
+///             "type": { "kind": "NON_NULL", "ofType": { "kind": "SCALAR", "name": "String" } },
+///             "isDeprecated": false
+///         }]
+///     }]
+/// } } });
+///
+/// assert_eq!(
+///     to_sdl(&result)?,
+///     "\"The entry point.\"\ntype Query {\n  hello: String!\n}\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn to_sdl(result: &Value) -> Result<String, IntrospectionError> {
+    let schema = result
+        .get("data")
+        .unwrap_or(result)
+        .get("__schema")
+        .and_then(Value::as_object)
+        .ok_or(IntrospectionError::MissingSchema)?;
+
+    let mut definitions = Vec::new();
+    if let Some(definition) = schema_definition(schema) {
+        definitions.push(definition);
+    }
+    for (index, directive) in array(schema, "directives", "__schema")?.iter().enumerate() {
+        let path = format!("__schema.directives[{}]", index);
+        let directive = object(directive, &path)?;
+        if !BUILT_IN_DIRECTIVES.contains(&string(directive, "name", &path)?) {
+            definitions.push(print_directive(directive, &path)?);
+        }
+    }
+    for (index, ty) in array(schema, "types", "__schema")?.iter().enumerate() {
+        let path = format!("__schema.types[{}]", index);
+        let ty = object(ty, &path)?;
+        let name = string(ty, "name", &path)?;
+        if name.starts_with("__") || BUILT_IN_SCALARS.contains(&name) {
+            continue;
+        }
+        definitions.push(print_type(ty, &path)?);
+    }
+
+    Ok(definitions.join("\n"))
+}
+
+fn schema_definition(schema: &Map<String, Value>) -> Option<String> {
+    let roots: Vec<(&str, &str)> = [
+        ("query", "queryType", "Query"),
+        ("mutation", "mutationType", "Mutation"),
+        ("subscription", "subscriptionType", "Subscription"),
+    ]
+    .iter()
+    .filter_map(|&(operation, field, _)| {
+        let name = schema.get(field)?.get("name")?.as_str()?;
+        Some((operation, name))
+    })
+    .collect();
+
+    let conventional = roots.iter().all(|&(operation, name)| {
+        let mut expected = operation.to_owned();
+        expected[..1].make_ascii_uppercase();
+        expected == name
+    });
+    if conventional {
+        return None;
+    }
+
+    let mut definition = "schema {\n".to_owned();
+    for (operation, name) in roots {
+        definition.push_str(&format!("  {}: {}\n", operation, name));
+    }
+    definition.push_str("}\n");
+    Some(definition)
+}
+
+fn print_type(ty: &Map<String, Value>, path: &str) -> Result<String, IntrospectionError> {
+    let kind = string(ty, "kind", path)?;
+    let name = string(ty, "name", path)?;
+    let mut printed = description(ty, "");
+
+    match kind {
+        "SCALAR" => {
+            printed.push_str(&format!("scalar {}", name));
+            if let Some(url) = ty.get("specifiedByURL").and_then(Value::as_str) {
+                printed.push_str(&format!(" @specifiedBy(url: {})", quote(url)));
+            }
+            printed.push('\n');
+        }
+        "OBJECT" | "INTERFACE" => {
+            let keyword = if kind == "OBJECT" {
+                "type"
+            } else {
+                "interface"
+            };
+            printed.push_str(&format!("{} {}", keyword, name));
+            let interfaces = optional_array(ty, "interfaces", path)?
+                .iter()
+                .enumerate()
+                .map(|(index, interface)| {
+                    let path = format!("{}.interfaces[{}]", path, index);
+                    string(object(interface, &path)?, "name", &path)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if !interfaces.is_empty() {
+                printed.push_str(&format!(" implements {}", interfaces.join(" & ")));
+            }
+            printed.push_str(" {\n");
+            for (index, field) in array(ty, "fields", path)?.iter().enumerate() {
+                let path = format!("{}.fields[{}]", path, index);
+                printed.push_str(&print_field(object(field, &path)?, &path)?);
+            }
+            printed.push_str("}\n");
+        }
+        "UNION" => {
+            let members = array(ty, "possibleTypes", path)?
+                .iter()
+                .enumerate()
+                .map(|(index, member)| {
+                    let path = format!("{}.possibleTypes[{}]", path, index);
+                    string(object(member, &path)?, "name", &path)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            printed.push_str(&format!("union {} = {}\n", name, members.join(" | ")));
+        }
+        "ENUM" => {
+            printed.push_str(&format!("enum {} {{\n", name));
+            for (index, value) in array(ty, "enumValues", path)?.iter().enumerate() {
+                let path = format!("{}.enumValues[{}]", path, index);
+                let value = object(value, &path)?;
+                printed.push_str(&description(value, "  "));
+                printed.push_str(&format!(
+                    "  {}{}\n",
+                    string(value, "name", &path)?,
+                    deprecation(value)
+                ));
+            }
+            printed.push_str("}\n");
+        }
+        "INPUT_OBJECT" => {
+            printed.push_str(&format!("input {} {{\n", name));
+            for (index, field) in array(ty, "inputFields", path)?.iter().enumerate() {
+                let path = format!("{}.inputFields[{}]", path, index);
+                let field = object(field, &path)?;
+                printed.push_str(&description(field, "  "));
+                printed.push_str(&format!("  {}\n", print_input_value(field, &path)?));
+            }
+            printed.push_str("}\n");
+        }
+        _ => return Err(IntrospectionError::Malformed(format!("{}.kind", path))),
+    }
+    Ok(printed)
+}
+
+fn print_field(field: &Map<String, Value>, path: &str) -> Result<String, IntrospectionError> {
+    let mut printed = description(field, "  ");
+    printed.push_str(&format!(
+        "  {}{}: {}{}\n",
+        string(field, "name", path)?,
+        print_arguments(field, path)?,
+        type_ref(field.get("type"), &format!("{}.type", path))?,
+        deprecation(field)
+    ));
+    Ok(printed)
+}
+
+fn print_directive(
+    directive: &Map<String, Value>,
+    path: &str,
+) -> Result<String, IntrospectionError> {
+    let locations = array(directive, "locations", path)?
+        .iter()
+        .enumerate()
+        .map(|(index, location)| {
+            location.as_str().ok_or_else(|| {
+                IntrospectionError::Malformed(format!("{}.locations[{}]", path, index))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let repeatable = directive.get("isRepeatable").and_then(Value::as_bool) == Some(true);
+
+    Ok(format!(
+        "{}directive @{}{}{} on {}\n",
+        description(directive, ""),
+        string(directive, "name", path)?,
+        print_arguments(directive, path)?,
+        if repeatable { " repeatable" } else { "" },
+        locations.join(" | ")
+    ))
+}
+
+fn print_arguments(owner: &Map<String, Value>, path: &str) -> Result<String, IntrospectionError> {
+    let arguments = optional_array(owner, "args", path)?
+        .iter()
+        .enumerate()
+        .map(|(index, argument)| {
+            let path = format!("{}.args[{}]", path, index);
+            print_input_value(object(argument, &path)?, &path)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if arguments.is_empty() {
+        Ok(String::new())
+    } else {
+        Ok(format!("({})", arguments.join(", ")))
+    }
+}
+
+fn print_input_value(value: &Map<String, Value>, path: &str) -> Result<String, IntrospectionError> {
+    let mut printed = format!(
+        "{}: {}",
+        string(value, "name", path)?,
+        type_ref(value.get("type"), &format!("{}.type", path))?
+    );
+    if let Some(default) = value.get("defaultValue").and_then(Value::as_str) {
+        printed.push_str(&format!(" = {}", default));
+    }
+    Ok(printed)
+}
+
+fn type_ref(ty: Option<&Value>, path: &str) -> Result<String, IntrospectionError> {
+    let malformed = || IntrospectionError::Malformed(path.to_owned());
+    let ty = ty.and_then(Value::as_object).ok_or_else(malformed)?;
+    let of_type = || type_ref(ty.get("ofType"), &format!("{}.ofType", path));
+
+    match ty.get("kind").and_then(Value::as_str) {
+        Some("NON_NULL") => Ok(format!("{}!", of_type()?)),
+        Some("LIST") => Ok(format!("[{}]", of_type()?)),
+        Some(_) => ty
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_owned)
+            .ok_or_else(malformed),
+        None => Err(malformed()),
+    }
+}
+
+fn description(owner: &Map<String, Value>, indent: &str) -> String {
+    let description = match owner.get("description").and_then(Value::as_str) {
+        Some(description) if !description.is_empty() => description,
+        _ => return String::new(),
+    };
+    if !description.contains('\n') {
+        return format!("{}{}\n", indent, quote(description));
+    }
+
+    let mut printed = format!("{}\"\"\"\n", indent);
+    for line in description.replace("\"\"\"", "\\\"\"\"").lines() {
+        if line.is_empty() {
+            printed.push('\n');
+        } else {
+            printed.push_str(&format!("{}{}\n", indent, line));
+        }
+    }
+    printed.push_str(&format!("{}\"\"\"\n", indent));
+    printed
+}
+
+fn deprecation(owner: &Map<String, Value>) -> String {
+    if owner.get("isDeprecated").and_then(Value::as_bool) != Some(true) {
+        return String::new();
+    }
+    match owner.get("deprecationReason").and_then(Value::as_str) {
+        Some(reason) if reason != DEFAULT_DEPRECATION_REASON => {
+            format!(" @deprecated(reason: {})", quote(reason))
+        }
+        _ => " @deprecated".to_owned(),
+    }
+}
+
+/// `value` as a GraphQL string, whose escapes are the ones of JSON.
+fn quote(value: &str) -> String {
+    Value::from(value).to_string()
+}
+
+fn object<'a>(value: &'a Value, path: &str) -> Result<&'a Map<String, Value>, IntrospectionError> {
+    value
+        .as_object()
+        .ok_or_else(|| IntrospectionError::Malformed(path.to_owned()))
+}
+
+fn string<'a>(
+    owner: &'a Map<String, Value>,
+    field: &str,
+    path: &str,
+) -> Result<&'a str, IntrospectionError> {
+    owner
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| IntrospectionError::Malformed(format!("{}.{}", path, field)))
+}
+
+fn array<'a>(
+    owner: &'a Map<String, Value>,
+    field: &str,
+    path: &str,
+) -> Result<&'a [Value], IntrospectionError> {
+    owner
+        .get(field)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .ok_or_else(|| IntrospectionError::Malformed(format!("{}.{}", path, field)))
+}
+
+/// Like `array`, with a missing or `null` field read as empty.
+fn optional_array<'a>(
+    owner: &'a Map<String, Value>,
+    field: &str,
+    path: &str,
+) -> Result<&'a [Value], IntrospectionError> {
+    match owner.get(field) {
+        None | Some(Value::Null) => Ok(&[]),
+        Some(_) => array(owner, field, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named(kind: &str, name: &str) -> Value {
+        json!({ "kind": kind, "name": name })
+    }
+
+    #[test]
+    fn it_prints_every_kind_of_type() {
+        let result = json!({ "__schema": {
+            "queryType": { "name": "Root" },
+            "mutationType": null,
+            "directives": [
+                { "name": "skip", "locations": ["FIELD"], "args": [] },
+                {
+                    "name": "auth",
+                    "description": "Guards a field.",
+                    "locations": ["FIELD_DEFINITION", "OBJECT"],
+                    "isRepeatable": true,
+                    "args": [{ "name": "role", "type": named("SCALAR", "String"), "defaultValue": "\"admin\"" }]
+                }
+            ],
+            "types": [
+                named("SCALAR", "String"),
+                { "kind": "OBJECT", "name": "__Schema", "fields": [], "interfaces": [] },
+                { "kind": "SCALAR", "name": "Date", "specifiedByURL": "https://example.com/date" },
+                {
+                    "kind": "OBJECT",
+                    "name": "Root",
+                    "interfaces": [named("INTERFACE", "Node")],
+                    "fields": [
+                        {
+                            "name": "search",
+                            "description": "Finds things.\n\nBy text.",
+                            "args": [{
+                                "name": "text",
+                                "type": { "kind": "NON_NULL", "ofType": named("SCALAR", "String") }
+                            }],
+                            "type": { "kind": "LIST", "ofType": named("UNION", "Result") },
+                            "isDeprecated": true,
+                            "deprecationReason": "Use `find`."
+                        },
+                        { "name": "id", "args": [], "type": named("SCALAR", "ID"), "isDeprecated": false }
+                    ]
+                },
+                { "kind": "INTERFACE", "name": "Node", "interfaces": null, "fields": [
+                    { "name": "id", "args": [], "type": named("SCALAR", "ID") }
+                ] },
+                { "kind": "UNION", "name": "Result", "possibleTypes": [named("OBJECT", "Root")] },
+                { "kind": "ENUM", "name": "Color", "enumValues": [
+                    { "name": "RED", "isDeprecated": false },
+                    { "name": "BLUE", "isDeprecated": true, "deprecationReason": "No longer supported" }
+                ] },
+                { "kind": "INPUT_OBJECT", "name": "Filter", "inputFields": [
+                    { "name": "limit", "description": "At most.", "type": named("SCALAR", "Int"), "defaultValue": "10" }
+                ] }
+            ]
+        } });
+
+        assert_eq!(
+            to_sdl(&result).unwrap(),
+            r#"schema {
+  query: Root
+}
+
+"Guards a field."
+directive @auth(role: String = "admin") repeatable on FIELD_DEFINITION | OBJECT
+
+scalar Date @specifiedBy(url: "https://example.com/date")
+
+type Root implements Node {
+  """
+  Finds things.
+
+  By text.
+  """
+  search(text: String!): [Result] @deprecated(reason: "Use `find`.")
+  id: ID
+}
+
+interface Node {
+  id: ID
+}
+
+union Result = Root
+
+enum Color {
+  RED
+  BLUE @deprecated
+}
+
+input Filter {
+  "At most."
+  limit: Int = 10
+}
+"#
+        );
+    }
+
+    #[test]
+    fn it_rejects_other_results() {
+        assert_eq!(
+            to_sdl(&json!({ "data": {} })),
+            Err(IntrospectionError::MissingSchema)
+        );
+        assert_eq!(
+            to_sdl(&json!({ "__schema": { "directives": [], "types": [{ "kind": "OBJECT" }] } })),
+            Err(IntrospectionError::Malformed(
+                "__schema.types[0].name".to_owned()
+            ))
+        );
+    }
+}
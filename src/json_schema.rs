@@ -0,0 +1,202 @@
+//! JSON Schema generation for configuration files.
+//!
+//! Editors such as VS Code can use the generated schema to offer completion
+//! and validation inside `.graphqlconfig` files. Registering extensions with
+//! `JsonSchemaGenerator` extends the schema with their shapes, so a team gets
+//! completion for the exact configuration their tooling reads.
+//!
+//! The keys and their documentation are those of `completion::KEYS`, the keys
+//! of version 2 and 3 files alike.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::completion::{ConfigKey, KeyKind, KeyScope, KEYS};
+use crate::extension::Extension;
+
+/// Builds a JSON Schema (draft-07) describing configuration files.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::extension::Extension;
+/// # use graphql_config::json_schema::JsonSchemaGenerator;
+/// #[derive(Serialize, Deserialize)]
+/// struct Codegen {
+///     output: String,
+/// }
+///
+/// impl Extension for Codegen {
+///     const NAME: &'static str = "codegen";
+///
+///     fn json_schema() -> serde_json::Value {
+///         json!({ "type": "object", "properties": { "output": { "type": "string" } } })
+///     }
+/// }
+///
+/// # fn main() {
+/// let schema = JsonSchemaGenerator::new().extension::<Codegen>().generate();
+///
+/// assert_eq!(
+///     schema["definitions"]["extensions"]["properties"]["codegen"],
+///     Codegen::json_schema()
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct JsonSchemaGenerator {
+    extensions: BTreeMap<String, Value>,
+}
+
+impl JsonSchemaGenerator {
+    /// Creates a generator without any registered extensions.
+    pub fn new() -> JsonSchemaGenerator {
+        JsonSchemaGenerator::default()
+    }
+
+    /// Registers a typed extension, describing it with `E::json_schema`.
+    pub fn extension<E: Extension>(self) -> JsonSchemaGenerator {
+        self.extension_schema(E::NAME, E::json_schema())
+    }
+
+    /// Registers the schema of an extension without a Rust type.
+    pub fn extension_schema(mut self, name: &str, schema: Value) -> JsonSchemaGenerator {
+        self.extensions.insert(name.to_owned(), schema);
+        self
+    }
+
+    /// Generates the schema. Unregistered extensions are still allowed by it.
+    pub fn generate(&self) -> Value {
+        serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "GraphQL configuration",
+            "type": "object",
+            "properties": properties(KeyScope::Root),
+            "definitions": {
+                "project": {
+                    "type": "object",
+                    "properties": properties(KeyScope::RootAndProjects)
+                },
+                "patterns": {
+                    "type": "array",
+                    "items": { "type": "string" }
+                },
+                "pointers": {
+                    "oneOf": [
+                        { "type": "string" },
+                        {
+                            "type": "array",
+                            "items": { "oneOf": [{ "type": "string" }, { "type": "object" }] }
+                        },
+                        { "type": "object" }
+                    ]
+                },
+                "extensions": {
+                    "description": "Reserved namespace for tool-specific configuration.",
+                    "type": "object",
+                    "properties": self.extensions,
+                    "additionalProperties": true
+                }
+            }
+        })
+    }
+}
+
+/// The JSON Schema of configuration files without any registered extensions.
+pub fn json_schema() -> Value {
+    JsonSchemaGenerator::new().generate()
+}
+
+/// The top-level keys of `KEYS` which may be written in `scope`: every one
+/// of them in the top-level object, and those shared with projects in
+/// projects.
+fn properties(scope: KeyScope) -> serde_json::Map<String, Value> {
+    KEYS.iter()
+        .filter(|key| !key.path.contains('.'))
+        .filter(|key| scope == KeyScope::Root || key.scope == KeyScope::RootAndProjects)
+        .map(|key| (key.path.to_owned(), property(key)))
+        .collect()
+}
+
+fn property(key: &ConfigKey) -> Value {
+    let mut property = match (key.path, key.kind) {
+        ("projects", _) => serde_json::json!({
+            "type": "object",
+            "additionalProperties": { "$ref": "#/definitions/project" }
+        }),
+        ("extensions", _) => serde_json::json!({ "$ref": "#/definitions/extensions" }),
+        (_, KeyKind::String) | (_, KeyKind::Path) => serde_json::json!({ "type": "string" }),
+        (_, KeyKind::Patterns) => serde_json::json!({ "$ref": "#/definitions/patterns" }),
+        (_, KeyKind::Strings) => serde_json::json!({
+            "type": "array",
+            "items": { "type": "string" }
+        }),
+        (_, KeyKind::Map) | (_, KeyKind::Object) => serde_json::json!({ "type": "object" }),
+        (_, KeyKind::StringOrObject) => serde_json::json!({
+            "oneOf": [{ "type": "string" }, { "type": "object" }]
+        }),
+        (_, KeyKind::Pointers) => serde_json::json!({ "$ref": "#/definitions/pointers" }),
+    };
+    property["description"] = Value::from(key.description);
+    property
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_describes_root_and_project_fields() {
+        let schema = json_schema();
+
+        assert_eq!(
+            schema["properties"]["schemaPath"],
+            schema["definitions"]["project"]["properties"]["schemaPath"]
+        );
+        assert_eq!(
+            schema["properties"]["projects"]["additionalProperties"],
+            json!({ "$ref": "#/definitions/project" })
+        );
+        assert_eq!(schema["definitions"]["extensions"]["properties"], json!({}));
+    }
+
+    #[test]
+    fn it_describes_the_keys_of_both_versions() {
+        let schema = json_schema();
+        let project = &schema["definitions"]["project"]["properties"];
+
+        for key in KEYS.iter().filter(|key| !key.path.contains('.')) {
+            assert_eq!(
+                schema["properties"][key.path]["description"], key.description,
+                "{}",
+                key.path
+            );
+            assert_eq!(
+                project.get(key.path).is_some(),
+                key.scope == KeyScope::RootAndProjects,
+                "{}",
+                key.path
+            );
+        }
+        assert_eq!(
+            project["documents"]["$ref"],
+            json!("#/definitions/pointers")
+        );
+    }
+
+    #[test]
+    fn it_includes_untyped_extension_schemas() {
+        let schema = JsonSchemaGenerator::new()
+            .extension_schema("lastUpdatedAt", json!({ "type": "integer" }))
+            .generate();
+
+        assert_eq!(
+            schema["definitions"]["extensions"]["properties"]["lastUpdatedAt"],
+            json!({ "type": "integer" })
+        );
+    }
+}
@@ -0,0 +1,189 @@
+//! Configurations with extensions left unparsed.
+//!
+//! Extensions can be large, such as the codegen blocks of a monorepo, and most
+//! tools read only their own. `LazyGraphQLConfiguration` keeps every
+//! extension as the JSON text it was written as, and parses one only when it
+//! is asked for with `extension`.
+//!
+//! Only JSON text can be kept as written, so these configurations deserialize
+//! with `serde_json::from_str`, `from_slice` and `from_reader`, but not from
+//! a `serde_json::Value` or other formats.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::de::{Deserialize, DeserializeOwned, Deserializer, MapAccess, Visitor};
+use serde_json::value::RawValue;
+
+use crate::de::{self, Fields};
+use crate::glob::GlobPattern;
+use crate::map::Map;
+use crate::projects::ProjectName;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// A `GraphQLConfiguration` with unparsed extensions.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # #[macro_use]
+/// # extern crate serde_derive;
+/// # extern crate serde_json;
+/// # use graphql_config::lazy::LazyGraphQLConfiguration;
+/// #[derive(Deserialize)]
+/// struct Codegen {
+///     output: String,
+/// }
+///
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: LazyGraphQLConfiguration = serde_json::from_str(
+///     r#"{
+///         "schemaPath": "./schema.graphql",
+///         "extensions": { "codegen": { "output": "./generated" }, "huge": [1, 2, 3] }
+///     }"#,
+/// )?;
+///
+/// let codegen: Codegen = config.root.extension("codegen").unwrap()?;
+/// assert_eq!(codegen.output, "./generated");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct LazyGraphQLConfiguration {
+    /// Project-specific configurations by name.
+    pub projects: Option<Map<ProjectName, LazyProjectConfiguration>>,
+    /// The top-level configuration.
+    #[serde(flatten)]
+    pub root: LazyProjectConfiguration,
+}
+
+/// A `GraphQLProjectConfiguration` with unparsed extensions.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LazyProjectConfiguration {
+    /// The name of the project.
+    pub name: Option<String>,
+    /// A file with schema IDL.
+    pub schema_path: Option<PathBuf>,
+    /// Patterns of the files belonging to the project.
+    pub includes: Option<Vec<GlobPattern>>,
+    /// Patterns of the files not belonging to the project.
+    pub excludes: Option<Vec<GlobPattern>>,
+    /// Tool-specific configuration, as written.
+    pub extensions: Option<Map<String, Box<RawValue>>>,
+}
+
+impl LazyGraphQLConfiguration {
+    /// Parses every extension into a `GraphQLConfiguration`.
+    pub fn into_configuration(self) -> Result<GraphQLConfiguration, serde_json::Error> {
+        let mut config = GraphQLConfiguration::new();
+        if let Some(projects) = self.projects {
+            let parsed = projects
+                .into_iter()
+                .map(|(key, project)| Ok((key, project.into_project()?)))
+                .collect::<Result<_, serde_json::Error>>()?;
+            config.projects = Some(parsed);
+        }
+        config.root = self.root.into_project()?;
+        Ok(config)
+    }
+}
+
+impl LazyProjectConfiguration {
+    /// The extension `name` parsed as a `T`, or `None` if there is no such
+    /// extension.
+    pub fn extension<'a, T>(&'a self, name: &str) -> Option<Result<T, serde_json::Error>>
+    where
+        T: Deserialize<'a>,
+    {
+        let raw = self.extensions.as_ref()?.get(name)?;
+        Some(serde_json::from_str(raw.get()))
+    }
+
+    /// Parses every extension into a `GraphQLProjectConfiguration`.
+    pub fn into_project(self) -> Result<GraphQLProjectConfiguration, serde_json::Error> {
+        let mut project = GraphQLProjectConfiguration::new();
+        project.name = self.name;
+        project.schema_path = self.schema_path;
+        project.includes = self.includes;
+        project.excludes = self.excludes;
+        project.extensions = self.extensions.map(parse_all).transpose()?;
+        Ok(project)
+    }
+}
+
+fn parse_all<T>(extensions: Map<String, Box<RawValue>>) -> Result<Map<String, T>, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    extensions
+        .into_iter()
+        .map(|(name, raw)| Ok((name, serde_json::from_str(raw.get())?)))
+        .collect()
+}
+
+impl<'de> Deserialize<'de> for LazyGraphQLConfiguration {
+    fn deserialize<D>(deserializer: D) -> Result<LazyGraphQLConfiguration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(LazyVisitor)
+    }
+}
+
+struct LazyVisitor;
+
+impl<'de> Visitor<'de> for LazyVisitor {
+    type Value = LazyGraphQLConfiguration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a GraphQL configuration")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<LazyGraphQLConfiguration, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let fields: Fields<LazyProjectConfiguration, Box<RawValue>> = de::read_fields(map)?;
+        Ok(LazyGraphQLConfiguration {
+            projects: fields.projects,
+            root: LazyProjectConfiguration {
+                name: fields.name,
+                schema_path: fields.schema_path,
+                includes: fields.includes,
+                excludes: fields.excludes,
+                extensions: fields.extensions,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_extensions_on_demand() {
+        let json = r#"{
+            "extensions": { "a": { "b": [1, 2] } },
+            "projects": { "app": { "schemaPath": "./app.graphql", "extensions": { "c": true } } }
+        }"#;
+        let lazy: LazyGraphQLConfiguration = ::serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            lazy.root
+                .extension::<::serde_json::Value>("a")
+                .unwrap()
+                .unwrap(),
+            json!({ "b": [1, 2] })
+        );
+        assert!(lazy.root.extension::<bool>("missing").is_none());
+        assert!(lazy.root.extension::<bool>("a").unwrap().is_err());
+
+        let expected: GraphQLConfiguration = ::serde_json::from_str(json).unwrap();
+        assert_eq!(
+            ::serde_json::to_value(&lazy).unwrap(),
+            ::serde_json::to_value(&expected).unwrap()
+        );
+        assert_eq!(lazy.into_configuration().unwrap(), expected);
+    }
+}
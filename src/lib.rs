@@ -6,6 +6,27 @@
 //!
 //! Currently, this library follows the spec as per [version 2.0.1 of the graphql-config specification](https://github.com/prismagraphql/graphql-config/tree/v2.0.1).
 //!
+//! ## Features
+//!
+//! The configuration types only depend on `serde` and `serde_json`. Heavier
+//! functionality is opt-in:
+//!
+//! - `fs` (default): reading configurations, schemas and documents from disk.
+//! - `yaml`: YAML configurations and v3 YAML export, using `serde_yaml`.
+//! - `graphql-parser`: schemas and documents parsed for editors, see
+//!   [`cache`](cache/index.html), and `GraphQLProjectConfiguration::load_schema`.
+//! - `persisted-queries`: persisted operation manifests, using `graphql-parser` and `sha2`.
+//! - `cli`: the `graphql-config` command, whose subcommands `graphql-config help`
+//!   lists. Implies `fs` and `yaml`.
+//! - `graphql-client`: inputs for `graphql_client` code generation. Implies `fs`.
+//! - `ffi`: a C interface, see [`ffi`](ffi/index.html). Implies `fs`.
+//! - `watch`: reloading the configuration when its file changes, see
+//!   [`watch`](watch/index.html). Implies `fs`.
+//! - `preserve-order` and `hash-map`: the container of `projects` and
+//!   `extensions`, see [`map`](map/index.html).
+//! - `raw-value`: configurations with extensions left unparsed until read,
+//!   see [`lazy`](lazy/index.html).
+//!
 //! ## Example
 //!
 //! ```
@@ -13,8 +34,6 @@
 //! # extern crate graphql_config;
 //! # #[macro_use]
 //! # extern crate serde_json;
-//! # #[macro_use]
-//! # extern crate maplit;
 //! # use graphql_config::*;
 //! # use std::io;
 //! # fn main() -> io::Result<()> {
@@ -28,24 +47,13 @@
 //!     }
 //! });
 //!
-//! let expected = GraphQLConfiguration {
-//!     root: GraphQLProjectConfiguration {
-//!         name: None,
-//!         schema_path: Some("./schema.graphql".into()),
-//!         includes: Some(vec!["./graphql/*.graphql".to_owned()]),
-//!         excludes: None,
-//!         extensions: None,
-//!     },
-//!     projects: Some(btreemap!{
-//!         "amazingLibrary".to_owned() => GraphQLProjectConfiguration {
-//!             schema_path: Some("./amazingLibrary.schema.graphql".into()),
-//!             name: None,
-//!             includes: None,
-//!             excludes: None,
-//!             extensions: None,
-//!         },
-//!     }),
-//! };
+//! let mut expected = GraphQLConfiguration::new();
+//! expected.root.schema_path = Some("./schema.graphql".into());
+//! expected.root.includes = Some(vec!["./graphql/*.graphql".parse().unwrap()]);
+//!
+//! let mut amazing_library = GraphQLProjectConfiguration::new();
+//! amazing_library.schema_path = Some("./amazingLibrary.schema.graphql".into());
+//! expected.insert_project("amazingLibrary".parse().unwrap(), amazing_library);
 //!
 //! let deserialized = serde_json::from_value::<GraphQLConfiguration>(config)?;
 //!
@@ -65,6 +73,18 @@ extern crate serde_json;
 #[macro_use]
 extern crate serde_json;
 
+#[cfg(feature = "yaml")]
+extern crate serde_yaml;
+
+#[cfg(feature = "graphql-parser")]
+extern crate graphql_parser;
+
+#[cfg(feature = "sha2")]
+extern crate sha2;
+
+#[cfg(feature = "indexmap")]
+extern crate indexmap;
+
 #[macro_use]
 extern crate serde_derive;
 
@@ -72,16 +92,108 @@ extern crate serde_derive;
 #[macro_use]
 extern crate maplit;
 
+/// Like `map!`, for whichever `map::Map` the features select.
+#[cfg(test)]
+macro_rules! map {
+    ($($key:expr => $value:expr),* $(,)*) => {{
+        let mut map = crate::map::Map::new();
+        $(map.insert($key, $value);)*
+        map
+    }};
+}
+
+pub mod apollo;
+pub mod borrowed;
+#[cfg(feature = "fs")]
+pub mod build_support;
+pub mod builder;
+#[cfg(all(feature = "fs", feature = "graphql-parser"))]
+pub mod cache;
+pub mod cancel;
+#[cfg(feature = "fs")]
+pub mod codegen;
+pub mod completion;
+mod de;
+#[cfg(feature = "fs")]
+pub mod discover;
+pub mod diff;
+#[cfg(feature = "fs")]
+pub mod drift;
+pub mod endpoints;
+pub mod env;
+pub mod extension;
+pub mod fetch;
+pub mod fixtures;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "graphql-client")]
+pub mod graphql_client;
+pub mod glob;
+pub mod handle;
+pub mod hosts;
+pub mod index;
+pub mod intern;
+pub mod introspection;
+pub mod json_schema;
+#[cfg(feature = "raw-value")]
+pub mod lazy;
+pub mod load;
+#[cfg(feature = "fs")]
+pub mod lsp;
+pub mod map;
+pub mod matcher;
+pub mod merge;
+mod merge_patch;
+mod mutate;
+pub mod normalize;
+pub mod observer;
+#[cfg(feature = "fs")]
+pub mod ownership;
+#[cfg(feature = "persisted-queries")]
+pub mod persisted_queries;
+pub mod projects;
+pub mod redact;
+pub mod relay;
+pub mod resolved;
+pub mod schema;
+pub mod schema_cache;
+pub mod shared;
+mod summary;
+pub mod template;
+pub mod testing;
+#[cfg(all(test, feature = "fs"))]
+mod test_dir;
+mod typescript;
+pub mod v3;
+pub mod visit;
+#[cfg(feature = "watch")]
+pub mod watch;
+mod write;
+#[cfg(feature = "yaml")]
+mod yaml;
+pub mod yaml_edit;
+
 /// `GraphQLConfiguration` is the type of the whole JSON document. It contains
 /// the top-level configuration (which serializes in the `root` field) and also
 /// optionally project-specific configuration in the `projects` field. The shapes
 /// of the top-level configuration and project-specific configurations are exactly
 /// the same.
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+///
+/// Configurations are compared and hashed structurally. Extension values are
+/// compared as JSON, so two configurations are equal whenever their
+/// extensions are, regardless of the key order they were written in, and the
+/// integer `1` differs from the float `1.0`.
+///
+/// New fields may be added as the specification evolves, so the struct cannot
+/// be constructed with a literal outside of this crate. Use `new` or
+/// `builder` instead.
+#[derive(Serialize, Clone, Default, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub struct GraphQLConfiguration {
-    /// A `BTreeMap` of project names as strings to `GraphQLProjectConfiguration`.
-    /// Names of projects are not snake-cased during deserialization.
-    pub projects: Option<::std::collections::BTreeMap<String, GraphQLProjectConfiguration>>,
+    /// A `Map` of project names to `GraphQLProjectConfiguration`.
+    /// Names of projects are validated, but not snake-cased during
+    /// deserialization.
+    pub projects: Option<map::Map<projects::ProjectName, GraphQLProjectConfiguration>>,
     /// Top-level configuration goes into `root`.
     #[serde(flatten)]
     pub root: GraphQLProjectConfiguration,
@@ -89,7 +201,11 @@ pub struct GraphQLConfiguration {
 
 /// The top-level configuration and project-specific
 /// configurations share this shape.
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
+///
+/// Like `GraphQLConfiguration`, this struct is constructed with `new` or
+/// `builder` outside of this crate.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 #[serde(rename_all = "camelCase")]
 pub struct GraphQLProjectConfiguration {
     /// The name of the project. The specification says this should default to
@@ -99,13 +215,44 @@ pub struct GraphQLProjectConfiguration {
     pub schema_path: Option<::std::path::PathBuf>,
     /// For multiple applications with overlapping files,
     /// these configuration options may be helpful.
-    pub includes: Option<Vec<String>>,
+    pub includes: Option<Vec<glob::GlobPattern>>,
     /// For multiple applications with overlapping files,
     /// these configuration options may be helpful.
-    pub excludes: Option<Vec<String>>,
+    pub excludes: Option<Vec<glob::GlobPattern>>,
     /// If you'd like to specify any other configurations,
     /// graphql-config provides a reserved namespace for it.
-    pub extensions: Option<::std::collections::BTreeMap<String, serde_json::Value>>,
+    pub extensions: Option<map::Map<String, serde_json::Value>>,
+}
+
+impl GraphQLConfiguration {
+    /// An empty configuration, without projects.
+    pub fn new() -> GraphQLConfiguration {
+        GraphQLConfiguration::default()
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// An empty project configuration.
+    pub fn new() -> GraphQLProjectConfiguration {
+        GraphQLProjectConfiguration::default()
+    }
+}
+
+impl ::std::hash::Hash for GraphQLConfiguration {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        map::hash(self.projects.as_ref(), state);
+        self.root.hash(state);
+    }
+}
+
+impl ::std::hash::Hash for GraphQLProjectConfiguration {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.schema_path.hash(state);
+        self.includes.hash(state);
+        self.excludes.hash(state);
+        map::hash(self.extensions.as_ref(), state);
+    }
 }
 
 #[cfg(test)]
@@ -156,8 +303,8 @@ mod tests {
                 excludes: None,
                 extensions: None,
             },
-            projects: Some(btreemap!{
-                "amazingLibrary".to_owned() => GraphQLProjectConfiguration {
+            projects: Some(map!{
+                "amazingLibrary".parse().unwrap() => GraphQLProjectConfiguration {
                     schema_path: Some("./amazingLibrary.schema.graphql".into()),
                     name: None,
                     includes: None,
@@ -191,15 +338,15 @@ mod tests {
                 excludes: None,
                 extensions: None,
             },
-            projects: Some(btreemap!{
-                "amazingLibrary".to_owned() => GraphQLProjectConfiguration {
+            projects: Some(map!{
+                "amazingLibrary".parse().unwrap() => GraphQLProjectConfiguration {
                     schema_path: Some("./amazingLibrary.schema.graphql".into()),
                     name: None,
                     includes: None,
                     excludes: None,
                     extensions: None,
                 },
-                "evenMoreAmazingLibrary".to_owned() => GraphQLProjectConfiguration {
+                "evenMoreAmazingLibrary".parse().unwrap() => GraphQLProjectConfiguration {
                     schema_path: Some("./evenMoreAmazingLibrary.schema.graphql".into()),
                     name: None,
                     includes: None,
@@ -234,15 +381,15 @@ mod tests {
                 excludes: None,
                 extensions: None,
             },
-            projects: Some(btreemap!{
-                "amazingLibrary".to_owned() => GraphQLProjectConfiguration {
+            projects: Some(map!{
+                "amazingLibrary".parse().unwrap() => GraphQLProjectConfiguration {
                     schema_path: Some("./amazingLibrary.schema.graphql".into()),
                     name: None,
                     includes: None,
                     excludes: None,
                     extensions: None,
                 },
-                "evenMoreAmazingLibrary".to_owned() => GraphQLProjectConfiguration {
+                "evenMoreAmazingLibrary".parse().unwrap() => GraphQLProjectConfiguration {
                     schema_path: Some("./evenMoreAmazingLibrary.schema.graphql".into()),
                     name: None,
                     includes: None,
@@ -270,7 +417,7 @@ mod tests {
                 includes: None,
                 excludes: None,
                 extensions: Some(
-                    btreemap!{ "lastUpdatedAt".to_owned() => json!(1532367255884u64) },
+                    map!{ "lastUpdatedAt".to_owned() => json!(1532367255884u64) },
                 ),
             },
             projects: None,
@@ -290,8 +437,8 @@ mod tests {
             root: GraphQLProjectConfiguration {
                 name: None,
                 schema_path: None,
-                includes: Some(vec!["./projectA/graphql/*.graphql".to_owned()]),
-                excludes: Some(vec!["./projectA/graphql/*.not_graphql".to_owned()]),
+                includes: Some(vec!["./projectA/graphql/*.graphql".parse().unwrap()]),
+                excludes: Some(vec!["./projectA/graphql/*.not_graphql".parse().unwrap()]),
                 extensions: None,
             },
             projects: None,
@@ -299,4 +446,33 @@ mod tests {
 
         test_deserialization(config, expected);
     }
+
+    #[test]
+    fn it_hashes_extensions_canonically() {
+        use std::collections::HashSet;
+
+        let config = |extensions: &str| {
+            serde_json::from_str::<GraphQLConfiguration>(&format!(
+                r#"{{ "extensions": {} }}"#,
+                extensions
+            ))
+            .unwrap()
+        };
+
+        let mut configs = HashSet::new();
+        configs.insert(config(r#"{ "a": 1, "b": { "c": true, "d": null } }"#));
+        configs.insert(config(r#"{ "b": { "d": null, "c": true }, "a": 1 }"#));
+        configs.insert(config(r#"{ "a": 1.0, "b": { "c": true, "d": null } }"#));
+
+        assert_eq!(configs.len(), 2);
+        assert!(configs.contains(&config(r#"{ "b": { "c": true, "d": null }, "a": 1 }"#)));
+    }
+
+    #[test]
+    fn it_defaults_to_an_empty_configuration() {
+        let empty = serde_json::from_value::<GraphQLConfiguration>(json!({})).unwrap();
+
+        assert_eq!(GraphQLConfiguration::default(), empty);
+        assert_eq!(empty.clone(), empty);
+    }
 }
@@ -0,0 +1,446 @@
+//! Reading configurations from files.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+#[cfg(feature = "fs")]
+use std::fs;
+use std::io;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "fs")]
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::v3::{ConversionError, DetectedVersion, SpecVersion, VersionedConfiguration};
+use crate::GraphQLConfiguration;
+
+/// The error returned when a configuration cannot be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not a valid JSON configuration.
+    Json(serde_json::Error),
+    /// The file is not a valid YAML configuration.
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    /// The format of the file, named by its extension, is not supported.
+    /// YAML files require the `yaml` feature.
+    UnsupportedFormat(String),
+    /// The file follows version 3 of the specification, and cannot be
+    /// converted to version 2.
+    Conversion(ConversionError),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Io(ref error) => write!(f, "cannot read the configuration: {}", error),
+            LoadError::Json(ref error) => write!(f, "invalid JSON configuration: {}", error),
+            #[cfg(feature = "yaml")]
+            LoadError::Yaml(ref error) => write!(f, "invalid YAML configuration: {}", error),
+            LoadError::UnsupportedFormat(ref extension) => {
+                write!(f, "unsupported configuration format `.{}`", extension)
+            }
+            LoadError::Conversion(ref error) => write!(f, "unsupported configuration: {}", error),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LoadError::Io(ref error) => Some(error),
+            LoadError::Json(ref error) => Some(error),
+            #[cfg(feature = "yaml")]
+            LoadError::Yaml(ref error) => Some(error),
+            LoadError::UnsupportedFormat(_) => None,
+            LoadError::Conversion(ref error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(error: io::Error) -> LoadError {
+        LoadError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(error: serde_json::Error) -> LoadError {
+        LoadError::Json(error)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for LoadError {
+    fn from(error: serde_yaml::Error) -> LoadError {
+        LoadError::Yaml(error)
+    }
+}
+
+impl From<ConversionError> for LoadError {
+    fn from(error: ConversionError) -> LoadError {
+        LoadError::Conversion(error)
+    }
+}
+
+/// Reads the configuration file at `path`. Files with a `.yaml` or `.yml`
+/// extension are read as YAML, files with a `.json` extension as JSON, and
+/// anything else, such as `.graphqlconfig`, in the format `Format::sniff`
+/// detects. A `package.json` file is configured by its `graphql` key, see
+/// `from_package_json`, and fails with `io::ErrorKind::NotFound` without
+/// one.
+///
+/// The file is read in the version of the specification `VersionedConfiguration`
+/// reads it in, and converted to version 2 if it follows version 3.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use std::convert::TryFrom;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), load::LoadError> {
+/// let config = GraphQLConfiguration::try_from(Path::new(".graphqlconfig"))?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "fs")]
+impl<'a> TryFrom<&'a Path> for GraphQLConfiguration {
+    type Error = LoadError;
+
+    fn try_from(path: &'a Path) -> Result<GraphQLConfiguration, LoadError> {
+        Ok(VersionedConfiguration::try_from(path)?.into_v2()?)
+    }
+}
+
+/// Parses the contents of a configuration file, in the format
+/// `Format::sniff` detects. Without the `yaml` feature, everything is parsed
+/// as JSON. Like files, configurations following version 3 are converted.
+impl<'a> TryFrom<&'a str> for GraphQLConfiguration {
+    type Error = LoadError;
+
+    fn try_from(contents: &'a str) -> Result<GraphQLConfiguration, LoadError> {
+        Ok(VersionedConfiguration::try_from(contents)?.into_v2()?)
+    }
+}
+
+/// Parses configuration text like `GraphQLConfiguration::try_from`.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # fn main() -> Result<(), load::LoadError> {
+/// let config: GraphQLConfiguration = r#"{ "schemaPath": "./schema.graphql" }"#.parse()?;
+/// assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+/// # Ok(())
+/// # }
+/// ```
+impl FromStr for GraphQLConfiguration {
+    type Err = LoadError;
+
+    fn from_str(contents: &str) -> Result<GraphQLConfiguration, LoadError> {
+        GraphQLConfiguration::try_from(contents)
+    }
+}
+
+#[cfg(feature = "fs")]
+impl GraphQLConfiguration {
+    /// Reads the configuration file at `path`, like
+    /// `GraphQLConfiguration::try_from`. `ResolvedGraphQLConfig::from_path`
+    /// also keeps its directory, which the paths in it are relative to.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<GraphQLConfiguration, LoadError> {
+        GraphQLConfiguration::try_from(path.as_ref())
+    }
+}
+
+#[cfg(feature = "fs")]
+impl ResolvedGraphQLConfig {
+    /// Reads the configuration file at `path`, and resolves it against its
+    /// directory.
+    ///
+    /// ```no_run
+    /// # extern crate graphql_config;
+    /// # use graphql_config::resolved::ResolvedGraphQLConfig;
+    /// # fn main() -> Result<(), graphql_config::load::LoadError> {
+    /// let config = ResolvedGraphQLConfig::from_path("/repo/.graphqlconfig")?;
+    /// assert_eq!(config.schema_path("default"), Some("/repo/schema.graphql".into()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<ResolvedGraphQLConfig, LoadError> {
+        let path = path.as_ref();
+        Ok(ResolvedGraphQLConfig::new(
+            GraphQLConfiguration::try_from(path)?,
+            path,
+        ))
+    }
+}
+
+/// Reads the configuration file at `path`, in the format
+/// `GraphQLConfiguration::try_from` reads it, and in the version of the
+/// specification `SpecVersion::detect` detects. Files which could follow
+/// either version, such as those with extensions only, follow the one of
+/// their name, and version 2 if it tells neither.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::v3::{SpecVersion, VersionedConfiguration};
+/// # use std::convert::TryFrom;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), graphql_config::load::LoadError> {
+/// let config = VersionedConfiguration::try_from(Path::new(".graphqlrc.yml"))?;
+/// if config.version() == SpecVersion::V2 {
+///     eprintln!("warning: `schemaPath` and `includes` are deprecated");
+/// }
+/// let config = config.into_v3();
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "fs")]
+impl<'a> TryFrom<&'a Path> for VersionedConfiguration {
+    type Error = LoadError;
+
+    fn try_from(path: &'a Path) -> Result<VersionedConfiguration, LoadError> {
+        let contents = fs::read_to_string(path)?;
+        if path.file_name() == Some("package.json".as_ref()) {
+            return package_config(&contents)?.ok_or_else(|| {
+                let message = format!("`{}` has no `graphql` key", path.display());
+                io::Error::new(io::ErrorKind::NotFound, message).into()
+            });
+        }
+        let fallback = path
+            .file_name()
+            .and_then(|name| SpecVersion::of_file_name(&name.to_string_lossy()))
+            .unwrap_or(SpecVersion::V2);
+        parse_versioned(&contents, Format::of_file(path, &contents), fallback)
+    }
+}
+
+/// Parses the contents of a configuration file like
+/// `GraphQLConfiguration::try_from`, in the version of the specification
+/// `SpecVersion::detect` detects, and version 2 if it could be either.
+impl<'a> TryFrom<&'a str> for VersionedConfiguration {
+    type Error = LoadError;
+
+    fn try_from(contents: &'a str) -> Result<VersionedConfiguration, LoadError> {
+        parse_versioned(contents, Format::of_contents(contents), SpecVersion::V2)
+    }
+}
+
+/// The format of a configuration file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// A JSON document.
+    Json,
+    /// A YAML document.
+    Yaml,
+}
+
+impl Format {
+    /// The format of `contents`, told from its first character without
+    /// parsing it: JSON configurations are objects, which start with `{`
+    /// after any whitespace and byte order mark, and anything else is taken
+    /// as YAML.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::load::Format;
+    /// assert_eq!(Format::sniff("\n{ \"schemaPath\": \"schema.graphql\" }"), Format::Json);
+    /// assert_eq!(Format::sniff("# comment\nschemaPath: schema.graphql"), Format::Yaml);
+    /// ```
+    pub fn sniff(contents: &str) -> Format {
+        let contents = contents.trim_start_matches('\u{feff}').trim_start();
+        if contents.starts_with('{') {
+            Format::Json
+        } else {
+            Format::Yaml
+        }
+    }
+
+    /// The format `contents` are parsed in: the one `sniff` detects, and
+    /// JSON without the `yaml` feature.
+    fn of_contents(contents: &str) -> Format {
+        match Format::sniff(contents) {
+            Format::Yaml if cfg!(feature = "yaml") => Format::Yaml,
+            _ => Format::Json,
+        }
+    }
+
+    /// The format of the file at `path`: the one of its extension, if it
+    /// is `.yaml`, `.yml` or `.json`, and the one of its contents otherwise.
+    #[cfg(feature = "fs")]
+    fn of_file(path: &Path, contents: &str) -> Format {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml") | Some("yml") => Format::Yaml,
+            Some("json") => Format::Json,
+            _ => Format::of_contents(contents),
+        }
+    }
+}
+
+/// The configuration of the `graphql` key of the contents of a
+/// `package.json` file, where the JavaScript implementation looks for one
+/// too, or `None` if there is no such key. Configurations following
+/// version 3 are converted.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::load;
+/// # fn main() -> Result<(), load::LoadError> {
+/// let package = r#"{ "name": "web", "graphql": { "schemaPath": "./schema.graphql" } }"#;
+/// let config = load::from_package_json(package)?.unwrap();
+/// assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+///
+/// assert!(load::from_package_json(r#"{ "name": "web" }"#)?.is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_package_json(contents: &str) -> Result<Option<GraphQLConfiguration>, LoadError> {
+    match package_config::<VersionedConfiguration>(contents)? {
+        Some(config) => Ok(Some(config.into_v2()?)),
+        None => Ok(None),
+    }
+}
+
+fn package_config<T: DeserializeOwned>(contents: &str) -> Result<Option<T>, LoadError> {
+    let mut package: serde_json::Map<String, serde_json::Value> = serde_json::from_str(contents)?;
+    match package.remove("graphql") {
+        Some(config) => Ok(Some(serde_json::from_value(config)?)),
+        None => Ok(None),
+    }
+}
+
+/// Parses `contents` in the version `SpecVersion::detect` detects, and in
+/// `fallback` if it could be either. The version is detected from the keys
+/// alone, skipping the values, and the document is then parsed in it once.
+/// Documents whose version cannot be detected, such as malformed ones, are
+/// parsed in `fallback`, which reports what is wrong with them.
+fn parse_versioned(
+    contents: &str,
+    format: Format,
+    fallback: SpecVersion,
+) -> Result<VersionedConfiguration, LoadError> {
+    let detected = parse(contents, format)
+        .ok()
+        .and_then(|DetectedVersion(version)| version);
+    match detected.unwrap_or(fallback) {
+        SpecVersion::V2 => parse(contents, format).map(VersionedConfiguration::V2),
+        SpecVersion::V3 => parse(contents, format).map(VersionedConfiguration::V3),
+    }
+}
+
+fn parse<T: DeserializeOwned>(contents: &str, format: Format) -> Result<T, LoadError> {
+    match format {
+        Format::Json => Ok(serde_json::from_str(contents)?),
+        Format::Yaml => from_yaml(contents),
+    }
+}
+
+#[cfg(feature = "yaml")]
+fn from_yaml<T: DeserializeOwned>(contents: &str) -> Result<T, LoadError> {
+    Ok(serde_yaml::from_str(contents)?)
+}
+
+#[cfg(not(feature = "yaml"))]
+fn from_yaml<T>(_contents: &str) -> Result<T, LoadError> {
+    Err(LoadError::UnsupportedFormat("yaml".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "fs")]
+    use crate::test_dir::TestDir;
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn it_loads_json_files() {
+        let dir = TestDir::new("it_loads_json_files");
+        let path = dir.join(".graphqlconfig");
+        fs::write(&path, r#"{ "schemaPath": "./schema.graphql" }"#).unwrap();
+
+        let config = GraphQLConfiguration::try_from(path.as_path()).unwrap();
+        assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+        let resolved = ResolvedGraphQLConfig::from_path(&path).unwrap();
+        assert_eq!(resolved.raw(), &config);
+        assert_eq!(
+            resolved.schema_path("default"),
+            Some(dir.join("schema.graphql"))
+        );
+
+        match GraphQLConfiguration::from_path(dir.join("missing")) {
+            Err(LoadError::Io(ref error)) if error.kind() == io::ErrorKind::NotFound => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn it_loads_the_graphql_key_of_package_json() {
+        let dir = TestDir::new("it_loads_the_graphql_key_of_package_json");
+        let path = dir.join("package.json");
+        fs::write(
+            &path,
+            r#"{ "name": "web", "graphql": { "schemaPath": "./schema.graphql" } }"#,
+        )
+        .unwrap();
+
+        let config = GraphQLConfiguration::try_from(path.as_path()).unwrap();
+        assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+
+        fs::write(&path, r#"{ "name": "web" }"#).unwrap();
+        match GraphQLConfiguration::try_from(path.as_path()) {
+            Err(LoadError::Io(ref error)) if error.kind() == io::ErrorKind::NotFound => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn it_converts_modern_files() {
+        let dir = TestDir::new("it_converts_modern_files");
+        let path = dir.join(".graphqlrc.json");
+        fs::write(
+            &path,
+            r#"{ "schema": "./schema.graphql", "documents": ["./src/*.graphql"] }"#,
+        )
+        .unwrap();
+
+        let config = GraphQLConfiguration::from_path(&path).unwrap();
+        assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+        assert_eq!(
+            config.root.includes,
+            Some(vec!["./src/*.graphql".parse().unwrap()])
+        );
+
+        fs::write(&path, r#"{ "schema": ["./a.graphql", "./b.graphql"] }"#).unwrap();
+        match GraphQLConfiguration::from_path(&path) {
+            Err(LoadError::Conversion(_)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_reports_invalid_json() {
+        match GraphQLConfiguration::try_from(r#"{ "schemaPath": 1 }"#) {
+            Err(LoadError::Json(_)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn it_sniffs_yaml_contents() {
+        let config = GraphQLConfiguration::try_from("schemaPath: ./schema.graphql\n").unwrap();
+        assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+
+        match GraphQLConfiguration::try_from("schemaPath: [") {
+            Err(LoadError::Yaml(_)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! Building blocks for GraphQL language servers.
+//!
+//! Language servers speak of `file://` URIs rather than paths, and are told
+//! of changes to files with `workspace/didChangeWatchedFiles` notifications.
+//! A `Workspace` holds the configuration of a workspace folder and answers
+//! in those terms: which project a document belongs to, which projects a
+//! schema file makes up, and what a batch of file events means for them,
+//! reloading the configuration when its own file changes.
+
+use std::convert::TryFrom;
+use std::path::{Component, Path, PathBuf};
+
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+use crate::glob::{self, GlobPattern};
+use crate::index::FileIndex;
+use crate::load::LoadError;
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::schema::SchemaPointer;
+use crate::GraphQLConfiguration;
+
+/// The configuration of a workspace folder, queried by URI.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::lsp::Workspace;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "projects": {
+///         "web": { "schemaPath": "web/schema.graphql", "includes": ["web/**/*.graphql"] }
+///     }
+/// }))?;
+/// let workspace = Workspace::new(ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig"));
+///
+/// assert_eq!(workspace.project_for_uri("file:///repo/web/src/query.graphql"), Some("web"));
+/// assert_eq!(workspace.schema_projects("file:///repo/web/schema.graphql"), vec!["web"]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Workspace {
+    config: ResolvedGraphQLConfig,
+    index: FileIndex,
+}
+
+/// The parameters of a `workspace/didChangeWatchedFiles` notification.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct DidChangeWatchedFilesParams {
+    /// The events, in the order they happened.
+    pub changes: Vec<FileEvent>,
+}
+
+/// A file the client watches was created, changed or deleted.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FileEvent {
+    /// The `file://` URI of the file.
+    pub uri: String,
+    /// What happened to the file.
+    #[serde(rename = "type")]
+    pub kind: FileChangeType,
+}
+
+/// The `FileChangeType` of the protocol, serialized as its number.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FileChangeType {
+    /// The file was created, `1`.
+    Created,
+    /// The file was changed, `2`.
+    Changed,
+    /// The file was deleted, `3`.
+    Deleted,
+}
+
+impl Serialize for FileChangeType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(match *self {
+            FileChangeType::Created => 1,
+            FileChangeType::Changed => 2,
+            FileChangeType::Deleted => 3,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for FileChangeType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<FileChangeType, D::Error> {
+        match u8::deserialize(deserializer)? {
+            1 => Ok(FileChangeType::Created),
+            2 => Ok(FileChangeType::Changed),
+            3 => Ok(FileChangeType::Deleted),
+            kind => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(kind.into()),
+                &"a FileChangeType of 1, 2 or 3",
+            )),
+        }
+    }
+}
+
+/// What a file event means for the workspace.
+#[derive(Debug)]
+pub enum WorkspaceChange {
+    /// The configuration file changed and was loaded again.
+    ConfigReloaded,
+    /// The configuration file changed but could not be loaded, and the
+    /// previous configuration is kept.
+    ConfigInvalid(LoadError),
+    /// A file making up the schema of the project changed.
+    SchemaChanged {
+        /// The project.
+        project: String,
+    },
+    /// A document of the project was created, changed or deleted.
+    DocumentChanged {
+        /// The project.
+        project: String,
+        /// The URI of the document.
+        uri: String,
+        /// What happened to it.
+        kind: FileChangeType,
+    },
+}
+
+impl Workspace {
+    /// The workspace of `config`.
+    pub fn new(config: ResolvedGraphQLConfig) -> Workspace {
+        Workspace {
+            index: FileIndex::new(&config),
+            config,
+        }
+    }
+
+    /// Loads the workspace of the configuration file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Workspace, LoadError> {
+        Ok(Workspace::new(ResolvedGraphQLConfig::from_path(path)?))
+    }
+
+    /// The configuration of the workspace.
+    pub fn config(&self) -> &ResolvedGraphQLConfig {
+        &self.config
+    }
+
+    /// The project the document at `uri` belongs to, see
+    /// `FileIndex::project_for_file`.
+    pub fn project_for_uri(&self, uri: &str) -> Option<&str> {
+        self.index.project_for_file(uri_to_path(uri)?)
+    }
+
+    /// The projects whose `schemaPath` names or matches the file at `uri`,
+    /// as listed by `ResolvedGraphQLConfig::projects`.
+    pub fn schema_projects(&self, uri: &str) -> Vec<&str> {
+        let path = match uri_to_path(uri) {
+            Some(path) => path,
+            None => return Vec::new(),
+        };
+        self.config
+            .projects()
+            .map(|(name, _)| name.as_str())
+            .filter(|name| self.is_schema_of(name, &path))
+            .collect()
+    }
+
+    fn is_schema_of(&self, name: &str, path: &Path) -> bool {
+        let schema_path = match self
+            .config
+            .project(name)
+            .and_then(|project| project.schema_path)
+        {
+            Some(schema_path) => schema_path,
+            None => return false,
+        };
+        let schema_path = schema_path.to_string_lossy();
+        match SchemaPointer::classify(&schema_path, self.config.dir()) {
+            SchemaPointer::File(schema) => normalize(&schema) == path,
+            SchemaPointer::Glob(_) => {
+                let relative = path
+                    .strip_prefix(normalize(self.config.dir()))
+                    .ok()
+                    .and_then(glob::to_slash);
+                match (GlobPattern::new(schema_path.as_ref()), relative) {
+                    (Ok(pattern), Some(relative)) => pattern.matches(&relative),
+                    _ => false,
+                }
+            }
+            SchemaPointer::Url(_) => false,
+        }
+    }
+
+    /// Applies the events of a `workspace/didChangeWatchedFiles`
+    /// notification, reloading the configuration if its file is among them,
+    /// and returns what they mean for the projects. Files the configuration
+    /// does not know of are ignored.
+    pub fn did_change_watched_files(
+        &mut self,
+        params: DidChangeWatchedFilesParams,
+    ) -> Vec<WorkspaceChange> {
+        let mut changes = Vec::new();
+        for event in params.changes {
+            let path = match uri_to_path(&event.uri) {
+                Some(path) => path,
+                None => continue,
+            };
+            if path == normalize(self.config.path()) {
+                changes.push(self.reload());
+                continue;
+            }
+
+            for project in self.schema_projects(&event.uri) {
+                changes.push(WorkspaceChange::SchemaChanged {
+                    project: project.to_owned(),
+                });
+            }
+            if let Some(project) = self.index.project_for_file(&path) {
+                changes.push(WorkspaceChange::DocumentChanged {
+                    project: project.to_owned(),
+                    uri: event.uri,
+                    kind: event.kind,
+                });
+            }
+        }
+        changes
+    }
+
+    fn reload(&mut self) -> WorkspaceChange {
+        match GraphQLConfiguration::try_from(self.config.path()) {
+            Ok(raw) => {
+                let config = ResolvedGraphQLConfig::new(raw, self.config.path())
+                    .with_env(self.config.env().clone())
+                    .with_host_map(self.config.host_map().clone());
+                *self = Workspace::new(config);
+                WorkspaceChange::ConfigReloaded
+            }
+            Err(error) => WorkspaceChange::ConfigInvalid(error),
+        }
+    }
+}
+
+/// The path of a `file://` URI, with its percent-escapes decoded, or `None`
+/// for other URIs. On Windows, `file:///C:/repo` is `C:/repo`.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::lsp::uri_to_path;
+/// # use std::path::PathBuf;
+/// assert_eq!(
+///     uri_to_path("file:///repo/my%20app/query.graphql"),
+///     Some(PathBuf::from("/repo/my app/query.graphql"))
+/// );
+/// assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+/// ```
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    // A host, rarely anything but `localhost`, comes before the path.
+    let path = &path[path.find('/')?..];
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+
+    let is_drive = |path: &str| {
+        let path = path.as_bytes();
+        path.len() >= 3 && path[0] == b'/' && path[1].is_ascii_alphabetic() && path[2] == b':'
+    };
+    if cfg!(windows) && is_drive(&path) {
+        Some(normalize(Path::new(&path[1..])))
+    } else {
+        Some(normalize(Path::new(&path)))
+    }
+}
+
+/// The `file://` URI of the absolute `path`, escaping every byte but
+/// unreserved characters and `/`.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::lsp::path_to_uri;
+/// assert_eq!(path_to_uri("/repo/my app/query.graphql"), "file:///repo/my%20app/query.graphql");
+/// ```
+pub fn path_to_uri(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref().to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            // The colon of Windows drives is kept, as editors write it.
+            b':' => uri.push(':'),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// `path` with its `.` and `..` components resolved, so that it compares
+/// equal to the paths of URIs.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use std::fs;
+
+    #[test]
+    fn it_follows_file_events() {
+        let dir = TestDir::new("it_follows_file_events");
+        let config = dir.join(".graphqlconfig");
+        fs::write(
+            &config,
+            r#"{ "schemaPath": "schema/*.graphql", "includes": ["src/**"] }"#,
+        )
+        .unwrap();
+        let mut workspace = Workspace::load(&config).unwrap();
+
+        let params: DidChangeWatchedFilesParams = ::serde_json::from_value(json!({
+            "changes": [
+                { "uri": path_to_uri(dir.join("schema/user.graphql")), "type": 2 },
+                { "uri": path_to_uri(dir.join("src/query.graphql")), "type": 1 },
+                { "uri": path_to_uri(dir.join("README.md")), "type": 3 }
+            ]
+        }))
+        .unwrap();
+        let changes = workspace.did_change_watched_files(params);
+        assert_eq!(changes.len(), 2, "{:?}", changes);
+        match changes[0] {
+            WorkspaceChange::SchemaChanged { ref project } => assert_eq!(project, "default"),
+            ref change => panic!("unexpected {:?}", change),
+        }
+        match changes[1] {
+            WorkspaceChange::DocumentChanged {
+                ref project, kind, ..
+            } => {
+                assert_eq!(project, "default");
+                assert_eq!(kind, FileChangeType::Created);
+            }
+            ref change => panic!("unexpected {:?}", change),
+        }
+
+        fs::write(&config, r#"{ "includes": ["lib/**"] }"#).unwrap();
+        let reload = DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                uri: path_to_uri(&config),
+                kind: FileChangeType::Changed,
+            }],
+        };
+        match workspace.did_change_watched_files(reload.clone())[..] {
+            [WorkspaceChange::ConfigReloaded] => {}
+            ref changes => panic!("unexpected {:?}", changes),
+        }
+        let query = path_to_uri(dir.join("src/query.graphql"));
+        assert_eq!(workspace.project_for_uri(&query), None);
+
+        fs::write(&config, "{").unwrap();
+        match workspace.did_change_watched_files(reload)[..] {
+            [WorkspaceChange::ConfigInvalid(LoadError::Json(_))] => {}
+            ref changes => panic!("unexpected {:?}", changes),
+        }
+        let query = path_to_uri(dir.join("lib/query.graphql"));
+        assert_eq!(workspace.project_for_uri(&query), Some("default"));
+    }
+}
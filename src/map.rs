@@ -0,0 +1,130 @@
+//! The map type of `projects` and `extensions`.
+//!
+//! Maps are `BTreeMap`s by default, iterating in key order. Consumers can
+//! choose another container with a feature:
+//!
+//! - `preserve-order`: `IndexMap`, iterating in the order of the file.
+//! - `hash-map`: `HashMap`, for faster lookups in large configurations.
+//!
+//! `preserve-order` takes precedence when both are enabled. Whatever the
+//! container, maps are compared and hashed regardless of their order, and
+//! listed, printed and rendered in key order unless `preserve-order` is
+//! enabled. Serialization follows the iteration order of the container.
+
+use std::hash::{Hash, Hasher};
+
+/// The map type of `projects` and `extensions`.
+#[cfg(not(any(feature = "preserve-order", feature = "hash-map")))]
+pub type Map<K, V> = std::collections::BTreeMap<K, V>;
+
+/// The map type of `projects` and `extensions`.
+#[cfg(all(feature = "hash-map", not(feature = "preserve-order")))]
+pub type Map<K, V> = std::collections::HashMap<K, V>;
+
+/// The map type of `projects` and `extensions`.
+#[cfg(feature = "preserve-order")]
+pub type Map<K, V> = indexmap::IndexMap<K, V>;
+
+/// The entries of `map` in the order they are listed: in the order of the
+/// map with `preserve-order`, in key order otherwise.
+pub(crate) fn ordered<K: Ord, V>(map: &Map<K, V>) -> Vec<(&K, &V)> {
+    #[allow(unused_mut)]
+    let mut entries: Vec<(&K, &V)> = map.iter().collect();
+    #[cfg(all(feature = "hash-map", not(feature = "preserve-order")))]
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Like `ordered`, with mutable values.
+pub(crate) fn ordered_mut<K: Ord, V>(map: &mut Map<K, V>) -> Vec<(&K, &mut V)> {
+    #[allow(unused_mut)]
+    let mut entries: Vec<(&K, &mut V)> = map.iter_mut().collect();
+    #[cfg(all(feature = "hash-map", not(feature = "preserve-order")))]
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+/// Removes `key` from `map`, keeping the order of the other entries.
+#[cfg(feature = "preserve-order")]
+pub(crate) fn remove<K, V>(map: &mut Map<K, V>, key: &str) -> Option<V>
+where
+    K: std::borrow::Borrow<str> + Hash + Eq,
+{
+    map.shift_remove(key)
+}
+
+/// Removes `key` from `map`.
+#[cfg(not(feature = "preserve-order"))]
+pub(crate) fn remove<K, V>(map: &mut Map<K, V>, key: &str) -> Option<V>
+where
+    K: std::borrow::Borrow<str> + Ord + Hash + Eq,
+{
+    map.remove(key)
+}
+
+/// Hashes `map` in key order, so that equal maps hash equally whatever the
+/// order of their entries.
+pub(crate) fn hash<K, V, H>(map: Option<&Map<K, V>>, state: &mut H)
+where
+    K: Ord + Hash,
+    V: Hash,
+    H: Hasher,
+{
+    let map = match map {
+        Some(map) => map,
+        None => return state.write_u8(0),
+    };
+
+    let mut entries: Vec<(&K, &V)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    state.write_u8(1);
+    state.write_usize(entries.len());
+    for entry in entries {
+        entry.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hashed(map: &Map<String, u8>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash(Some(map), &mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn it_hashes_regardless_of_order() {
+        let mut a = Map::new();
+        a.insert("a".to_owned(), 1);
+        a.insert("b".to_owned(), 2);
+        let mut b = Map::new();
+        b.insert("b".to_owned(), 2);
+        b.insert("a".to_owned(), 1);
+
+        assert_eq!(a, b);
+        assert_eq!(hashed(&a), hashed(&b));
+
+        assert_eq!(remove(&mut a, "a"), Some(1));
+        assert_eq!(remove(&mut a, "a"), None);
+        assert_ne!(hashed(&a), hashed(&b));
+    }
+
+    #[test]
+    fn it_lists_projects_in_key_or_file_order() {
+        let config: crate::GraphQLConfiguration =
+            serde_json::from_str(r#"{ "projects": { "lib": {}, "app": {} } }"#).unwrap();
+        let names: Vec<&str> = config
+            .iter_projects()
+            .map(|(name, _)| name.as_str())
+            .collect();
+
+        if cfg!(feature = "preserve-order") {
+            assert_eq!(names, vec!["lib", "app"]);
+        } else {
+            assert_eq!(names, vec!["app", "lib"]);
+        }
+    }
+}
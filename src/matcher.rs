@@ -0,0 +1,163 @@
+//! Sets of `includes` and `excludes`, shared between call sites.
+//!
+//! Every `ProjectHandle` of a `ResolvedGraphQLConfig` matches paths with a
+//! `GlobSet` taken from the `MatcherCache` of the configuration, so that
+//! handles of projects with the same patterns, created wherever, share one
+//! set. Sets are keyed by the patterns themselves: a changed configuration
+//! gets new sets rather than stale ones.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use crate::glob::GlobPattern;
+
+/// The patterns deciding which files belong to a project.
+///
+/// Sets serialize as their `includes` and `excludes`, so that they can be
+/// saved along with other caches. A pattern is compiled into little more than
+/// its characters, so loading a set compiles it again at no extra cost.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct GlobSet {
+    includes: Vec<GlobPattern>,
+    excludes: Vec<GlobPattern>,
+}
+
+impl GlobSet {
+    /// The set of `includes`, empty meaning all files, and `excludes`.
+    pub fn new(includes: Vec<GlobPattern>, excludes: Vec<GlobPattern>) -> GlobSet {
+        GlobSet { includes, excludes }
+    }
+
+    /// Whether the `/`-separated `path` is matched by one of the `includes`,
+    /// or there are none, and by none of the `excludes`.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::matcher::GlobSet;
+    /// # fn main() -> Result<(), graphql_config::glob::PatternError> {
+    /// let set = GlobSet::new(vec!["src/**".parse()?], vec!["**/*.js".parse()?]);
+    ///
+    /// assert!(set.matches("src/query.graphql"));
+    /// assert!(!set.matches("src/index.js"));
+    /// assert!(!set.matches("lib/query.graphql"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn matches(&self, path: &str) -> bool {
+        let matches = |pattern: &GlobPattern| pattern.matches(path);
+        (self.includes.is_empty() || self.includes.iter().any(matches))
+            && !self.excludes.iter().any(matches)
+    }
+
+    /// Whether some file under the `/`-separated directory `dir` may be
+    /// matched, see `GlobPattern::may_match_under`.
+    pub fn may_match_under(&self, dir: &str) -> bool {
+        self.includes.is_empty()
+            || self
+                .includes
+                .iter()
+                .any(|pattern| pattern.may_match_under(dir))
+    }
+
+    /// The include patterns.
+    pub fn includes(&self) -> &[GlobPattern] {
+        &self.includes
+    }
+
+    /// The exclude patterns.
+    pub fn excludes(&self) -> &[GlobPattern] {
+        &self.excludes
+    }
+}
+
+/// The `GlobSet`s built so far, by the hash of their patterns.
+#[derive(Default, Debug)]
+pub struct MatcherCache {
+    sets: Mutex<HashMap<u64, Vec<Arc<GlobSet>>>>,
+}
+
+impl MatcherCache {
+    /// An empty cache.
+    pub fn new() -> MatcherCache {
+        MatcherCache::default()
+    }
+
+    /// The set of `includes` and `excludes`, built unless it is cached.
+    pub fn get(&self, includes: &[GlobPattern], excludes: &[GlobPattern]) -> Arc<GlobSet> {
+        let mut hasher = DefaultHasher::new();
+        includes.hash(&mut hasher);
+        excludes.hash(&mut hasher);
+
+        let mut sets = self.sets.lock().unwrap_or_else(|error| error.into_inner());
+        let sets = sets.entry(hasher.finish()).or_default();
+        if let Some(set) = sets
+            .iter()
+            .find(|set| set.includes == includes && set.excludes == excludes)
+        {
+            return Arc::clone(set);
+        }
+        let set = Arc::new(GlobSet::new(includes.to_vec(), excludes.to_vec()));
+        sets.push(Arc::clone(&set));
+        set
+    }
+
+    /// The number of cached sets.
+    pub fn len(&self) -> usize {
+        let sets = self.sets.lock().unwrap_or_else(|error| error.into_inner());
+        sets.values().map(Vec::len).sum()
+    }
+
+    /// Whether no set is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops every cached set.
+    pub fn clear(&self) {
+        self.sets
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .clear();
+    }
+}
+
+impl Clone for MatcherCache {
+    fn clone(&self) -> MatcherCache {
+        let sets = self.sets.lock().unwrap_or_else(|error| error.into_inner());
+        MatcherCache {
+            sets: Mutex::new(sets.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_shares_sets_of_equal_patterns() {
+        let patterns = |patterns: &[&str]| -> Vec<GlobPattern> {
+            patterns
+                .iter()
+                .map(|pattern| pattern.parse().unwrap())
+                .collect()
+        };
+        let cache = MatcherCache::new();
+
+        let a = cache.get(&patterns(&["src/**"]), &[]);
+        let b = cache.get(&patterns(&["src/**"]), &[]);
+        let c = cache.get(&[], &patterns(&["src/**"]));
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &c));
+        assert_eq!(cache.len(), 2);
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let saved = ::serde_json::to_string(&*a).unwrap();
+        assert_eq!(saved, r#"{"includes":["src/**"],"excludes":[]}"#);
+        assert_eq!(::serde_json::from_str::<GlobSet>(&saved).unwrap(), *a);
+    }
+}
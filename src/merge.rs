@@ -0,0 +1,179 @@
+//! Merging configurations into one another.
+//!
+//! `Merge` lays one configuration over another, which is what `extends`,
+//! cascading discovery and programmatic overlays all come down to:
+//!
+//! - `name` and `schemaPath` are replaced when the other configuration sets
+//!   them,
+//! - `includes` and `excludes` are concatenated, dropping repeated patterns,
+//! - `extensions` are merged deeply: objects key by key, any other value
+//!   replaced,
+//! - projects are merged by key, projects only found in the other
+//!   configuration are added.
+
+use serde_json::Value;
+
+use crate::glob::GlobPattern;
+use crate::map::Map;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// Lays `other` over `self`, see the [module documentation](index.html).
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::merge::Merge;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let mut config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "schemaPath": "./schema.graphql",
+///     "includes": ["./src/*.graphql"],
+///     "extensions": { "codegen": { "output": "./generated", "hooks": true } }
+/// }))?;
+/// let overlay: GraphQLConfiguration = serde_json::from_value(json!({
+///     "includes": ["./src/*.graphql", "./lib/*.graphql"],
+///     "extensions": { "codegen": { "output": "./gen" } }
+/// }))?;
+///
+/// config.merge(overlay);
+///
+/// assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+/// assert_eq!(
+///     serde_json::to_value(&config.root.includes)?,
+///     json!(["./src/*.graphql", "./lib/*.graphql"])
+/// );
+/// assert_eq!(
+///     config.root.extensions.unwrap()["codegen"],
+///     json!({ "output": "./gen", "hooks": true })
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub trait Merge {
+    /// Lays `other` over `self`.
+    fn merge(&mut self, other: Self);
+}
+
+impl Merge for GraphQLConfiguration {
+    fn merge(&mut self, other: GraphQLConfiguration) {
+        self.root.merge(other.root);
+
+        if let Some(other) = other.projects {
+            let projects = self.projects.get_or_insert_with(Map::new);
+            for (key, project) in other {
+                match projects.get_mut(&key) {
+                    Some(existing) => existing.merge(project),
+                    None => {
+                        projects.insert(key, project);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Merge for GraphQLProjectConfiguration {
+    fn merge(&mut self, other: GraphQLProjectConfiguration) {
+        if other.name.is_some() {
+            self.name = other.name;
+        }
+        if other.schema_path.is_some() {
+            self.schema_path = other.schema_path;
+        }
+        merge_patterns(&mut self.includes, other.includes);
+        merge_patterns(&mut self.excludes, other.excludes);
+        if let Some(ref other) = other.extensions {
+            merge_extensions(self.extensions.get_or_insert_with(Map::new), other);
+        }
+    }
+}
+
+fn merge_patterns(patterns: &mut Option<Vec<GlobPattern>>, other: Option<Vec<GlobPattern>>) {
+    let other = match other {
+        Some(other) => other,
+        None => return,
+    };
+    let patterns = patterns.get_or_insert_with(Vec::new);
+    for pattern in other {
+        if !patterns.contains(&pattern) {
+            patterns.push(pattern);
+        }
+    }
+}
+
+/// Merges every extension of `overlay` into `extensions` with `deep_merge`.
+pub(crate) fn merge_extensions(extensions: &mut Map<String, Value>, overlay: &Map<String, Value>) {
+    for (name, value) in overlay {
+        match extensions.get_mut(name) {
+            Some(existing) => deep_merge(existing, value),
+            None => {
+                extensions.insert(name.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Merges `overlay` into `target` key by key if both are objects, and
+/// replaces `target` otherwise.
+pub(crate) fn deep_merge(target: &mut Value, overlay: &Value) {
+    match (target, overlay) {
+        (Value::Object(target), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match target.get_mut(key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, overlay) => *target = overlay.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: Value) -> GraphQLConfiguration {
+        ::serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn it_merges_projects_by_key() {
+        let mut base = config(json!({
+            "projects": {
+                "app": { "name": "App", "schemaPath": "./app.graphql", "excludes": ["./gen/*"] }
+            }
+        }));
+        base.merge(config(json!({
+            "projects": {
+                "app": { "schemaPath": "./schema.graphql", "excludes": ["./dist/*"] },
+                "lib": { "schemaPath": "./lib.graphql" }
+            }
+        })));
+
+        assert_eq!(
+            base,
+            config(json!({
+                "projects": {
+                    "app": {
+                        "name": "App",
+                        "schemaPath": "./schema.graphql",
+                        "excludes": ["./gen/*", "./dist/*"]
+                    },
+                    "lib": { "schemaPath": "./lib.graphql" }
+                }
+            }))
+        );
+    }
+
+    #[test]
+    fn it_replaces_everything_but_objects() {
+        let mut extensions = json!({ "a": [1], "b": { "c": 1 } });
+        deep_merge(&mut extensions, &json!({ "a": [2], "b": { "d": 2 } }));
+
+        assert_eq!(extensions, json!({ "a": [2], "b": { "c": 1, "d": 2 } }));
+    }
+}
@@ -0,0 +1,143 @@
+//! [RFC 7396](https://tools.ietf.org/html/rfc7396) JSON Merge Patch support.
+
+use serde_json::{self, Value};
+
+use crate::GraphQLConfiguration;
+
+impl GraphQLConfiguration {
+    /// Applies a JSON Merge Patch to the configuration.
+    ///
+    /// The patch is written against the JSON representation of the
+    /// configuration: objects are merged recursively, `null` removes a key and
+    /// any other value replaces the current one. If the patched document is
+    /// not a valid configuration an error is returned and `self` is left
+    /// untouched.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let mut config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "./schema.graphql",
+    ///     "projects": { "app": { "schemaPath": "./app.graphql" } }
+    /// }))?;
+    ///
+    /// config.apply_merge_patch(&json!({
+    ///     "includes": ["./src/*.graphql"],
+    ///     "projects": { "app": null }
+    /// }))?;
+    ///
+    /// assert_eq!(config.root.includes, Some(vec!["./src/*.graphql".parse().unwrap()]));
+    /// assert_eq!(config.projects, Some(Default::default()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply_merge_patch(&mut self, patch: &Value) -> Result<(), serde_json::Error> {
+        let mut document = serde_json::to_value(&*self)?;
+        merge_patch(&mut document, patch);
+        *self = serde_json::from_value(document)?;
+        Ok(())
+    }
+}
+
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let patch = match *patch {
+        Value::Object(ref patch) => patch,
+        _ => {
+            *target = patch.clone();
+            return;
+        }
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    let target = target.as_object_mut().expect("target was made an object");
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_follows_the_rfc_examples() {
+        let mut target = json!({
+            "title": "Goodbye!",
+            "author": { "givenName": "John", "familyName": "Doe" },
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+
+        merge_patch(
+            &mut target,
+            &json!({
+                "title": "Hello!",
+                "phoneNumber": "+01-123-456-7890",
+                "author": { "familyName": null },
+                "tags": ["example"]
+            }),
+        );
+
+        assert_eq!(
+            target,
+            json!({
+                "title": "Hello!",
+                "author": { "givenName": "John" },
+                "tags": ["example"],
+                "content": "This will be unchanged",
+                "phoneNumber": "+01-123-456-7890"
+            })
+        );
+    }
+
+    #[test]
+    fn it_patches_nested_projects_and_extensions() {
+        let mut config: GraphQLConfiguration = serde_json::from_value(json!({
+            "projects": {
+                "app": {
+                    "schemaPath": "./app.graphql",
+                    "extensions": { "endpoint": "http://localhost", "lastUpdatedAt": 1 }
+                }
+            }
+        }))
+        .unwrap();
+
+        config
+            .apply_merge_patch(&json!({
+                "projects": {
+                    "app": { "extensions": { "lastUpdatedAt": null } },
+                    "admin": { "schemaPath": "./admin.graphql" }
+                }
+            }))
+            .unwrap();
+
+        let projects = config.projects.unwrap();
+        assert_eq!(
+            projects["app"].extensions,
+            Some(map! { "endpoint".to_owned() => json!("http://localhost") })
+        );
+        assert_eq!(
+            projects["admin"].schema_path,
+            Some("./admin.graphql".into())
+        );
+    }
+
+    #[test]
+    fn it_leaves_the_config_untouched_on_invalid_patches() {
+        let mut config: GraphQLConfiguration =
+            serde_json::from_value(json!({ "schemaPath": "./schema.graphql" })).unwrap();
+
+        assert!(config.apply_merge_patch(&json!({ "includes": 1 })).is_err());
+        assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+    }
+}
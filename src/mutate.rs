@@ -0,0 +1,220 @@
+//! Mutators keeping configurations tidy.
+//!
+//! Unlike writing to the fields directly, these never leave repeated
+//! patterns, empty lists or an empty `projects` object behind, and keep the
+//! `name` of a renamed project in line with its key.
+
+use std::path::PathBuf;
+
+use crate::glob::GlobPattern;
+use crate::map;
+use crate::projects::ProjectName;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+impl GraphQLProjectConfiguration {
+    /// Sets `schemaPath`.
+    pub fn set_schema_path(&mut self, schema_path: impl Into<PathBuf>) {
+        self.schema_path = Some(schema_path.into());
+    }
+
+    /// Removes `schemaPath`, returning it.
+    pub fn clear_schema_path(&mut self) -> Option<PathBuf> {
+        self.schema_path.take()
+    }
+
+    /// Adds `pattern` to `includes` unless it is already there. Returns
+    /// whether it was added.
+    pub fn add_include(&mut self, pattern: GlobPattern) -> bool {
+        add_pattern(&mut self.includes, pattern)
+    }
+
+    /// Removes `pattern` from `includes`, removing the list if it becomes
+    /// empty. Returns whether it was there.
+    pub fn remove_include(&mut self, pattern: &str) -> bool {
+        remove_pattern(&mut self.includes, pattern)
+    }
+
+    /// Adds `pattern` to `excludes` unless it is already there. Returns
+    /// whether it was added.
+    pub fn add_exclude(&mut self, pattern: GlobPattern) -> bool {
+        add_pattern(&mut self.excludes, pattern)
+    }
+
+    /// Removes `pattern` from `excludes`, removing the list if it becomes
+    /// empty. Returns whether it was there.
+    pub fn remove_exclude(&mut self, pattern: &str) -> bool {
+        remove_pattern(&mut self.excludes, pattern)
+    }
+}
+
+impl GraphQLConfiguration {
+    /// Adds or replaces the project `key`, returning the replaced one.
+    pub fn insert_project(
+        &mut self,
+        key: ProjectName,
+        project: GraphQLProjectConfiguration,
+    ) -> Option<GraphQLProjectConfiguration> {
+        self.projects
+            .get_or_insert_with(Default::default)
+            .insert(key, project)
+    }
+
+    /// Removes the project `key`, removing `projects` if none are left.
+    pub fn remove_project(&mut self, key: &str) -> Option<GraphQLProjectConfiguration> {
+        let removed = map::remove(self.projects.as_mut()?, key);
+        if self
+            .projects
+            .as_ref()
+            .is_some_and(|projects| projects.is_empty())
+        {
+            self.projects = None;
+        }
+        removed
+    }
+
+    /// Moves the project `from` to the key `to`. A `name` equal to the old
+    /// key is renamed along with it. Returns `false`, changing nothing, if
+    /// there is no project `from` or there already is a project `to`.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::projects::ProjectName;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "projects": { "app": { "name": "app" } }
+    /// }))?;
+    ///
+    /// assert!(config.rename_project("app", ProjectName::new("web")?));
+    /// assert_eq!(config.project("web").unwrap().name, Some("web".to_owned()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rename_project(&mut self, from: &str, to: ProjectName) -> bool {
+        let projects = match self.projects {
+            Some(ref mut projects) if !projects.contains_key(&to) => projects,
+            _ => return false,
+        };
+        let mut project = match map::remove(projects, from) {
+            Some(project) => project,
+            None => return false,
+        };
+
+        if project.name.as_ref().is_some_and(|name| name == from) {
+            project.name = Some(to.to_string());
+        }
+        projects.insert(to, project);
+        true
+    }
+
+    /// Copies the project `from`, extensions included, to the key `to`. A
+    /// `name` equal to the old key is renamed in the copy, like with
+    /// `rename_project`. Returns `false`, changing nothing, if there is no
+    /// project `from` or there already is a project `to`.
+    pub fn duplicate_project(&mut self, from: &str, to: ProjectName) -> bool {
+        let projects = match self.projects {
+            Some(ref mut projects) if !projects.contains_key(&to) => projects,
+            _ => return false,
+        };
+        let mut project = match projects.get(from) {
+            Some(project) => project.clone(),
+            None => return false,
+        };
+
+        if project.name.as_ref().is_some_and(|name| name == from) {
+            project.name = Some(to.to_string());
+        }
+        projects.insert(to, project);
+        true
+    }
+}
+
+fn add_pattern(patterns: &mut Option<Vec<GlobPattern>>, pattern: GlobPattern) -> bool {
+    let patterns = patterns.get_or_insert_with(Vec::new);
+    if patterns.contains(&pattern) {
+        return false;
+    }
+    patterns.push(pattern);
+    true
+}
+
+fn remove_pattern(patterns: &mut Option<Vec<GlobPattern>>, pattern: &str) -> bool {
+    let list = match *patterns {
+        Some(ref mut list) => list,
+        None => return false,
+    };
+
+    let len = list.len();
+    list.retain(|kept| kept.as_str() != pattern);
+    let removed = list.len() != len;
+    if list.is_empty() {
+        *patterns = None;
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_pattern_lists_tidy() {
+        let mut project = GraphQLProjectConfiguration::new();
+        let pattern: GlobPattern = "./src/*.graphql".parse().unwrap();
+
+        assert!(project.add_include(pattern.clone()));
+        assert!(!project.add_include(pattern.clone()));
+        assert_eq!(project.includes, Some(vec![pattern]));
+
+        assert!(!project.remove_exclude("./src/*.graphql"));
+        assert!(project.remove_include("./src/*.graphql"));
+        assert_eq!(project.includes, None);
+    }
+
+    fn name(name: &str) -> ProjectName {
+        ProjectName::new(name).unwrap()
+    }
+
+    #[test]
+    fn it_keeps_projects_tidy() {
+        let mut config = GraphQLConfiguration::new();
+        let mut app = GraphQLProjectConfiguration::new();
+        app.name = Some("App".to_owned());
+        config.insert_project(name("app"), app);
+        config.insert_project(name("lib"), GraphQLProjectConfiguration::new());
+
+        assert!(!config.rename_project("app", name("lib")));
+        assert!(!config.rename_project("web", name("site")));
+        assert!(config.rename_project("app", name("web")));
+        assert_eq!(config.project("web").unwrap().name, Some("App".to_owned()));
+
+        assert!(config.duplicate_project("web", name("staging")));
+        assert!(!config.duplicate_project("web", name("lib")));
+        assert_eq!(config.project("staging"), config.project("web"));
+
+        assert!(config.remove_project("staging").is_some());
+        assert!(config.remove_project("web").is_some());
+        assert!(config.remove_project("lib").is_some());
+        assert_eq!(config.projects, None);
+        assert_eq!(config.remove_project("lib"), None);
+    }
+
+    #[test]
+    fn it_duplicates_projects_under_a_new_key() {
+        let mut config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "dev": { "name": "dev", "extensions": { "endpoints": { "default": "http://dev" } } }
+            }
+        }))
+        .unwrap();
+
+        assert!(config.duplicate_project("dev", name("prod")));
+
+        let prod = config.project("prod").unwrap();
+        assert_eq!(prod.name, Some("prod".to_owned()));
+        assert_eq!(prod.extensions, config.project("dev").unwrap().extensions);
+        assert_eq!(config.project("dev").unwrap().name, Some("dev".to_owned()));
+    }
+}
@@ -0,0 +1,374 @@
+//! Normalization of configurations, usable as the backend of an auto-fixer.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::glob::GlobPattern;
+use crate::map;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// The changes made by `GraphQLConfiguration::normalize`.
+#[derive(Serialize, Clone, PartialEq, Debug, Default)]
+pub struct NormalizationReport {
+    /// The changes, in the order they were made.
+    pub changes: Vec<Normalization>,
+}
+
+/// Which pattern list a `Normalization` applies to.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub enum PatternList {
+    /// The `includes` field.
+    Includes,
+    /// The `excludes` field.
+    Excludes,
+}
+
+/// A single change made while normalizing.
+///
+/// `project` is `None` for changes to the top-level configuration.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+#[serde(tag = "change", rename_all = "camelCase")]
+pub enum Normalization {
+    /// Whitespace was trimmed from `name`.
+    TrimmedName {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<String>,
+        /// The name before trimming.
+        name: String,
+    },
+    /// Whitespace was trimmed from `schemaPath`.
+    TrimmedSchemaPath {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<String>,
+        /// The path before trimming.
+        #[serde(rename = "schemaPath")]
+        schema_path: PathBuf,
+    },
+    /// Whitespace was trimmed from a pattern.
+    TrimmedPattern {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<String>,
+        /// The list holding the pattern.
+        list: PatternList,
+        /// The pattern before trimming.
+        pattern: String,
+    },
+    /// An empty or repeated pattern was removed.
+    RemovedPattern {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<String>,
+        /// The list which held the pattern.
+        list: PatternList,
+        /// The removed pattern.
+        pattern: String,
+    },
+    /// A pattern list without any patterns was removed.
+    RemovedEmptyList {
+        /// The affected project, `None` for the top-level configuration.
+        project: Option<String>,
+        /// The removed list.
+        list: PatternList,
+    },
+    /// A project without a `name` was given its key as name, which is what
+    /// the specification says it defaults to.
+    FilledProjectName {
+        /// The affected project.
+        project: String,
+    },
+}
+
+impl GraphQLConfiguration {
+    /// Normalizes the configuration in place:
+    ///
+    /// - whitespace around names, schema paths and patterns is trimmed,
+    /// - empty and repeated patterns are removed,
+    /// - pattern lists left without patterns are removed,
+    /// - projects without a `name` are named after their key.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let mut config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "includes": [" ./src/*.graphql", "./src/*.graphql"],
+    ///     "excludes": []
+    /// }))?;
+    ///
+    /// let report = config.normalize();
+    ///
+    /// assert_eq!(report.changes.len(), 3);
+    /// assert_eq!(config.root.includes, Some(vec!["./src/*.graphql".parse().unwrap()]));
+    /// assert_eq!(config.root.excludes, None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn normalize(&mut self) -> NormalizationReport {
+        let mut changes = Vec::new();
+        normalize_project(None, &mut self.root, &mut changes);
+
+        if let Some(ref mut projects) = self.projects {
+            for (key, project) in map::ordered_mut(projects) {
+                normalize_project(Some(key.as_str()), project, &mut changes);
+                if project.name.is_none() {
+                    project.name = Some(key.to_string());
+                    changes.push(Normalization::FilledProjectName {
+                        project: key.to_string(),
+                    });
+                }
+            }
+        }
+
+        NormalizationReport { changes }
+    }
+}
+
+impl NormalizationReport {
+    /// Whether the configuration was already normalized.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for NormalizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PatternList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            PatternList::Includes => "includes",
+            PatternList::Excludes => "excludes",
+        })
+    }
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn scope(project: &Option<String>) -> String {
+            match *project {
+                Some(ref project) => format!("project `{}`", project),
+                None => "root".to_owned(),
+            }
+        }
+
+        match *self {
+            Normalization::TrimmedName {
+                ref project,
+                ref name,
+            } => write!(f, "{}: trimmed name {:?}", scope(project), name),
+            Normalization::TrimmedSchemaPath {
+                ref project,
+                ref schema_path,
+            } => write!(
+                f,
+                "{}: trimmed schemaPath {:?}",
+                scope(project),
+                schema_path
+            ),
+            Normalization::TrimmedPattern {
+                ref project,
+                list,
+                ref pattern,
+            } => write!(
+                f,
+                "{}: trimmed {} pattern {:?}",
+                scope(project),
+                list,
+                pattern
+            ),
+            Normalization::RemovedPattern {
+                ref project,
+                list,
+                ref pattern,
+            } => write!(
+                f,
+                "{}: removed {} pattern {:?}",
+                scope(project),
+                list,
+                pattern
+            ),
+            Normalization::RemovedEmptyList { ref project, list } => {
+                write!(f, "{}: removed empty {}", scope(project), list)
+            }
+            Normalization::FilledProjectName { ref project } => {
+                write!(f, "project `{}`: set name to its key", project)
+            }
+        }
+    }
+}
+
+fn normalize_project(
+    project: Option<&str>,
+    config: &mut GraphQLProjectConfiguration,
+    changes: &mut Vec<Normalization>,
+) {
+    let key = || project.map(str::to_owned);
+
+    if let Some(ref mut name) = config.name {
+        if name.trim() != name {
+            changes.push(Normalization::TrimmedName {
+                project: key(),
+                name: name.clone(),
+            });
+            *name = name.trim().to_owned();
+        }
+    }
+
+    if let Some(ref mut schema_path) = config.schema_path {
+        let trimmed = schema_path
+            .to_str()
+            .map(str::trim)
+            .filter(|trimmed| trimmed.len() != schema_path.as_os_str().len())
+            .map(PathBuf::from);
+        if let Some(trimmed) = trimmed {
+            changes.push(Normalization::TrimmedSchemaPath {
+                project: key(),
+                schema_path: ::std::mem::replace(schema_path, trimmed),
+            });
+        }
+    }
+
+    for &mut (list, ref mut patterns) in &mut [
+        (PatternList::Includes, &mut config.includes),
+        (PatternList::Excludes, &mut config.excludes),
+    ] {
+        if let Some(ref mut patterns) = **patterns {
+            normalize_patterns(project, list, patterns, changes);
+        }
+        if patterns.as_ref().is_some_and(Vec::is_empty) {
+            **patterns = None;
+            changes.push(Normalization::RemovedEmptyList {
+                project: key(),
+                list,
+            });
+        }
+    }
+}
+
+fn normalize_patterns(
+    project: Option<&str>,
+    list: PatternList,
+    patterns: &mut Vec<GlobPattern>,
+    changes: &mut Vec<Normalization>,
+) {
+    let mut normalized: Vec<GlobPattern> = Vec::with_capacity(patterns.len());
+
+    for pattern in patterns.drain(..) {
+        let trimmed = pattern.trim();
+        if trimmed.is_empty() || normalized.iter().any(|kept| kept == trimmed) {
+            changes.push(Normalization::RemovedPattern {
+                project: project.map(str::to_owned),
+                list,
+                pattern: pattern.into_string(),
+            });
+            continue;
+        }
+
+        // Trimming an escaped trailing space leaves a malformed pattern,
+        // which is kept as it is.
+        match GlobPattern::new(trimmed) {
+            Ok(trimmed) if trimmed != pattern => {
+                normalized.push(trimmed);
+                changes.push(Normalization::TrimmedPattern {
+                    project: project.map(str::to_owned),
+                    list,
+                    pattern: pattern.into_string(),
+                });
+            }
+            _ => normalized.push(pattern),
+        }
+    }
+
+    *patterns = normalized;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_normalizes_projects() {
+        let mut config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": {
+                    "name": " App ",
+                    "schemaPath": "./app.graphql ",
+                    "includes": ["", "./app/*.graphql"],
+                    "excludes": ["  "]
+                },
+                "lib": {}
+            }
+        }))
+        .unwrap();
+
+        let report = config.normalize();
+
+        let app = Some("app".to_owned());
+        assert_eq!(
+            report.changes,
+            vec![
+                Normalization::TrimmedName {
+                    project: app.clone(),
+                    name: " App ".to_owned(),
+                },
+                Normalization::TrimmedSchemaPath {
+                    project: app.clone(),
+                    schema_path: "./app.graphql ".into(),
+                },
+                Normalization::RemovedPattern {
+                    project: app.clone(),
+                    list: PatternList::Includes,
+                    pattern: "".to_owned(),
+                },
+                Normalization::RemovedPattern {
+                    project: app.clone(),
+                    list: PatternList::Excludes,
+                    pattern: "  ".to_owned(),
+                },
+                Normalization::RemovedEmptyList {
+                    project: app,
+                    list: PatternList::Excludes,
+                },
+                Normalization::FilledProjectName {
+                    project: "lib".to_owned(),
+                },
+            ]
+        );
+
+        let projects = config.projects.as_ref().unwrap();
+        assert_eq!(projects["app"].name, Some("App".to_owned()));
+        assert_eq!(projects["app"].schema_path, Some("./app.graphql".into()));
+        assert_eq!(
+            projects["app"].includes,
+            Some(vec!["./app/*.graphql".parse().unwrap()])
+        );
+        assert_eq!(projects["lib"].name, Some("lib".to_owned()));
+    }
+
+    #[test]
+    fn it_is_idempotent() {
+        let mut config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "includes": [" ./a/*.graphql", "./a/*.graphql ", "./b/*.graphql"],
+            "projects": { "app": { "excludes": [] } }
+        }))
+        .unwrap();
+
+        assert!(!config.normalize().is_empty());
+        assert!(config.normalize().is_empty());
+        assert_eq!(
+            config.root.includes,
+            Some(vec![
+                "./a/*.graphql".parse().unwrap(),
+                "./b/*.graphql".parse().unwrap()
+            ])
+        );
+    }
+}
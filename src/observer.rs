@@ -0,0 +1,223 @@
+//! Hooks reporting what the crate spends its time on.
+//!
+//! An `Observer` is called when a configuration is loaded, when a cache is
+//! looked up and when a schema is fetched, so that services can record
+//! durations and hit rates in whatever metrics library they use:
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # use graphql_config::observer::{CacheKind, Observer, ObservedCache};
+//! # use graphql_config::schema_cache::{MemoryCache, SchemaCache};
+//! # use std::sync::atomic::{AtomicUsize, Ordering};
+//! # use std::sync::Arc;
+//! #[derive(Default)]
+//! struct Misses(AtomicUsize);
+//!
+//! impl Observer for Misses {
+//!     fn cache_lookup(&self, _kind: CacheKind, _key: &str, hit: bool) {
+//!         if !hit {
+//!             self.0.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let misses = Arc::new(Misses::default());
+//! let cache = ObservedCache::new(MemoryCache::new(), misses.clone());
+//! cache.get("url:https://example.com/graphql")?;
+//! assert_eq!(misses.0.load(Ordering::Relaxed), 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Every method does nothing unless overridden.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+#[cfg(feature = "fs")]
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "fs")]
+use crate::load::LoadError;
+use crate::schema_cache::SchemaCache;
+#[cfg(feature = "fs")]
+use crate::GraphQLConfiguration;
+
+/// The caches an `Observer` is told about.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CacheKind {
+    /// A `SchemaCache` wrapped in an `ObservedCache`, by cache key.
+    SchemaCache,
+    /// The parsed schemas of a `GraphQLCache`, by project.
+    Schema,
+    /// The parsed documents of a `GraphQLCache`, by path.
+    Document,
+}
+
+/// Callbacks for the work of the crate.
+pub trait Observer: Send + Sync {
+    /// The configuration file at `path` was loaded with `load`, or failed to
+    /// be, in `duration`.
+    #[cfg(feature = "fs")]
+    fn config_loaded(&self, _path: &Path, _duration: Duration, _result: Result<(), &LoadError>) {}
+
+    /// `key` was looked up in a cache, and found if `hit`.
+    fn cache_lookup(&self, _kind: CacheKind, _key: &str, _hit: bool) {}
+
+    /// The schema of `url` was fetched with `fetch`, or failed to be, in
+    /// `duration`.
+    fn schema_fetched(&self, _url: &str, _duration: Duration, _result: Result<(), &dyn Error>) {}
+}
+
+/// Loads the configuration file at `path`, like
+/// `GraphQLConfiguration::try_from`, and reports it to `observer`.
+#[cfg(feature = "fs")]
+pub fn load(path: &Path, observer: &dyn Observer) -> Result<GraphQLConfiguration, LoadError> {
+    use std::convert::TryFrom;
+
+    let start = Instant::now();
+    let config = GraphQLConfiguration::try_from(path);
+    observer.config_loaded(path, start.elapsed(), config.as_ref().map(|_| ()));
+    config
+}
+
+/// Fetches the schema of `url` with `fetch`, which the crate leaves to the
+/// HTTP client of the embedder, and reports it to `observer`.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::observer::{self, Observer};
+/// # use std::error::Error;
+/// # use std::time::Duration;
+/// struct Log;
+///
+/// impl Observer for Log {
+///     fn schema_fetched(&self, url: &str, duration: Duration, result: Result<(), &dyn Error>) {
+///         println!("{} in {:?}: {}", url, duration, if result.is_ok() { "ok" } else { "failed" });
+///     }
+/// }
+///
+/// let url = "http://localhost:4000/graphql";
+/// let schema = observer::fetch(url, &Log, || Ok::<_, std::io::Error>("type Query { a: Int }"));
+/// assert!(schema.is_ok());
+/// ```
+pub fn fetch<T, E, F>(url: &str, observer: &dyn Observer, fetch: F) -> Result<T, E>
+where
+    E: Error + 'static,
+    F: FnOnce() -> Result<T, E>,
+{
+    let start = Instant::now();
+    let schema = fetch();
+    observer.schema_fetched(
+        url,
+        start.elapsed(),
+        schema
+            .as_ref()
+            .map(|_| ())
+            .map_err(|error| error as &dyn Error),
+    );
+    schema
+}
+
+/// A `SchemaCache` reporting its lookups to an `Observer`.
+#[derive(Clone)]
+pub struct ObservedCache<C> {
+    cache: C,
+    observer: Arc<dyn Observer>,
+}
+
+impl<C: SchemaCache> ObservedCache<C> {
+    /// Wraps `cache`.
+    pub fn new(cache: C, observer: Arc<dyn Observer>) -> ObservedCache<C> {
+        ObservedCache { cache, observer }
+    }
+
+    /// The wrapped cache.
+    pub fn inner(&self) -> &C {
+        &self.cache
+    }
+}
+
+impl<C: SchemaCache> SchemaCache for ObservedCache<C> {
+    fn get(&self, key: &str) -> io::Result<Option<String>> {
+        let schema = self.cache.get(key)?;
+        self.observer
+            .cache_lookup(CacheKind::SchemaCache, key, schema.is_some());
+        Ok(schema)
+    }
+
+    fn put(&self, key: &str, schema: &str) -> io::Result<()> {
+        self.cache.put(key, schema)
+    }
+}
+
+impl<C: fmt::Debug> fmt::Debug for ObservedCache<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ObservedCache")
+            .field("cache", &self.cache)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Observer for Recorder {
+        #[cfg(feature = "fs")]
+        fn config_loaded(&self, path: &Path, _: Duration, result: Result<(), &LoadError>) {
+            self.record(format!("load {} {}", path.display(), result.is_ok()));
+        }
+
+        fn cache_lookup(&self, kind: CacheKind, key: &str, hit: bool) {
+            self.record(format!("{:?} {} {}", kind, key, hit));
+        }
+
+        fn schema_fetched(&self, url: &str, _: Duration, result: Result<(), &dyn Error>) {
+            self.record(format!("fetch {} {}", url, result.is_ok()));
+        }
+    }
+
+    impl Recorder {
+        fn record(&self, event: String) {
+            self.0
+                .lock()
+                .unwrap_or_else(|error| error.into_inner())
+                .push(event);
+        }
+    }
+
+    #[test]
+    fn it_reports_lookups_and_fetches() {
+        let recorder = Arc::new(Recorder::default());
+        let cache = ObservedCache::new(
+            crate::schema_cache::MemoryCache::new(),
+            Arc::clone(&recorder) as Arc<dyn Observer>,
+        );
+        cache.get("a").unwrap();
+        cache.put("a", "type Query { a: Int }").unwrap();
+        cache.get("a").unwrap();
+        let failed: Result<(), io::Error> =
+            fetch("http://b", &*recorder, || Err(io::Error::other("refused")));
+        assert!(failed.is_err());
+        #[cfg(feature = "fs")]
+        assert!(load(Path::new("/nonexistent/.graphqlconfig"), &*recorder).is_err());
+
+        let mut expected = vec![
+            "SchemaCache a false".to_owned(),
+            "SchemaCache a true".to_owned(),
+            "fetch http://b false".to_owned(),
+        ];
+        if cfg!(feature = "fs") {
+            expected.push("load /nonexistent/.graphqlconfig false".to_owned());
+        }
+        assert_eq!(*recorder.0.lock().unwrap(), expected);
+    }
+}
@@ -0,0 +1,227 @@
+//! Which project every file of a repository belongs to, saved between runs.
+//!
+//! Matching every file of a large repository against every project takes a
+//! while, and command line tools would do it on every invocation. An
+//! `Ownership` map can be saved to disk with a freshness token, a hash of the
+//! configuration and of the modification times of the directories of the
+//! repository. As long as the token does not change, the saved map is
+//! loaded instead of scanning again. Files are only added or removed by
+//! changing a directory, which is what makes the directory times enough.
+//!
+//! The token is only meant to be compared with tokens computed by the same
+//! build of the crate.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::glob;
+use crate::index::FileIndex;
+use crate::resolved::ResolvedGraphQLConfig;
+
+/// The project of every file under the directory of a configuration.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::ownership::Ownership;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let path = Path::new("/repo/.graphqlconfig");
+/// let config = ResolvedGraphQLConfig::from_path(path)?;
+///
+/// let ownership = Ownership::load_or_scan("/repo/.cache/graphql-ownership.json", &config)?;
+/// println!("{:?}", ownership.project_for_file("src/query.graphql"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Ownership {
+    token: u64,
+    files: BTreeMap<String, String>,
+}
+
+impl Ownership {
+    /// Matches every file under the directory of `config`, skipping hidden
+    /// files and directories and symbolic links like
+    /// `ProjectHandle::document_files`, against the projects.
+    pub fn scan(config: &ResolvedGraphQLConfig) -> io::Result<Ownership> {
+        let mut tree = Tree::default();
+        tree.walk(config.dir())?;
+
+        let index = FileIndex::new(config);
+        let files = tree
+            .files
+            .iter()
+            .filter_map(|file| {
+                let project = index.project_for_file(file)?;
+                let relative = glob::to_slash(file.strip_prefix(config.dir()).ok()?)?;
+                Some((relative, project.to_owned()))
+            })
+            .collect();
+        Ok(Ownership {
+            token: tree.token(config)?,
+            files,
+        })
+    }
+
+    /// Loads the map saved at `cache` if it is still fresh for `config`, and
+    /// scans and saves it there otherwise.
+    pub fn load_or_scan(
+        cache: impl AsRef<Path>,
+        config: &ResolvedGraphQLConfig,
+    ) -> io::Result<Ownership> {
+        let cache = cache.as_ref();
+        // Creating the directory of the cache within the repository changes
+        // the time of its parent, so it has to happen before the token is
+        // computed.
+        if let Some(dir) = cache.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if let Ok(saved) = Ownership::load(cache) {
+            if saved.token == freshness_token(config)? {
+                return Ok(saved);
+            }
+        }
+
+        let ownership = Ownership::scan(config)?;
+        ownership.save(cache)?;
+        Ok(ownership)
+    }
+
+    /// Reads a map saved with `save`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Ownership> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(io::Error::from)
+    }
+
+    /// Writes the map to `path` as JSON, creating its directory.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+    }
+
+    /// The freshness token the map was scanned with.
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Whether the map is still the one `scan` would return for `config`.
+    pub fn is_fresh(&self, config: &ResolvedGraphQLConfig) -> io::Result<bool> {
+        Ok(self.token == freshness_token(config)?)
+    }
+
+    /// The projects by `/`-separated file path, relative to the directory of
+    /// the configuration.
+    pub fn files(&self) -> &BTreeMap<String, String> {
+        &self.files
+    }
+
+    /// The project of the `/`-separated file path `path`, relative to the
+    /// directory of the configuration.
+    pub fn project_for_file(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+}
+
+/// The freshness token of `config` and the directories under its own.
+pub fn freshness_token(config: &ResolvedGraphQLConfig) -> io::Result<u64> {
+    let mut tree = Tree::default();
+    tree.walk(config.dir())?;
+    tree.token(config)
+}
+
+#[derive(Default)]
+struct Tree {
+    dirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+}
+
+impl Tree {
+    fn walk(&mut self, dir: &Path) -> io::Result<()> {
+        self.dirs.push(dir.to_owned());
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                self.walk(&entry.path())?;
+            } else if file_type.is_file() {
+                self.files.push(entry.path());
+            }
+        }
+        Ok(())
+    }
+
+    fn token(&mut self, config: &ResolvedGraphQLConfig) -> io::Result<u64> {
+        let mut hasher = DefaultHasher::new();
+        config.raw().hash(&mut hasher);
+        self.dirs.sort();
+        for dir in &self.dirs {
+            let modified = fs::metadata(dir)?.modified()?;
+            dir.hash(&mut hasher);
+            modified
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .hash(&mut hasher);
+        }
+        Ok(hasher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+    use crate::GraphQLConfiguration;
+
+    #[test]
+    fn it_reuses_fresh_maps() {
+        let dir = TestDir::new("it_reuses_fresh_maps");
+        fs::create_dir_all(dir.join("web")).unwrap();
+        fs::create_dir_all(dir.join("admin")).unwrap();
+        fs::write(dir.join("web/query.graphql"), "").unwrap();
+        fs::write(dir.join("admin/query.graphql"), "").unwrap();
+        fs::write(dir.join("README.md"), "").unwrap();
+
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "web": { "includes": ["web/**"] },
+                "admin": { "includes": ["admin/**"] }
+            }
+        }))
+        .unwrap();
+        let config = ResolvedGraphQLConfig::new(config, dir.join(".graphqlconfig"));
+        let cache = dir.join(".cache/ownership.json");
+
+        let scanned = Ownership::load_or_scan(&cache, &config).unwrap();
+        assert_eq!(
+            scanned.files(),
+            &btreemap! {
+                "admin/query.graphql".to_owned() => "admin".to_owned(),
+                "web/query.graphql".to_owned() => "web".to_owned(),
+            }
+        );
+        assert_eq!(Ownership::load(&cache).unwrap(), scanned);
+        assert!(scanned.is_fresh(&config).unwrap());
+
+        let mut changed = config.raw().clone();
+        changed.remove_project("admin");
+        let changed = ResolvedGraphQLConfig::new(changed, dir.join(".graphqlconfig"));
+        assert!(!scanned.is_fresh(&changed).unwrap());
+        let rescanned = Ownership::load_or_scan(&cache, &changed).unwrap();
+        assert_eq!(rescanned.project_for_file("admin/query.graphql"), None);
+        assert_eq!(rescanned.project_for_file("web/query.graphql"), Some("web"));
+    }
+}
@@ -0,0 +1,532 @@
+//! Persisted operation manifests, enabled by the `persisted-queries` feature.
+//!
+//! A manifest maps every named operation of a project's documents to a
+//! normalized body and its SHA-256 hash. The body of an operation is the
+//! operation printed by `graphql-parser` followed by every fragment it uses,
+//! directly or through other fragments, ordered by name.
+//!
+//! Operations and the fragments they use are checked against the schema:
+//! their root types, the type conditions of fragments and every field they
+//! select must exist. Nothing else, such as arguments or variables, is
+//! validated, and no fields (such as `__typename`) are added to them.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::{self, Write};
+
+use graphql_parser::query::{
+    self, Definition, FragmentDefinition, OperationDefinition, Selection, SelectionSet,
+    TypeCondition,
+};
+use graphql_parser::schema::{self, Type, TypeDefinition, TypeExtension};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// The kind of a persisted operation.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationType {
+    /// A `query` operation.
+    Query,
+    /// A `mutation` operation.
+    Mutation,
+    /// A `subscription` operation.
+    Subscription,
+}
+
+/// An operation of a `PersistedQueryManifest`.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct PersistedOperation {
+    /// The hex-encoded SHA-256 hash of `body`.
+    pub id: String,
+    /// The name of the operation.
+    pub name: String,
+    /// Whether the operation is a query, mutation or subscription.
+    #[serde(rename = "type")]
+    pub operation_type: OperationType,
+    /// The normalized text of the operation and the fragments it uses.
+    pub body: String,
+}
+
+/// The persisted operations of a set of documents.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # extern crate graphql_parser;
+/// # use graphql_config::persisted_queries::PersistedQueryManifest;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let schema = graphql_parser::parse_schema::<&str>(
+///     "type Query { viewer: User } type User { id: ID! name: String }",
+/// )?;
+/// let manifest = PersistedQueryManifest::from_documents(
+///     &schema,
+///     vec![
+///         "query Viewer { viewer { ...User } }",
+///         "fragment User on User { id name }",
+///     ],
+/// )?;
+///
+/// assert_eq!(manifest.operations[0].name, "Viewer");
+/// assert_eq!(
+///     manifest.operations[0].body,
+///     "query Viewer {\n  viewer {\n    ...User\n  }\n}\n\nfragment User on User {\n  id\n  name\n}\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PersistedQueryManifest {
+    /// The operations, ordered by name.
+    pub operations: Vec<PersistedOperation>,
+}
+
+/// The error returned when a manifest cannot be built from a set of
+/// documents.
+#[derive(Debug)]
+pub enum PersistedQueryError {
+    /// A document could not be parsed.
+    Parse(query::ParseError),
+    /// An operation has no name, so it cannot be referred to.
+    AnonymousOperation,
+    /// Two operations or two fragments share a name.
+    DuplicateDefinition(String),
+    /// An operation uses a fragment which is not defined in any document.
+    UnknownFragment(String),
+    /// The schema has no root type for operations of this kind.
+    MissingRootType(OperationType),
+    /// A fragment is on a type which is not an object, interface or union
+    /// type of the schema.
+    UnknownType(String),
+    /// A field is selected which its type, the first value, does not have.
+    UnknownField(String, String),
+}
+
+impl fmt::Display for PersistedQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PersistedQueryError::Parse(ref error) => write!(f, "invalid document: {}", error),
+            PersistedQueryError::AnonymousOperation => {
+                write!(f, "anonymous operations cannot be persisted")
+            }
+            PersistedQueryError::DuplicateDefinition(ref name) => {
+                write!(f, "`{}` is defined more than once", name)
+            }
+            PersistedQueryError::UnknownFragment(ref name) => {
+                write!(f, "unknown fragment `{}`", name)
+            }
+            PersistedQueryError::MissingRootType(operation_type) => {
+                write!(f, "the schema has no {} type", operation_type.keyword())
+            }
+            PersistedQueryError::UnknownType(ref name) => write!(f, "unknown type `{}`", name),
+            PersistedQueryError::UnknownField(ref type_name, ref field) => {
+                write!(f, "type `{}` has no field `{}`", type_name, field)
+            }
+        }
+    }
+}
+
+impl Error for PersistedQueryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            PersistedQueryError::Parse(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<query::ParseError> for PersistedQueryError {
+    fn from(error: query::ParseError) -> PersistedQueryError {
+        PersistedQueryError::Parse(error)
+    }
+}
+
+type Document<'a> = query::Document<'a, &'a str>;
+
+impl OperationType {
+    fn keyword(self) -> &'static str {
+        match self {
+            OperationType::Query => "query",
+            OperationType::Mutation => "mutation",
+            OperationType::Subscription => "subscription",
+        }
+    }
+}
+
+/// The types of a schema which fields can be selected on, with the named
+/// types of their fields, and its root operation types.
+struct Schema<'s> {
+    types: BTreeMap<&'s str, BTreeMap<&'s str, &'s str>>,
+    query: Option<&'s str>,
+    mutation: Option<&'s str>,
+    subscription: Option<&'s str>,
+}
+
+impl<'s> Schema<'s> {
+    fn new<T: schema::Text<'s>>(document: &'s schema::Document<'s, T>) -> Schema<'s> {
+        fn named<'s, T: schema::Text<'s>>(field_type: &'s Type<'s, T>) -> &'s str {
+            match *field_type {
+                Type::NamedType(ref name) => name.as_ref(),
+                Type::ListType(ref inner) | Type::NonNullType(ref inner) => named(inner),
+            }
+        }
+
+        let mut types: BTreeMap<&'s str, BTreeMap<&'s str, &'s str>> = BTreeMap::new();
+        let mut roots = None;
+        for definition in &document.definitions {
+            let (name, fields) = match *definition {
+                schema::Definition::SchemaDefinition(ref schema) => {
+                    roots = Some(schema);
+                    continue;
+                }
+                schema::Definition::TypeDefinition(TypeDefinition::Object(ref object)) => {
+                    (&object.name, &object.fields[..])
+                }
+                schema::Definition::TypeDefinition(TypeDefinition::Interface(ref interface)) => {
+                    (&interface.name, &interface.fields[..])
+                }
+                schema::Definition::TypeDefinition(TypeDefinition::Union(ref union)) => {
+                    (&union.name, &[][..])
+                }
+                schema::Definition::TypeExtension(TypeExtension::Object(ref object)) => {
+                    (&object.name, &object.fields[..])
+                }
+                schema::Definition::TypeExtension(TypeExtension::Interface(ref interface)) => {
+                    (&interface.name, &interface.fields[..])
+                }
+                _ => continue,
+            };
+            types.entry(name.as_ref()).or_default().extend(
+                fields
+                    .iter()
+                    .map(|field| (field.name.as_ref(), named(&field.field_type))),
+            );
+        }
+
+        let default = |name: &'static str| types.get_key_value(name).map(|(&name, _)| name);
+        let (query, mutation, subscription) = match roots {
+            Some(roots) => (
+                roots.query.as_ref().map(AsRef::as_ref),
+                roots.mutation.as_ref().map(AsRef::as_ref),
+                roots.subscription.as_ref().map(AsRef::as_ref),
+            ),
+            None => (
+                default("Query"),
+                default("Mutation"),
+                default("Subscription"),
+            ),
+        };
+        Schema {
+            types,
+            query,
+            mutation,
+            subscription,
+        }
+    }
+
+    fn root(&self, operation_type: OperationType) -> Result<&'s str, PersistedQueryError> {
+        let root = match operation_type {
+            OperationType::Query => self.query,
+            OperationType::Mutation => self.mutation,
+            OperationType::Subscription => self.subscription,
+        };
+        root.ok_or(PersistedQueryError::MissingRootType(operation_type))
+    }
+
+    /// Checks that the fields of `selection_set` exist on `type_name` and the
+    /// types of fragments on it in the schema. The selections of fragment
+    /// spreads are checked with the fragment.
+    fn check<'a>(
+        &self,
+        type_name: &str,
+        selection_set: &SelectionSet<'a, &'a str>,
+    ) -> Result<(), PersistedQueryError> {
+        let fields = self
+            .types
+            .get(type_name)
+            .ok_or_else(|| PersistedQueryError::UnknownType(type_name.to_owned()))?;
+        for selection in &selection_set.items {
+            match *selection {
+                // `__typename`, `__schema` and `__type` are not declared.
+                Selection::Field(ref field) if field.name.starts_with("__") => {}
+                Selection::Field(ref field) => {
+                    let field_type = fields.get(field.name).ok_or_else(|| {
+                        PersistedQueryError::UnknownField(
+                            type_name.to_owned(),
+                            field.name.to_owned(),
+                        )
+                    })?;
+                    if !field.selection_set.items.is_empty() && self.types.contains_key(field_type)
+                    {
+                        self.check(field_type, &field.selection_set)?;
+                    }
+                }
+                Selection::InlineFragment(ref inline) => {
+                    let type_name = match inline.type_condition {
+                        Some(TypeCondition::On(type_name)) => type_name,
+                        None => type_name,
+                    };
+                    self.check(type_name, &inline.selection_set)?;
+                }
+                Selection::FragmentSpread(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PersistedQueryManifest {
+    /// Builds the manifest of the operations defined in `documents`, the
+    /// sources of a project's GraphQL documents, checking them against
+    /// `schema`, such as the one `GraphQLProjectConfiguration::load_schema`
+    /// reads. Fragments may be defined in a different document than the
+    /// operations using them.
+    pub fn from_documents<'a, 's, T, I>(
+        schema: &'s schema::Document<'s, T>,
+        documents: I,
+    ) -> Result<PersistedQueryManifest, PersistedQueryError>
+    where
+        T: schema::Text<'s>,
+        I: IntoIterator<Item = &'a str>,
+    {
+        let schema = Schema::new(schema);
+        let documents = documents
+            .into_iter()
+            .map(query::parse_query)
+            .collect::<Result<Vec<Document<'a>>, _>>()?;
+
+        let mut operations = BTreeMap::new();
+        let mut fragments = BTreeMap::new();
+        for definition in documents.iter().flat_map(|document| &document.definitions) {
+            let duplicate = match *definition {
+                Definition::Operation(ref operation) => {
+                    let (name, _, _) = describe(operation);
+                    let name = name.ok_or(PersistedQueryError::AnonymousOperation)?;
+                    operations.insert(name, operation).is_some().then_some(name)
+                }
+                Definition::Fragment(ref fragment) => fragments
+                    .insert(fragment.name, fragment)
+                    .is_some()
+                    .then_some(fragment.name),
+            };
+            if let Some(name) = duplicate {
+                return Err(PersistedQueryError::DuplicateDefinition(name.to_owned()));
+            }
+        }
+
+        let operations = operations
+            .into_iter()
+            .map(|(name, operation)| {
+                let (_, operation_type, selection_set) = describe(operation);
+
+                let mut used = BTreeSet::new();
+                collect_fragments(selection_set, &fragments, &mut used)?;
+                schema.check(schema.root(operation_type)?, selection_set)?;
+                for &name in &used {
+                    let fragment = fragments[name];
+                    let TypeCondition::On(type_name) = fragment.type_condition;
+                    schema.check(type_name, &fragment.selection_set)?;
+                }
+
+                let mut body = operation.to_string();
+                for name in used {
+                    body.push('\n');
+                    body.push_str(&fragments[name].to_string());
+                }
+
+                Ok(PersistedOperation {
+                    id: sha256(&body),
+                    name: name.to_owned(),
+                    operation_type,
+                    body,
+                })
+            })
+            .collect::<Result<_, PersistedQueryError>>()?;
+
+        Ok(PersistedQueryManifest { operations })
+    }
+
+    /// The manifest in the format of Apollo's
+    /// `generate-persisted-query-manifest`.
+    pub fn to_apollo_json(&self) -> Value {
+        serde_json::json!({
+            "format": "apollo-persisted-query-manifest",
+            "version": 1,
+            "operations": self.operations,
+        })
+    }
+
+    /// The manifest in the format of Relay's persisted queries, an object
+    /// mapping ids to bodies.
+    pub fn to_relay_json(&self) -> Value {
+        Value::Object(
+            self.operations
+                .iter()
+                .map(|operation| (operation.id.clone(), Value::from(operation.body.clone())))
+                .collect(),
+        )
+    }
+}
+
+fn describe<'a, 'b>(
+    operation: &'b OperationDefinition<'a, &'a str>,
+) -> (
+    Option<&'a str>,
+    OperationType,
+    &'b SelectionSet<'a, &'a str>,
+) {
+    match *operation {
+        OperationDefinition::SelectionSet(ref selection_set) => {
+            (None, OperationType::Query, selection_set)
+        }
+        OperationDefinition::Query(ref query) => {
+            (query.name, OperationType::Query, &query.selection_set)
+        }
+        OperationDefinition::Mutation(ref mutation) => (
+            mutation.name,
+            OperationType::Mutation,
+            &mutation.selection_set,
+        ),
+        OperationDefinition::Subscription(ref subscription) => (
+            subscription.name,
+            OperationType::Subscription,
+            &subscription.selection_set,
+        ),
+    }
+}
+
+fn collect_fragments<'a>(
+    selection_set: &SelectionSet<'a, &'a str>,
+    fragments: &BTreeMap<&'a str, &FragmentDefinition<'a, &'a str>>,
+    used: &mut BTreeSet<&'a str>,
+) -> Result<(), PersistedQueryError> {
+    for selection in &selection_set.items {
+        match *selection {
+            Selection::Field(ref field) => {
+                collect_fragments(&field.selection_set, fragments, used)?
+            }
+            Selection::InlineFragment(ref inline) => {
+                collect_fragments(&inline.selection_set, fragments, used)?
+            }
+            Selection::FragmentSpread(ref spread) => {
+                let name = spread.fragment_name;
+                let fragment = fragments
+                    .get(name)
+                    .ok_or_else(|| PersistedQueryError::UnknownFragment(name.to_owned()))?;
+                if used.insert(name) {
+                    collect_fragments(&fragment.selection_set, fragments, used)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn sha256(body: &str) -> String {
+    let mut id = String::with_capacity(64);
+    for byte in Sha256::digest(body.as_bytes()).iter() {
+        write!(id, "{:02x}", byte).unwrap();
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = "
+        type Query { ping: Boolean a: Int b: Int node: Node }
+        type Mutation { rename(name: String!): Node }
+        interface Node { id: ID! }
+        type User implements Node { id: ID! name: String }
+    ";
+
+    fn manifest(documents: Vec<&str>) -> Result<PersistedQueryManifest, PersistedQueryError> {
+        let schema = graphql_parser::parse_schema::<&str>(SCHEMA).unwrap();
+        PersistedQueryManifest::from_documents(&schema, documents)
+    }
+
+    #[test]
+    fn it_includes_fragments_used_transitively() {
+        let manifest = manifest(vec![
+            "mutation Rename($name: String!) { rename(name: $name) { ...Node } }",
+            "fragment Node on Node { id ... on User { ...Name } }\nfragment Name on User { name }",
+            "fragment Unused on User { id }",
+        ])
+        .unwrap();
+
+        let operation = &manifest.operations[0];
+        assert_eq!(operation.operation_type, OperationType::Mutation);
+        assert!(operation.body.contains("fragment Name on User"));
+        assert!(operation.body.contains("fragment Node on Node"));
+        assert!(!operation.body.contains("Unused"));
+        assert_eq!(operation.id, sha256(&operation.body));
+    }
+
+    #[test]
+    fn it_renders_apollo_and_relay_formats() {
+        let manifest = manifest(vec!["query Ping { ping }"]).unwrap();
+        let id = sha256("query Ping {\n  ping\n}\n");
+
+        assert_eq!(
+            manifest.to_apollo_json(),
+            json!({
+                "format": "apollo-persisted-query-manifest",
+                "version": 1,
+                "operations": [
+                    { "id": id, "name": "Ping", "type": "query", "body": "query Ping {\n  ping\n}\n" }
+                ]
+            })
+        );
+        assert_eq!(
+            manifest.to_relay_json(),
+            json!({ id: "query Ping {\n  ping\n}\n" })
+        );
+    }
+
+    #[test]
+    fn it_rejects_anonymous_and_duplicate_operations() {
+        match manifest(vec!["{ ping }"]) {
+            Err(PersistedQueryError::AnonymousOperation) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match manifest(vec!["query A { a }", "query A { b }"]) {
+            Err(PersistedQueryError::DuplicateDefinition(ref name)) if name == "A" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match manifest(vec!["query A { ...Missing }"]) {
+            Err(PersistedQueryError::UnknownFragment(ref name)) if name == "Missing" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_checks_operations_against_the_schema() {
+        match manifest(vec![
+            "query A { node { id __typename ... on User { email } } }",
+        ]) {
+            Err(PersistedQueryError::UnknownField(ref type_name, ref field))
+                if type_name == "User" && field == "email" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match manifest(vec![
+            "query A { node { ...F } }",
+            "fragment F on Admin { id }",
+        ]) {
+            Err(PersistedQueryError::UnknownType(ref name)) if name == "Admin" => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match manifest(vec!["subscription A { ping }"]) {
+            Err(error @ PersistedQueryError::MissingRootType(OperationType::Subscription)) => {
+                assert_eq!(error.to_string(), "the schema has no subscription type");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let schema = graphql_parser::parse_schema::<String>(
+            "schema { query: Root } type Root { version: String }",
+        )
+        .unwrap();
+        let manifest = PersistedQueryManifest::from_documents(&schema, vec!["query V { version }"]);
+        assert_eq!(manifest.unwrap().operations[0].name, "V");
+    }
+}
@@ -0,0 +1,410 @@
+//! Project names, and looking up and listing projects.
+
+use std::borrow::{Borrow, Cow};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::path::Path;
+use std::str::FromStr;
+use std::vec;
+
+use crate::glob::{self, GlobPattern};
+use crate::map;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// The key of a project.
+///
+/// Project names are non-empty and consist of alphanumeric characters and
+/// the punctuation found in package names, `-_.@/:+`. Configurations with
+/// other keys fail to deserialize.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::projects::ProjectName;
+/// # fn main() {
+/// assert!(ProjectName::new("@acme/web-app").is_ok());
+/// assert!(ProjectName::new("my app").is_err());
+/// # }
+/// ```
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[serde(try_from = "String", into = "String")]
+pub struct ProjectName(Cow<'static, str>);
+
+/// The error returned for invalid project names.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProjectNameError {
+    /// The name is empty.
+    Empty,
+    /// The name contains a character which is not allowed, such as
+    /// whitespace.
+    InvalidCharacter(String, char),
+}
+
+impl ProjectName {
+    /// Validates `name`.
+    pub fn new(name: impl Into<String>) -> Result<ProjectName, ProjectNameError> {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(ProjectNameError::Empty);
+        }
+        if let Some(invalid) = name
+            .chars()
+            .find(|&c| !c.is_alphanumeric() && !"-_.@/:+".contains(c))
+        {
+            return Err(ProjectNameError::InvalidCharacter(name, invalid));
+        }
+        Ok(ProjectName(Cow::Owned(name)))
+    }
+
+    /// The name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Unwraps the name.
+    pub fn into_string(self) -> String {
+        self.0.into_owned()
+    }
+}
+
+impl Deref for ProjectName {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ProjectName {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Allows looking projects up by `&str`.
+impl Borrow<str> for ProjectName {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ProjectName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl PartialEq<str> for ProjectName {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for ProjectName {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl FromStr for ProjectName {
+    type Err = ProjectNameError;
+
+    fn from_str(name: &str) -> Result<ProjectName, ProjectNameError> {
+        ProjectName::new(name)
+    }
+}
+
+impl TryFrom<String> for ProjectName {
+    type Error = ProjectNameError;
+
+    fn try_from(name: String) -> Result<ProjectName, ProjectNameError> {
+        ProjectName::new(name)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for ProjectName {
+    type Error = ProjectNameError;
+
+    fn try_from(name: &'a str) -> Result<ProjectName, ProjectNameError> {
+        ProjectName::new(name)
+    }
+}
+
+impl From<ProjectName> for String {
+    fn from(name: ProjectName) -> String {
+        name.into_string()
+    }
+}
+
+impl fmt::Display for ProjectNameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProjectNameError::Empty => write!(f, "project names cannot be empty"),
+            ProjectNameError::InvalidCharacter(ref name, invalid) => write!(
+                f,
+                "project name {:?} contains the invalid character {:?}",
+                name, invalid
+            ),
+        }
+    }
+}
+
+impl Error for ProjectNameError {}
+
+/// The name under which `GraphQLConfiguration::iter_projects` yields the
+/// top-level configuration of a configuration without projects.
+pub const DEFAULT_PROJECT_NAME: &str = "default";
+
+/// `DEFAULT_PROJECT_NAME` as a `ProjectName`, which `Projects` can lend out.
+static DEFAULT_PROJECT: ProjectName = ProjectName(Cow::Borrowed(DEFAULT_PROJECT_NAME));
+
+/// An iterator over the projects of a configuration, see
+/// `GraphQLConfiguration::iter_projects`.
+#[derive(Clone, Debug)]
+pub struct Projects<'a> {
+    root: Option<&'a GraphQLProjectConfiguration>,
+    projects: vec::IntoIter<(&'a ProjectName, &'a GraphQLProjectConfiguration)>,
+}
+
+impl<'a> Iterator for Projects<'a> {
+    type Item = (&'a ProjectName, &'a GraphQLProjectConfiguration);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            return Some((&DEFAULT_PROJECT, root));
+        }
+        self.projects.next()
+    }
+}
+
+impl GraphQLConfiguration {
+    /// The project with the key `name`, if there is one.
+    pub fn project(&self, name: &str) -> Option<&GraphQLProjectConfiguration> {
+        self.projects
+            .as_ref()
+            .and_then(|projects| projects.get(name))
+    }
+
+    /// The project with the key `name`, falling back to the top-level
+    /// configuration when there is no such project. This is how the reference
+    /// implementation resolves project names, so that tools asked for a
+    /// project of a single-project configuration keep working, and the rule
+    /// `effective_project` follows too.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "./schema.graphql",
+    ///     "projects": {
+    ///         "app": { "schemaPath": "./app.graphql" }
+    ///     }
+    /// }))?;
+    ///
+    /// assert_eq!(config.project_or_root("app").schema_path, Some("./app.graphql".into()));
+    /// assert_eq!(config.project_or_root("lib").schema_path, Some("./schema.graphql".into()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn project_or_root(&self, name: &str) -> &GraphQLProjectConfiguration {
+        self.project(name).unwrap_or(&self.root)
+    }
+
+    /// Iterates over the projects by name, the way the reference
+    /// implementation lists them to tooling: a configuration with projects
+    /// yields each of them, in key order unless the `preserve-order` feature
+    /// keeps the order of the file, while one without projects yields its
+    /// top-level configuration as `DEFAULT_PROJECT_NAME`, unless that is empty.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration =
+    ///     serde_json::from_value(json!({ "schemaPath": "./schema.graphql" }))?;
+    ///
+    /// let names: Vec<&str> = config.iter_projects().map(|(name, _)| name.as_str()).collect();
+    /// assert_eq!(names, vec!["default"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_projects(&self) -> Projects<'_> {
+        let projects = self
+            .projects
+            .as_ref()
+            .filter(|projects| !projects.is_empty());
+        let root = if projects.is_some() || is_empty(&self.root) {
+            None
+        } else {
+            Some(&self.root)
+        };
+
+        Projects {
+            root,
+            projects: projects.map(map::ordered).unwrap_or_default().into_iter(),
+        }
+    }
+
+    /// The project which `path`, relative to the directory of the
+    /// configuration, belongs to: the first one, as listed by
+    /// `iter_projects`, whose `includes` match it, or which has none, and
+    /// whose `excludes` do not. Projects without `includes` or `excludes`
+    /// take those of the top-level configuration. Files of no project belong
+    /// to the top-level configuration, as `DEFAULT_PROJECT_NAME`, unless its
+    /// own patterns leave them out.
+    ///
+    /// This is `ProjectHandle::include_file` without a directory to resolve
+    /// absolute paths against, which never belong to a project.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "includes": ["**/*.graphql"],
+    ///     "projects": {
+    ///         "web": { "includes": ["web/**"], "excludes": ["web/generated/**"] },
+    ///         "api": { "includes": ["api/**"] }
+    ///     }
+    /// }))?;
+    ///
+    /// let owner = |path| config.project_for_file(path).map(|(name, _)| name.as_str());
+    /// assert_eq!(owner("web/src/query.graphql"), Some("web"));
+    /// assert_eq!(owner("api/schema.js"), Some("api"));
+    /// assert_eq!(owner("web/generated/query.graphql"), Some("default"));
+    /// assert_eq!(owner("README.md"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn project_for_file(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Option<(&ProjectName, &GraphQLProjectConfiguration)> {
+        let path = glob::to_slash(path.as_ref())?;
+        let includes_file = |project: &GraphQLProjectConfiguration| {
+            let includes = project.includes.as_ref().or(self.root.includes.as_ref());
+            let excludes = project.excludes.as_ref().or(self.root.excludes.as_ref());
+            let matches = |pattern: &GlobPattern| pattern.matches(&path);
+            includes.is_none_or(|includes| includes.is_empty() || includes.iter().any(matches))
+                && !excludes.is_some_and(|excludes| excludes.iter().any(matches))
+        };
+
+        self.iter_projects()
+            .find(|&(_, project)| includes_file(project))
+            .or_else(|| {
+                Some((&DEFAULT_PROJECT, &self.root)).filter(|&(_, root)| includes_file(root))
+            })
+    }
+}
+
+fn is_empty(project: &GraphQLProjectConfiguration) -> bool {
+    project.name.is_none()
+        && project.schema_path.is_none()
+        && project.includes.is_none()
+        && project.excludes.is_none()
+        && project.extensions.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_looks_up_projects_by_key() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": { "name": "App" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(config.project("app").unwrap().name, Some("App".to_owned()));
+        assert_eq!(config.project("App"), None);
+        assert_eq!(config.project_or_root("App"), &config.root);
+    }
+
+    #[test]
+    fn it_falls_back_to_root_without_projects() {
+        let config: GraphQLConfiguration =
+            ::serde_json::from_value(json!({ "schemaPath": "./schema.graphql" })).unwrap();
+
+        assert_eq!(config.project("app"), None);
+        assert_eq!(config.project_or_root("app"), &config.root);
+    }
+
+    #[test]
+    fn it_iterates_named_projects_without_the_root() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "./schema.graphql",
+            "projects": {
+                "lib": {},
+                "app": {}
+            }
+        }))
+        .unwrap();
+
+        let names: Vec<&str> = config
+            .iter_projects()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(names, vec!["app", "lib"]);
+    }
+
+    #[test]
+    fn it_validates_project_names_when_parsing() {
+        let error = ::serde_json::from_value::<GraphQLConfiguration>(json!({
+            "projects": { "my app": {} }
+        }))
+        .unwrap_err();
+        assert!(error.to_string().contains("invalid character ' '"));
+
+        assert_eq!(ProjectName::new(""), Err(ProjectNameError::Empty));
+        assert_eq!(
+            "web.app".parse::<ProjectName>().map(String::from),
+            Ok("web.app".to_owned())
+        );
+    }
+
+    #[test]
+    fn it_finds_the_project_of_a_file() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "excludes": ["**/*.js"],
+            "projects": {
+                "a": { "includes": ["src/**"], "excludes": ["src/gen/**"] },
+                "b": { "includes": ["src/gen/**", "lib/**"] }
+            }
+        }))
+        .unwrap();
+        let owner = |path: &str| config.project_for_file(path).map(|(name, _)| name.as_str());
+
+        assert_eq!(owner("./src/query.graphql"), Some("a"));
+        assert_eq!(owner("src/gen/query.graphql"), Some("b"));
+        assert_eq!(owner("lib/query.js"), None);
+        assert_eq!(owner("query.graphql"), Some(DEFAULT_PROJECT_NAME));
+        assert_eq!(owner("../query.graphql"), None);
+        assert_eq!(owner("/repo/src/query.graphql"), None);
+
+        let single: GraphQLConfiguration =
+            ::serde_json::from_value(json!({ "includes": ["*.graphql"] })).unwrap();
+        assert_eq!(
+            single.project_for_file("query.graphql"),
+            Some((&DEFAULT_PROJECT, &single.root))
+        );
+        assert_eq!(single.project_for_file("src/query.graphql"), None);
+    }
+
+    #[test]
+    fn it_skips_an_empty_root() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({})).unwrap();
+
+        assert_eq!(config.iter_projects().count(), 0);
+    }
+}
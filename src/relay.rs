@@ -0,0 +1,213 @@
+//! Conversion of [Relay](https://relay.dev/docs/getting-started/compiler-config/)
+//! compiler configurations, `relay.config.json`, to projects and back.
+//!
+//! The `schema` of the compiler becomes the `schemaPath` of the project, its
+//! `src` directory the `includes` and its `excludes` the `excludes`. The
+//! options which have no graphql-config equivalent, among them `src` itself
+//! and `artifactDirectory`, are kept in the typed `RelayExtension`, from
+//! which the compiler configuration is written back.
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # extern crate serde_json;
+//! # use std::convert::TryFrom;
+//! # use graphql_config::relay::RelayConfig;
+//! # use graphql_config::GraphQLProjectConfiguration;
+//! # fn main() {
+//! let relay: RelayConfig = serde_json::from_str(r#"{
+//!     "src": "./src",
+//!     "schema": "./schema.graphql",
+//!     "artifactDirectory": "./src/__generated__"
+//! }"#).unwrap();
+//!
+//! let project = GraphQLProjectConfiguration::try_from(&relay).unwrap();
+//! assert_eq!(project.schema_path, Some("./schema.graphql".into()));
+//! assert_eq!(project.includes.as_ref().unwrap()[0].as_str(), "./src/**");
+//!
+//! assert_eq!(RelayConfig::try_from(&project).unwrap(), relay);
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::extension::Extension;
+use crate::glob::{GlobPattern, PatternError};
+use crate::map::Map;
+use crate::GraphQLProjectConfiguration;
+
+/// The files the Relay compiler excludes unless told otherwise.
+pub const DEFAULT_EXCLUDES: &[&str] = &[
+    "**/node_modules/**",
+    "**/__mocks__/**",
+    "**/__generated__/**",
+];
+
+/// A `relay.config.json`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[non_exhaustive]
+#[serde(rename_all = "camelCase")]
+pub struct RelayConfig {
+    /// The directory of the documents.
+    pub src: String,
+    /// The schema file.
+    pub schema: String,
+    /// Where artifacts are generated, next to their documents unless given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_directory: Option<String>,
+    /// Files of `src` which are not compiled, `DEFAULT_EXCLUDES` unless
+    /// given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excludes: Option<Vec<String>>,
+    /// Every other option of the compiler.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// The `relay` extension of a project converted from a `RelayConfig`.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[non_exhaustive]
+#[serde(rename_all = "camelCase")]
+pub struct RelayExtension {
+    /// The `src` of the compiler.
+    pub src: String,
+    /// The `artifactDirectory` of the compiler.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifact_directory: Option<String>,
+    /// Every other option of the compiler.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Extension for RelayExtension {
+    const NAME: &'static str = "relay";
+}
+
+/// Why a configuration could not be converted.
+#[derive(Debug)]
+pub enum RelayError {
+    /// A pattern of `excludes` is malformed.
+    Pattern(PatternError),
+    /// The project has no `relay` extension to write the compiler
+    /// configuration from.
+    MissingExtension,
+    /// The `relay` extension of the project is malformed.
+    Extension(serde_json::Error),
+    /// The project has no `schemaPath`.
+    MissingSchema,
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RelayError::Pattern(ref error) => write!(f, "`excludes`: {}", error),
+            RelayError::MissingExtension => write!(f, "there is no `relay` extension"),
+            RelayError::Extension(ref error) => write!(f, "invalid `relay` extension: {}", error),
+            RelayError::MissingSchema => write!(f, "there is no `schemaPath`"),
+        }
+    }
+}
+
+impl Error for RelayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RelayError::Pattern(ref error) => Some(error),
+            RelayError::Extension(ref error) => Some(error),
+            RelayError::MissingExtension | RelayError::MissingSchema => None,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a RelayConfig> for GraphQLProjectConfiguration {
+    type Error = RelayError;
+
+    fn try_from(relay: &'a RelayConfig) -> Result<GraphQLProjectConfiguration, RelayError> {
+        let includes = format!("{}/**", relay.src.trim_end_matches('/'));
+        let excludes: Result<Vec<GlobPattern>, PatternError> = match relay.excludes {
+            Some(ref excludes) => excludes.iter().map(GlobPattern::new).collect(),
+            None => DEFAULT_EXCLUDES
+                .iter()
+                .map(|pattern| GlobPattern::new(*pattern))
+                .collect(),
+        };
+
+        let mut project = GraphQLProjectConfiguration::new();
+        project.schema_path = Some(relay.schema.clone().into());
+        project.includes = Some(vec![
+            GlobPattern::new(includes).map_err(RelayError::Pattern)?
+        ]);
+        project.excludes = Some(excludes.map_err(RelayError::Pattern)?);
+        project
+            .set_extension(&RelayExtension {
+                src: relay.src.clone(),
+                artifact_directory: relay.artifact_directory.clone(),
+                other: relay.other.clone(),
+            })
+            .map_err(RelayError::Extension)?;
+        Ok(project)
+    }
+}
+
+impl<'a> TryFrom<&'a GraphQLProjectConfiguration> for RelayConfig {
+    type Error = RelayError;
+
+    fn try_from(project: &'a GraphQLProjectConfiguration) -> Result<RelayConfig, RelayError> {
+        let extension = project
+            .extension::<RelayExtension>()
+            .ok_or(RelayError::MissingExtension)?
+            .map_err(RelayError::Extension)?;
+        let schema = project
+            .schema_path
+            .as_ref()
+            .ok_or(RelayError::MissingSchema)?;
+        let excludes: Option<Vec<String>> = project
+            .excludes
+            .as_ref()
+            .map(|excludes| excludes.iter().map(GlobPattern::to_string).collect());
+
+        Ok(RelayConfig {
+            src: extension.src,
+            schema: schema.to_string_lossy().into_owned(),
+            artifact_directory: extension.artifact_directory,
+            excludes: excludes.filter(|excludes| excludes.as_slice() != DEFAULT_EXCLUDES),
+            other: extension.other,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_relay_configurations_both_ways() {
+        let relay: RelayConfig = ::serde_json::from_value(json!({
+            "src": "./app/",
+            "schema": "./data/schema.graphql",
+            "excludes": ["**/legacy/**"],
+            "language": "typescript"
+        }))
+        .unwrap();
+
+        let project = GraphQLProjectConfiguration::try_from(&relay).unwrap();
+        assert_eq!(
+            ::serde_json::to_value(&project).unwrap(),
+            json!({
+                "name": null,
+                "schemaPath": "./data/schema.graphql",
+                "includes": ["./app/**"],
+                "excludes": ["**/legacy/**"],
+                "extensions": { "relay": { "src": "./app/", "language": "typescript" } }
+            })
+        );
+        assert_eq!(RelayConfig::try_from(&project).unwrap(), relay);
+
+        match RelayConfig::try_from(&GraphQLProjectConfiguration::new()) {
+            Err(RelayError::MissingExtension) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,511 @@
+//! Resolved views of configurations, with the inheritance rules applied.
+//!
+//! The serde types mirror the configuration file as written. The types here
+//! answer what a tool actually wants to know about a project, such as which
+//! schema to load, taking the top-level fallbacks and the location of the
+//! configuration file into account.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::env::{Env, EnvError, Placeholders};
+use crate::glob::GlobPattern;
+use crate::hosts::HostMap;
+use crate::map::{self, Map};
+use crate::matcher::MatcherCache;
+use crate::merge::merge_extensions;
+use crate::projects::Projects;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// A configuration together with the path of the file it was read from.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use std::path::Path;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "schemaPath": "./schema.graphql",
+///     "extensions": { "endpoints": { "dev": "http://localhost:4000/graphql" } }
+/// }))?;
+/// let resolved = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+///
+/// assert_eq!(
+///     resolved.schema_path("default"),
+///     Some(Path::new("/repo/./schema.graphql").to_owned())
+/// );
+/// assert_eq!(
+///     resolved.endpoint("default", "dev"),
+///     Some("http://localhost:4000/graphql".to_owned())
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ResolvedGraphQLConfig {
+    raw: GraphQLConfiguration,
+    path: PathBuf,
+    dir: PathBuf,
+    env: Env,
+    hosts: HostMap,
+    matchers: MatcherCache,
+}
+
+/// The document patterns of a project, see `ResolvedGraphQLConfig::documents`.
+/// The patterns are relative to `ResolvedGraphQLConfig::dir`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Documents {
+    /// Patterns of the files belonging to the project. Empty means all files.
+    pub includes: Vec<GlobPattern>,
+    /// Patterns of the files not belonging to the project.
+    pub excludes: Vec<GlobPattern>,
+}
+
+/// A project with the settings of the top-level configuration merged into it,
+/// see `GraphQLConfiguration::effective_project`.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedProjectConfig {
+    /// The `name` of the project, defaulting to its key.
+    pub name: String,
+    /// The schema of the project, inherited from the top-level configuration
+    /// if the project has none.
+    pub schema_path: Option<PathBuf>,
+    /// The include patterns of the project, inherited as a whole from the
+    /// top-level configuration if the project has none. Empty means all files.
+    pub includes: Vec<GlobPattern>,
+    /// The exclude patterns of the project, inherited as a whole from the
+    /// top-level configuration if the project has none.
+    pub excludes: Vec<GlobPattern>,
+    /// The extensions of the top-level configuration, deep-merged with the
+    /// ones of the project.
+    pub extensions: Map<String, Value>,
+}
+
+impl ResolvedGraphQLConfig {
+    /// Wraps `raw`, which was read from the file at `path`.
+    pub fn new(raw: GraphQLConfiguration, path: impl Into<PathBuf>) -> ResolvedGraphQLConfig {
+        let path = path.into();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_owned(),
+            _ => PathBuf::from("."),
+        };
+        ResolvedGraphQLConfig {
+            raw,
+            path,
+            dir,
+            env: Env::process(),
+            hosts: HostMap::new(),
+            matchers: MatcherCache::new(),
+        }
+    }
+
+    /// Uses `env` for the `${env:NAME}` placeholders of every project, instead
+    /// of the environment of the process.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::env::Env;
+    /// # use graphql_config::resolved::ResolvedGraphQLConfig;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "extensions": { "endpoints": { "dev": "http://${env:HOST}/graphql" } }
+    /// }))?;
+    /// let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig")
+    ///     .with_env(Env::from_map(vec![("HOST", "localhost:4000")]));
+    ///
+    /// assert_eq!(
+    ///     resolved.endpoint("default", "dev"),
+    ///     Some("http://localhost:4000/graphql".to_owned())
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_env(mut self, env: Env) -> ResolvedGraphQLConfig {
+        self.env = env;
+        self
+    }
+
+    /// Rewrites the hosts of the URLs `endpoint` returns with `hosts`, such
+    /// as `HostMap::docker` inside containers.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::hosts::HostMap;
+    /// # use graphql_config::resolved::ResolvedGraphQLConfig;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "extensions": { "endpoints": { "dev": "http://localhost:4000/graphql" } }
+    /// }))?;
+    /// let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig")
+    ///     .with_host_map(HostMap::docker());
+    ///
+    /// assert_eq!(
+    ///     resolved.endpoint("default", "dev"),
+    ///     Some("http://host.docker.internal:4000/graphql".to_owned())
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_host_map(mut self, hosts: HostMap) -> ResolvedGraphQLConfig {
+        self.hosts = hosts;
+        self
+    }
+
+    /// The hosts endpoint URLs are rewritten with.
+    pub fn host_map(&self) -> &HostMap {
+        &self.hosts
+    }
+
+    /// The environment placeholders are read from.
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// The configuration as written.
+    pub fn raw(&self) -> &GraphQLConfiguration {
+        &self.raw
+    }
+
+    /// Unwraps the configuration as written.
+    pub fn into_raw(self) -> GraphQLConfiguration {
+        self.raw
+    }
+
+    /// The path of the configuration file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The directory of the configuration file, which the paths and patterns
+    /// in it are relative to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The cache the project handles take their `GlobSet`s from.
+    pub fn matchers(&self) -> &MatcherCache {
+        &self.matchers
+    }
+
+    /// The projects, as listed by `GraphQLConfiguration::iter_projects`.
+    pub fn projects(&self) -> Projects<'_> {
+        self.raw.iter_projects()
+    }
+
+    /// The project `name` with the top-level settings applied, see
+    /// `GraphQLConfiguration::effective_project`, or `None` if `projects` does
+    /// not list it rather than the top-level fallback, and the `${env:NAME}`
+    /// placeholders of set variables replaced. Patterns which the values of
+    /// variables would make malformed are kept as written; `try_project`
+    /// reports them.
+    pub fn project(&self, name: &str) -> Option<ResolvedProjectConfig> {
+        self.interpolated_project(name, &mut Vec::new())
+    }
+
+    /// Like `project`, but failing with an `EnvError::InvalidPattern` for the
+    /// first pattern the values of variables make malformed. Placeholders of
+    /// unset variables are kept as written.
+    pub fn try_project(&self, name: &str) -> Result<Option<ResolvedProjectConfig>, EnvError> {
+        let mut invalid = Vec::new();
+        let project = self.interpolated_project(name, &mut invalid);
+        match invalid.into_iter().next() {
+            Some(invalid) => Err(invalid),
+            None => Ok(project),
+        }
+    }
+
+    fn interpolated_project(
+        &self,
+        name: &str,
+        invalid: &mut Vec<EnvError>,
+    ) -> Option<ResolvedProjectConfig> {
+        let (key, _) = self.raw.iter_projects().find(|&(key, _)| key == name)?;
+        let mut project = self.raw.effective_project(name);
+        Placeholders::of_resolved(&mut project).replace(
+            &self.env,
+            Some(key),
+            &mut Vec::new(),
+            invalid,
+        );
+        Some(project)
+    }
+
+    /// The schema file of the project `name`, relative to the working
+    /// directory rather than to the configuration file.
+    pub fn schema_path(&self, name: &str) -> Option<PathBuf> {
+        self.project(name)
+            .and_then(|project| project.schema_path)
+            .map(|schema_path| self.dir.join(schema_path))
+    }
+
+    /// The document patterns of the project `name`.
+    pub fn documents(&self, name: &str) -> Option<Documents> {
+        self.project(name).map(|project| Documents {
+            includes: project.includes,
+            excludes: project.excludes,
+        })
+    }
+
+    /// The URL of the endpoint `endpoint` of the project `name`, read from the
+    /// `endpoints` extension, see `GraphQLConfigEndpointConfig`. Its host is
+    /// rewritten by the `HostMap`.
+    pub fn endpoint(&self, name: &str, endpoint: &str) -> Option<String> {
+        let endpoints = self.project(name)?.endpoints()?.ok()?;
+        Some(self.hosts.remap(&endpoints.get(endpoint)?.url))
+    }
+
+    /// The `headers` of the endpoint `endpoint` of the project `name`.
+    pub fn endpoint_headers(&self, name: &str, endpoint: &str) -> Vec<(String, String)> {
+        let endpoints = match self.project(name).and_then(|project| project.endpoints()) {
+            Some(Ok(endpoints)) => endpoints,
+            _ => return Vec::new(),
+        };
+        let headers = match endpoints.endpoints.get(endpoint) {
+            Some(endpoint) => map::ordered(&endpoint.headers),
+            None => return Vec::new(),
+        };
+        headers
+            .into_iter()
+            .map(|(header, value)| (header.clone(), value.clone()))
+            .collect()
+    }
+}
+
+impl GraphQLConfiguration {
+    /// Resolves the project `name` into a single view: every setting of the
+    /// project wins over the top-level one, the top-level value fills in
+    /// otherwise, and extensions are merged recursively so a project can
+    /// override part of an extension.
+    ///
+    /// Names are looked up as `project_or_root` does: one which is not a key
+    /// of `projects` resolves the top-level configuration, named `name`
+    /// unless it has a `name` of its own.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "./schema.graphql",
+    ///     "extensions": { "codegen": { "output": "./generated", "hooks": true } },
+    ///     "projects": {
+    ///         "app": { "extensions": { "codegen": { "output": "./app/generated" } } }
+    ///     }
+    /// }))?;
+    ///
+    /// let app = config.effective_project("app");
+    /// assert_eq!(app.schema_path, Some("./schema.graphql".into()));
+    /// assert_eq!(
+    ///     app.extensions["codegen"],
+    ///     json!({ "output": "./app/generated", "hooks": true })
+    /// );
+    /// assert_eq!(config.effective_project("lib").extensions["codegen"]["output"], "./generated");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn effective_project(&self, name: &str) -> ResolvedProjectConfig {
+        self.resolve(name, self.project_or_root(name))
+    }
+
+    fn resolve(&self, key: &str, project: &GraphQLProjectConfiguration) -> ResolvedProjectConfig {
+        let root = &self.root;
+
+        let mut extensions = root.extensions.clone().unwrap_or_default();
+        if let Some(ref overlay) = project.extensions {
+            merge_extensions(&mut extensions, overlay);
+        }
+
+        ResolvedProjectConfig {
+            name: project.name.clone().unwrap_or_else(|| key.to_owned()),
+            schema_path: project
+                .schema_path
+                .as_ref()
+                .or(root.schema_path.as_ref())
+                .cloned(),
+            includes: project
+                .includes
+                .as_ref()
+                .or(root.includes.as_ref())
+                .cloned()
+                .unwrap_or_default(),
+            excludes: project
+                .excludes
+                .as_ref()
+                .or(root.excludes.as_ref())
+                .cloned()
+                .unwrap_or_default(),
+            extensions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lets_projects_win_over_the_root() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "./schema.graphql",
+            "includes": ["./src/*.graphql"],
+            "excludes": ["./src/generated/*"],
+            "extensions": { "lastUpdatedAt": 1, "endpoints": { "dev": "http://localhost" } },
+            "projects": {
+                "app": {
+                    "name": "App",
+                    "includes": ["./app/*.graphql"],
+                    "extensions": { "lastUpdatedAt": 2, "endpoints": { "prod": "https://example.com" } }
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.effective_project("app"),
+            ResolvedProjectConfig {
+                name: "App".to_owned(),
+                schema_path: Some("./schema.graphql".into()),
+                includes: vec!["./app/*.graphql".parse().unwrap()],
+                excludes: vec!["./src/generated/*".parse().unwrap()],
+                extensions: map! {
+                    "lastUpdatedAt".to_owned() => json!(2),
+                    "endpoints".to_owned() => json!({
+                        "dev": "http://localhost",
+                        "prod": "https://example.com"
+                    }),
+                },
+            }
+        );
+        let default = config.effective_project("default");
+        assert_eq!(default.name, "default");
+        assert_eq!(default.excludes[0].as_str(), "./src/generated/*");
+        assert_eq!(
+            default.extensions["endpoints"],
+            json!({ "dev": "http://localhost" })
+        );
+    }
+
+    #[test]
+    fn it_resolves_the_default_project() {
+        let config: GraphQLConfiguration =
+            ::serde_json::from_value(json!({ "schemaPath": "./schema.graphql" })).unwrap();
+
+        let resolved = config.effective_project("default");
+        assert_eq!(resolved.name, "default");
+        assert_eq!(resolved.schema_path, Some("./schema.graphql".into()));
+        assert!(resolved.includes.is_empty());
+    }
+
+    #[test]
+    fn it_resolves_relative_to_the_config_file() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": {
+                    "schemaPath": "app.graphql",
+                    "includes": ["app/*.graphql"],
+                    "extensions": {
+                        "endpoints": { "prod": { "url": "https://example.com/graphql" } }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig");
+
+        assert_eq!(resolved.dir(), Path::new("."));
+        assert_eq!(
+            resolved.schema_path("app"),
+            Some(PathBuf::from("./app.graphql"))
+        );
+        assert_eq!(
+            resolved.documents("app"),
+            Some(Documents {
+                includes: vec!["app/*.graphql".parse().unwrap()],
+                excludes: vec![],
+            })
+        );
+        assert_eq!(
+            resolved.endpoint("app", "prod"),
+            Some("https://example.com/graphql".to_owned())
+        );
+        assert_eq!(resolved.endpoint("app", "dev"), None);
+        assert_eq!(resolved.schema_path("lib"), None);
+    }
+
+    #[test]
+    fn it_reads_endpoints_and_headers_through_one_parser() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "extensions": {
+                "endpoints": {
+                    "prod": {
+                        "url": "https://example.com/graphql",
+                        "headers": { "Authorization": "Bearer token" }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+        let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig");
+        assert_eq!(
+            resolved.endpoint("default", "prod"),
+            Some("https://example.com/graphql".to_owned())
+        );
+        assert_eq!(
+            resolved.endpoint_headers("default", "prod"),
+            vec![("Authorization".to_owned(), "Bearer token".to_owned())]
+        );
+
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "extensions": {
+                "endpoints": {
+                    "prod": { "url": "https://example.com/graphql", "headers": { "X-Retries": 3 } }
+                }
+            }
+        }))
+        .unwrap();
+        let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig");
+        assert_eq!(resolved.endpoint("default", "prod"), None);
+        assert!(resolved.endpoint_headers("default", "prod").is_empty());
+    }
+
+    #[test]
+    fn it_reports_patterns_the_variables_make_malformed() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "name": "${env:NAME}",
+            "includes": ["${env:SRC}/*.graphql", "${env:UNSET}/*.graphql"]
+        }))
+        .unwrap();
+        let env = Env::from_map(vec![("NAME", "web"), ("SRC", "src")]);
+        let resolved = ResolvedGraphQLConfig::new(config.clone(), ".graphqlconfig").with_env(env);
+        let project = resolved.try_project("default").unwrap().unwrap();
+        assert_eq!(project.name, "web");
+        assert_eq!(project.includes[0].as_str(), "src/*.graphql");
+        assert_eq!(project.includes[1].as_str(), "${env:UNSET}/*.graphql");
+
+        let env = Env::from_map(vec![("SRC", "[src")]);
+        let resolved = ResolvedGraphQLConfig::new(config, ".graphqlconfig").with_env(env);
+        match resolved.try_project("default") {
+            Err(EnvError::InvalidPattern { ref pattern, .. })
+                if pattern == "${env:SRC}/*.graphql" => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            resolved.project("default").unwrap().includes[0].as_str(),
+            "${env:SRC}/*.graphql"
+        );
+        assert_eq!(resolved.try_project("web"), Ok(None));
+    }
+}
@@ -0,0 +1,323 @@
+//! Locating the schema of a project.
+
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::resolved::{ResolvedGraphQLConfig, ResolvedProjectConfig};
+use crate::{v3, GraphQLProjectConfiguration};
+
+/// Where a schema is loaded from.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum SchemaPointer {
+    /// A local file, holding either SDL or an introspection result.
+    File(PathBuf),
+    /// A glob matching several local files whose SDL makes up the schema.
+    Glob(String),
+    /// A GraphQL endpoint to introspect.
+    Url(String),
+}
+
+impl SchemaPointer {
+    /// Classifies `pointer` as written in a configuration, resolving local
+    /// paths against `base_dir`.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::schema::SchemaPointer;
+    /// # use std::path::Path;
+    /// # fn main() {
+    /// let base_dir = Path::new("/repo");
+    ///
+    /// assert_eq!(
+    ///     SchemaPointer::classify("schema.graphql", base_dir),
+    ///     SchemaPointer::File("/repo/schema.graphql".into())
+    /// );
+    /// assert_eq!(
+    ///     SchemaPointer::classify("schema/*.graphql", base_dir),
+    ///     SchemaPointer::Glob("/repo/schema/*.graphql".to_owned())
+    /// );
+    /// assert_eq!(
+    ///     SchemaPointer::classify("https://example.com/graphql", base_dir),
+    ///     SchemaPointer::Url("https://example.com/graphql".to_owned())
+    /// );
+    /// # }
+    /// ```
+    pub fn classify(pointer: &str, base_dir: &Path) -> SchemaPointer {
+        if is_url(pointer) {
+            SchemaPointer::Url(pointer.to_owned())
+        } else if pointer.contains(|c| "*?[{".contains(c)) {
+            SchemaPointer::Glob(base_dir.join(pointer).to_string_lossy().into_owned())
+        } else {
+            SchemaPointer::File(base_dir.join(pointer))
+        }
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// The schema of the project, classified and resolved against `base_dir`.
+    /// Like `v3::GraphQLProjectConfiguration::schema_sources`, so that code
+    /// handling both versions reads the same.
+    pub fn schema_sources(&self, base_dir: &Path) -> Vec<SchemaPointer> {
+        self.schema_path
+            .iter()
+            .map(|schema_path| SchemaPointer::classify(&schema_path.to_string_lossy(), base_dir))
+            .collect()
+    }
+}
+
+impl v3::GraphQLProjectConfiguration {
+    /// The schema pointers of the project, classified and resolved against
+    /// `base_dir`, whether `schema` is a single pointer or a list.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::schema::SchemaPointer;
+    /// # use std::path::Path;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let legacy: GraphQLConfiguration =
+    ///     serde_json::from_value(json!({ "schemaPath": "schema.graphql" }))?;
+    /// let modern: v3::GraphQLConfiguration =
+    ///     serde_json::from_value(json!({ "schema": ["schema.graphql"] }))?;
+    ///
+    /// let base_dir = Path::new("/repo");
+    /// assert_eq!(
+    ///     legacy.root.schema_sources(base_dir),
+    ///     vec![SchemaPointer::File("/repo/schema.graphql".into())]
+    /// );
+    /// assert_eq!(legacy.root.schema_sources(base_dir), modern.root.schema_sources(base_dir));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schema_sources(&self, base_dir: &Path) -> Vec<SchemaPointer> {
+        self.schema
+            .as_ref()
+            .map_or_else(Vec::new, v3::Pointer::pointers)
+            .into_iter()
+            .map(|pointer| SchemaPointer::classify(pointer, base_dir))
+            .collect()
+    }
+}
+
+fn is_url(pointer: &str) -> bool {
+    pointer.starts_with("http://") || pointer.starts_with("https://")
+}
+
+/// Where the schema of a project comes from, telling tools which loading
+/// strategy applies.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SchemaSource {
+    /// The project has neither a schema nor endpoints.
+    None,
+    /// The schema is read from local files.
+    Files,
+    /// The schema is introspected from an endpoint, given either as the
+    /// `schemaPath` or in the `endpoints` extension.
+    Endpoint,
+    /// The schema is read from local files, which can be refreshed from the
+    /// endpoints of the project.
+    Both,
+}
+
+impl ResolvedProjectConfig {
+    /// Classifies where the schema of the project comes from.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::schema::SchemaSource;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "schema.graphql",
+    ///     "projects": {
+    ///         "app": {},
+    ///         "api": { "extensions": { "endpoints": { "dev": "http://localhost:4000" } } }
+    ///     }
+    /// }))?;
+    ///
+    /// let app = config.effective_project("app");
+    /// assert_eq!(app.schema_source(), SchemaSource::Files);
+    /// assert!(app.is_file_based() && !app.is_endpoint_based());
+    /// assert_eq!(config.effective_project("api").schema_source(), SchemaSource::Both);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schema_source(&self) -> SchemaSource {
+        let schema_path = self
+            .schema_path
+            .as_ref()
+            .map(|schema_path| schema_path.to_string_lossy());
+        let remote_schema = schema_path.as_ref().is_some_and(|path| is_url(path));
+        let files = schema_path.is_some() && !remote_schema;
+        let endpoints = remote_schema
+            || match self.endpoints() {
+                Some(Ok(endpoints)) => !endpoints.endpoints.is_empty(),
+                _ => false,
+            };
+
+        match (files, endpoints) {
+            (false, false) => SchemaSource::None,
+            (true, false) => SchemaSource::Files,
+            (false, true) => SchemaSource::Endpoint,
+            (true, true) => SchemaSource::Both,
+        }
+    }
+
+    /// Whether the schema is read from local files.
+    pub fn is_file_based(&self) -> bool {
+        match self.schema_source() {
+            SchemaSource::Files | SchemaSource::Both => true,
+            SchemaSource::None | SchemaSource::Endpoint => false,
+        }
+    }
+
+    /// Whether the schema can be introspected from an endpoint.
+    pub fn is_endpoint_based(&self) -> bool {
+        match self.schema_source() {
+            SchemaSource::Endpoint | SchemaSource::Both => true,
+            SchemaSource::None | SchemaSource::Files => false,
+        }
+    }
+}
+
+/// The error returned when the schema of a project cannot be located.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SchemaPathError {
+    /// There is no project with the given name.
+    UnknownProject(String),
+    /// Neither the project nor the top-level configuration has a schema.
+    MissingSchema(String),
+}
+
+impl fmt::Display for SchemaPathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SchemaPathError::UnknownProject(ref project) => {
+                write!(f, "there is no project `{}`", project)
+            }
+            SchemaPathError::MissingSchema(ref project) => {
+                write!(f, "project `{}` has no schemaPath", project)
+            }
+        }
+    }
+}
+
+impl Error for SchemaPathError {}
+
+impl ResolvedGraphQLConfig {
+    /// Answers which schema to load for the project `name`: the schema of the
+    /// project or else of the top-level configuration, resolved against the
+    /// directory of the configuration file.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # use graphql_config::resolved::ResolvedGraphQLConfig;
+    /// # use graphql_config::schema::SchemaPointer;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "schema.graphql",
+    ///     "projects": { "app": {} }
+    /// }))?;
+    /// let resolved = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+    ///
+    /// assert_eq!(
+    ///     resolved.schema_path_for("app")?,
+    ///     SchemaPointer::File("/repo/schema.graphql".into())
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schema_path_for(&self, name: &str) -> Result<SchemaPointer, SchemaPathError> {
+        let project = self
+            .project(name)
+            .ok_or_else(|| SchemaPathError::UnknownProject(name.to_owned()))?;
+        let schema_path = project
+            .schema_path
+            .ok_or_else(|| SchemaPathError::MissingSchema(name.to_owned()))?;
+
+        Ok(SchemaPointer::classify(
+            &schema_path.to_string_lossy(),
+            self.dir(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GraphQLConfiguration;
+
+    #[test]
+    fn it_lists_schema_sources_of_both_versions() {
+        let config: v3::GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schema": ["https://example.com/graphql", "schema/*.graphql"]
+        }))
+        .unwrap();
+        let base_dir = Path::new("/repo");
+
+        assert_eq!(
+            config.root.schema_sources(base_dir),
+            vec![
+                SchemaPointer::Url("https://example.com/graphql".to_owned()),
+                SchemaPointer::Glob("/repo/schema/*.graphql".to_owned()),
+            ]
+        );
+        assert!(GraphQLProjectConfiguration::new()
+            .schema_sources(base_dir)
+            .is_empty());
+    }
+
+    #[test]
+    fn it_classifies_remote_schema_paths_as_endpoints() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "remote": { "schemaPath": "https://example.com/graphql" },
+                "empty": { "extensions": { "endpoints": {} } }
+            }
+        }))
+        .unwrap();
+
+        let remote = config.effective_project("remote");
+        assert_eq!(remote.schema_source(), SchemaSource::Endpoint);
+        assert!(!remote.is_file_based());
+        assert_eq!(
+            config.effective_project("empty").schema_source(),
+            SchemaSource::None
+        );
+    }
+
+    #[test]
+    fn it_reports_why_there_is_no_schema() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": { "schemaPath": "http://localhost:4000/graphql" },
+                "lib": {}
+            }
+        }))
+        .unwrap();
+        let resolved = ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig");
+
+        assert_eq!(
+            resolved.schema_path_for("app"),
+            Ok(SchemaPointer::Url(
+                "http://localhost:4000/graphql".to_owned()
+            ))
+        );
+        assert_eq!(
+            resolved.schema_path_for("lib"),
+            Err(SchemaPathError::MissingSchema("lib".to_owned()))
+        );
+        assert_eq!(
+            resolved.schema_path_for("default"),
+            Err(SchemaPathError::UnknownProject("default".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Caching the schemas fetched for projects.
+//!
+//! Introspecting an endpoint or reading a large schema is slow, so tools keep
+//! the results. `SchemaCache` is the store they keep them in, by a key such as
+//! `SchemaPointer::cache_key`. `MemoryCache` and `DiskCache` are provided;
+//! services can implement the trait on top of Redis or their own stores.
+
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::fs;
+use std::io;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::schema::SchemaPointer;
+
+/// A store of schemas by key.
+///
+/// The methods take `&self`, so that one cache can be shared, and return
+/// `io::Result`s, into which other stores can wrap their errors with
+/// `io::Error::new`.
+pub trait SchemaCache {
+    /// The schema stored under `key`, if any.
+    fn get(&self, key: &str) -> io::Result<Option<String>>;
+
+    /// Stores `schema` under `key`, replacing whatever was stored there.
+    fn put(&self, key: &str, schema: &str) -> io::Result<()>;
+}
+
+/// A shared cache, such as one a `GraphQLCache` reads and a
+/// `fetch::SchemaPoller` writes.
+impl<C: SchemaCache + ?Sized> SchemaCache for Arc<C> {
+    fn get(&self, key: &str) -> io::Result<Option<String>> {
+        (**self).get(key)
+    }
+
+    fn put(&self, key: &str, schema: &str) -> io::Result<()> {
+        (**self).put(key, schema)
+    }
+}
+
+impl SchemaPointer {
+    /// The key the schema of the pointer is cached under.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::schema::SchemaPointer;
+    /// let pointer = SchemaPointer::Url("https://example.com/graphql".to_owned());
+    /// assert_eq!(pointer.cache_key(), "url:https://example.com/graphql");
+    /// ```
+    pub fn cache_key(&self) -> String {
+        match *self {
+            SchemaPointer::File(ref path) => format!("file:{}", path.to_string_lossy()),
+            SchemaPointer::Glob(ref glob) => format!("glob:{}", glob),
+            SchemaPointer::Url(ref url) => format!("url:{}", url),
+        }
+    }
+}
+
+/// A cache in memory, which can be shared between threads.
+#[derive(Default, Debug)]
+pub struct MemoryCache {
+    schemas: Mutex<HashMap<String, String>>,
+}
+
+impl MemoryCache {
+    /// An empty cache.
+    pub fn new() -> MemoryCache {
+        MemoryCache::default()
+    }
+}
+
+impl SchemaCache for MemoryCache {
+    fn get(&self, key: &str) -> io::Result<Option<String>> {
+        let schemas = self
+            .schemas
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        Ok(schemas.get(key).cloned())
+    }
+
+    fn put(&self, key: &str, schema: &str) -> io::Result<()> {
+        let mut schemas = self
+            .schemas
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        schemas.insert(key.to_owned(), schema.to_owned());
+        Ok(())
+    }
+}
+
+/// A cache in a directory, with one file per key, which survives the
+/// process.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::schema::SchemaPointer;
+/// # use graphql_config::schema_cache::{DiskCache, SchemaCache};
+/// # fn main() -> std::io::Result<()> {
+/// let cache = DiskCache::new("/tmp/graphql-schemas");
+/// let key = SchemaPointer::Url("https://example.com/graphql".to_owned()).cache_key();
+///
+/// if cache.get(&key)?.is_none() {
+///     cache.put(&key, "type Query { hello: String }")?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "fs")]
+#[derive(Clone, Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[cfg(feature = "fs")]
+impl DiskCache {
+    /// A cache in `dir`, which is created when the first schema is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> DiskCache {
+        DiskCache { dir: dir.into() }
+    }
+
+    /// The directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file of `key`, whose name escapes every character but ASCII
+    /// letters, digits, `-` and `_` as `%` and its hexadecimal UTF-8 bytes.
+    fn path(&self, key: &str) -> PathBuf {
+        let mut name = String::with_capacity(key.len() + ".graphql".len());
+        for byte in key.bytes() {
+            match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+                _ => name.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        name.push_str(".graphql");
+        self.dir.join(name)
+    }
+}
+
+#[cfg(feature = "fs")]
+impl SchemaCache for DiskCache {
+    fn get(&self, key: &str) -> io::Result<Option<String>> {
+        match fs::read_to_string(self.path(key)) {
+            Ok(schema) => Ok(Some(schema)),
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn put(&self, key: &str, schema: &str) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(key), schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "fs")]
+    use crate::test_dir::TestDir;
+
+    fn round_trip(cache: &dyn SchemaCache) {
+        let key = "url:https://example.com/graphql";
+        assert_eq!(cache.get(key).unwrap(), None);
+        cache.put(key, "type Query { a: Int }").unwrap();
+        cache.put(key, "type Query { b: Int }").unwrap();
+        assert_eq!(
+            cache.get(key).unwrap(),
+            Some("type Query { b: Int }".to_owned())
+        );
+        assert_eq!(cache.get("url:https://example.com").unwrap(), None);
+    }
+
+    #[test]
+    fn it_caches_in_memory() {
+        round_trip(&MemoryCache::new());
+    }
+
+    #[test]
+    #[cfg(feature = "fs")]
+    fn it_caches_on_disk() {
+        let dir = TestDir::new("it_caches_on_disk");
+        let cache = DiskCache::new(&*dir);
+
+        round_trip(&cache);
+        assert!(dir
+            .join("url%3Ahttps%3A%2F%2Fexample%2Ecom%2Fgraphql.graphql")
+            .is_file());
+    }
+}
@@ -0,0 +1,226 @@
+//! Configurations shared between threads.
+//!
+//! The configuration types, `GraphQLConfiguration`,
+//! `GraphQLProjectConfiguration` and `ResolvedGraphQLConfig` with its `Env`,
+//! `HostMap` and `MatcherCache`, are all `Send` and `Sync`, as are the
+//! caches, handles and watchers built on them. Reading a configuration from
+//! several threads only takes sharing it, as a `SharedConfig` does.
+//!
+//! Tools which also edit it at runtime hold a `ConcurrentConfig`, whose
+//! readers take snapshots while writers edit a copy and swap it in.
+
+use std::ops::Deref;
+use std::sync::{Arc, RwLock};
+
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::GraphQLConfiguration;
+
+/// A `ResolvedGraphQLConfig` behind an `Arc`, for servers handling requests
+/// on several threads. Cloning it only counts a reference, and all of the
+/// read accessors of the configuration are reachable through `Deref`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use graphql_config::shared::SharedConfig;
+/// # use std::thread;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({ "schemaPath": "schema.graphql" }))?;
+/// let shared = SharedConfig::new(ResolvedGraphQLConfig::new(config, "/repo/.graphqlconfig"));
+///
+/// let handler = {
+///     let shared = shared.clone();
+///     thread::spawn(move || shared.schema_path("default"))
+/// };
+/// assert_eq!(handler.join().unwrap(), shared.schema_path("default"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SharedConfig(Arc<ResolvedGraphQLConfig>);
+
+impl SharedConfig {
+    /// Shares `config`.
+    pub fn new(config: ResolvedGraphQLConfig) -> SharedConfig {
+        SharedConfig(Arc::new(config))
+    }
+
+    /// The shared configuration.
+    pub fn get(&self) -> &ResolvedGraphQLConfig {
+        &self.0
+    }
+
+    /// Whether `self` and `other` share the same configuration, rather than
+    /// equal ones.
+    pub fn ptr_eq(&self, other: &SharedConfig) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for SharedConfig {
+    type Target = ResolvedGraphQLConfig;
+
+    fn deref(&self) -> &ResolvedGraphQLConfig {
+        &self.0
+    }
+}
+
+impl AsRef<ResolvedGraphQLConfig> for SharedConfig {
+    fn as_ref(&self) -> &ResolvedGraphQLConfig {
+        &self.0
+    }
+}
+
+impl From<ResolvedGraphQLConfig> for SharedConfig {
+    fn from(config: ResolvedGraphQLConfig) -> SharedConfig {
+        SharedConfig::new(config)
+    }
+}
+
+impl From<Arc<ResolvedGraphQLConfig>> for SharedConfig {
+    fn from(config: Arc<ResolvedGraphQLConfig>) -> SharedConfig {
+        SharedConfig(config)
+    }
+}
+
+/// A configuration shared between threads which may edit it.
+///
+/// Reads are cheap: `snapshot` only counts a reference to the configuration
+/// as it is, which later edits leave alone. `edit` clones the configuration
+/// as written, edits it and swaps it in, keeping the path, `Env` and
+/// `HostMap`; edits on several threads wait for each other, so none is lost.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::resolved::ResolvedGraphQLConfig;
+/// # use graphql_config::shared::ConcurrentConfig;
+/// # use std::thread;
+/// # fn main() {
+/// let config = ConcurrentConfig::new(ResolvedGraphQLConfig::new(
+///     GraphQLConfiguration::new(),
+///     "/repo/.graphqlconfig",
+/// ));
+/// let before = config.snapshot();
+///
+/// let editor = {
+///     let config = config.clone();
+///     thread::spawn(move || config.edit(|raw| raw.root.set_schema_path("schema.graphql")))
+/// };
+/// editor.join().unwrap();
+///
+/// assert_eq!(before.schema_path("default"), None);
+/// assert!(config.snapshot().schema_path("default").is_some());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConcurrentConfig(Arc<RwLock<SharedConfig>>);
+
+impl ConcurrentConfig {
+    /// Shares `config` for reading and editing.
+    pub fn new(config: ResolvedGraphQLConfig) -> ConcurrentConfig {
+        ConcurrentConfig(Arc::new(RwLock::new(SharedConfig::new(config))))
+    }
+
+    /// The configuration as it is now.
+    pub fn snapshot(&self) -> SharedConfig {
+        self.0
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+            .clone()
+    }
+
+    /// Edits the configuration as written with `edit`, and returns what it
+    /// returned. Snapshots taken from now on see the edit.
+    pub fn edit<T>(&self, edit: impl FnOnce(&mut GraphQLConfiguration) -> T) -> T {
+        let mut shared = self.0.write().unwrap_or_else(|error| error.into_inner());
+        let mut raw = shared.raw().clone();
+        let result = edit(&mut raw);
+        let config = ResolvedGraphQLConfig::new(raw, shared.path())
+            .with_env(shared.env().clone())
+            .with_host_map(shared.host_map().clone());
+        *shared = SharedConfig::new(config);
+        result
+    }
+}
+
+impl From<ResolvedGraphQLConfig> for ConcurrentConfig {
+    fn from(config: ResolvedGraphQLConfig) -> ConcurrentConfig {
+        ConcurrentConfig::new(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::Env;
+    use crate::hosts::HostMap;
+    use crate::matcher::MatcherCache;
+    use crate::schema_cache::MemoryCache;
+    use crate::GraphQLProjectConfiguration;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn the_core_types_are_send_and_sync() {
+        assert_send_sync::<GraphQLConfiguration>();
+        assert_send_sync::<GraphQLProjectConfiguration>();
+        assert_send_sync::<ResolvedGraphQLConfig>();
+        assert_send_sync::<Env>();
+        assert_send_sync::<HostMap>();
+        assert_send_sync::<MatcherCache>();
+        assert_send_sync::<SharedConfig>();
+        assert_send_sync::<ConcurrentConfig>();
+        assert_send_sync::<MemoryCache>();
+        #[cfg(all(feature = "fs", feature = "graphql-parser"))]
+        assert_send_sync::<crate::cache::GraphQLCache>();
+        #[cfg(feature = "watch")]
+        assert_send_sync::<crate::watch::ConfigHandle>();
+    }
+
+    #[test]
+    fn it_shares_rather_than_copies() {
+        let resolved = || ResolvedGraphQLConfig::new(GraphQLConfiguration::new(), ".graphqlconfig");
+        let shared = SharedConfig::new(resolved());
+
+        assert!(shared.ptr_eq(&shared.clone()));
+        assert!(!shared.ptr_eq(&resolved().into()));
+        assert_eq!(shared.path(), shared.get().path());
+    }
+
+    #[test]
+    fn it_keeps_every_concurrent_edit() {
+        let resolved = ResolvedGraphQLConfig::new(GraphQLConfiguration::new(), ".graphqlconfig")
+            .with_env(Env::from_map(vec![("SCHEMA", "schema.graphql")]));
+        let config = ConcurrentConfig::new(resolved);
+
+        let editors: Vec<_> = (0..8)
+            .map(|index| {
+                let config = config.clone();
+                thread::spawn(move || {
+                    config.edit(|raw| {
+                        let project = GraphQLProjectConfiguration {
+                            schema_path: Some("${env:SCHEMA}".into()),
+                            ..GraphQLProjectConfiguration::new()
+                        };
+                        raw.insert_project(format!("p{}", index).parse().unwrap(), project)
+                    })
+                })
+            })
+            .collect();
+        for editor in editors {
+            assert_eq!(editor.join().unwrap(), None);
+        }
+
+        let snapshot = config.snapshot();
+        assert_eq!(snapshot.projects().count(), 8);
+        assert_eq!(
+            snapshot.schema_path("p3"),
+            Some(snapshot.dir().join("schema.graphql"))
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Human-readable overviews of configurations.
+
+use std::fmt;
+
+use crate::map;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// Prints a compact overview of the configuration, meant for `show`/`info`
+/// style commands. Documents are summarized by the number of their include
+/// and exclude patterns, as finding the files they match takes a directory.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "schemaPath": "./schema.graphql",
+///     "projects": {
+///         "app": {
+///             "includes": ["./app/**/*.graphql"],
+///             "extensions": { "endpoints": {} }
+///         }
+///     }
+/// }))?;
+///
+/// assert_eq!(
+///     config.to_string(),
+///     "schema: ./schema.graphql
+/// patterns: (all files)
+/// projects: 1
+///   app
+///     schema: (none)
+///     patterns: 1 include
+///     extensions: endpoints
+/// "
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl fmt::Display for GraphQLConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_project(f, &self.root, "")?;
+
+        if let Some(ref projects) = self.projects {
+            writeln!(f, "projects: {}", projects.len())?;
+            for (key, project) in map::ordered(projects) {
+                writeln!(f, "  {}", key)?;
+                write_project(f, project, "    ")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints a compact overview of the project, one field per line.
+impl fmt::Display for GraphQLProjectConfiguration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_project(f, self, "")
+    }
+}
+
+fn write_project(
+    f: &mut fmt::Formatter,
+    project: &GraphQLProjectConfiguration,
+    indent: &str,
+) -> fmt::Result {
+    if let Some(ref name) = project.name {
+        writeln!(f, "{}name: {}", indent, name)?;
+    }
+
+    match project.schema_path {
+        Some(ref schema_path) => writeln!(f, "{}schema: {}", indent, schema_path.display())?,
+        None => writeln!(f, "{}schema: (none)", indent)?,
+    }
+
+    write!(f, "{}patterns: ", indent)?;
+    match project.includes {
+        Some(ref includes) => write!(f, "{}", count(includes.len(), "include"))?,
+        None => write!(f, "(all files)")?,
+    }
+    match project.excludes {
+        Some(ref excludes) if !excludes.is_empty() => {
+            writeln!(f, ", {}", count(excludes.len(), "exclude"))?
+        }
+        _ => writeln!(f)?,
+    }
+
+    if let Some(ref extensions) = project.extensions {
+        if !extensions.is_empty() {
+            let names: Vec<&str> = map::ordered(extensions)
+                .into_iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            writeln!(f, "{}extensions: {}", indent, names.join(", "))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn count(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_summarizes_projects() {
+        let project: GraphQLProjectConfiguration = ::serde_json::from_value(json!({
+            "name": "App",
+            "schemaPath": "./app.graphql",
+            "includes": ["./app/*.graphql", "./shared/*.graphql"],
+            "excludes": ["./app/generated/*"],
+            "extensions": { "endpoints": {}, "lastUpdatedAt": 1 }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            project.to_string(),
+            "name: App
+schema: ./app.graphql
+patterns: 2 includes, 1 exclude
+extensions: endpoints, lastUpdatedAt
+"
+        );
+    }
+}
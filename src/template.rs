@@ -0,0 +1,129 @@
+//! Starting points for new configurations.
+//!
+//! Each `ConfigTemplate` describes a common repository layout and builds the
+//! configuration for it, ready to be serialized as `.graphqlconfig`. They back
+//! scaffolding such as an `init` command, and keep examples in onboarding
+//! documentation in line with what the crate accepts.
+
+use crate::builder::BuildError;
+use crate::GraphQLConfiguration;
+
+/// A preset for a new configuration.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::template::ConfigTemplate;
+/// # fn main() -> Result<(), graphql_config::builder::BuildError> {
+/// let config = ConfigTemplate::Monorepo(vec!["web".to_owned()]).build()?;
+///
+/// assert_eq!(
+///     serde_json::to_value(&config).unwrap(),
+///     json!({
+///         "name": null,
+///         "schemaPath": "schema.graphql",
+///         "includes": null,
+///         "excludes": ["**/node_modules/**"],
+///         "extensions": null,
+///         "projects": {
+///             "web": {
+///                 "name": "web",
+///                 "schemaPath": null,
+///                 "includes": ["packages/web/src/**/*.graphql"],
+///                 "excludes": null,
+///                 "extensions": null
+///             }
+///         }
+///     })
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ConfigTemplate {
+    /// A single application: `schema.graphql` next to the configuration, and
+    /// the documents under `src`.
+    SingleProject,
+    /// A repository of packages sharing `schema.graphql`, with one project
+    /// per package, named by the variant, whose documents are under
+    /// `packages/<name>/src`.
+    Monorepo(Vec<String>),
+    /// A schema served by the GraphQL endpoint with the URL in the variant,
+    /// configured as the `default` endpoint, and downloaded to
+    /// `schema.graphql`.
+    EndpointBased(String),
+}
+
+/// The schema file of every template.
+const SCHEMA_PATH: &str = "schema.graphql";
+
+impl ConfigTemplate {
+    /// Builds the configuration of the template. Fails on invalid or
+    /// repeated package names of a `Monorepo`.
+    pub fn build(&self) -> Result<GraphQLConfiguration, BuildError> {
+        match *self {
+            ConfigTemplate::SingleProject => GraphQLConfiguration::builder()
+                .schema_path(SCHEMA_PATH)
+                .include("src/**/*.graphql")
+                .build(),
+            ConfigTemplate::Monorepo(ref packages) => packages
+                .iter()
+                .fold(
+                    GraphQLConfiguration::builder()
+                        .schema_path(SCHEMA_PATH)
+                        .exclude("**/node_modules/**"),
+                    |config, package| {
+                        config.project(package.as_str(), |project| {
+                            project
+                                .name(package.as_str())
+                                .include(format!("packages/{}/src/**/*.graphql", package))
+                        })
+                    },
+                )
+                .build(),
+            ConfigTemplate::EndpointBased(ref url) => GraphQLConfiguration::builder()
+                .schema_path(SCHEMA_PATH)
+                .extension(
+                    "endpoints",
+                    serde_json::json!({ "default": { "url": url } }),
+                )
+                .build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolved::ResolvedGraphQLConfig;
+
+    #[test]
+    fn it_builds_usable_configurations() {
+        let single = ConfigTemplate::SingleProject.build().unwrap();
+        assert_eq!(single.root.schema_path, Some(SCHEMA_PATH.into()));
+        assert_eq!(
+            single.root.includes,
+            Some(vec!["src/**/*.graphql".parse().unwrap()])
+        );
+
+        let endpoint = ConfigTemplate::EndpointBased("http://localhost:4000/graphql".to_owned())
+            .build()
+            .unwrap();
+        assert_eq!(
+            ResolvedGraphQLConfig::new(endpoint, ".graphqlconfig").endpoint("default", "default"),
+            Some("http://localhost:4000/graphql".to_owned())
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_package_names() {
+        assert_eq!(
+            ConfigTemplate::Monorepo(vec!["web".to_owned(), "web".to_owned()]).build(),
+            Err(BuildError::DuplicateProject("web".to_owned()))
+        );
+        assert!(ConfigTemplate::Monorepo(vec!["my app".to_owned()])
+            .build()
+            .is_err());
+    }
+}
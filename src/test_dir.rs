@@ -0,0 +1,41 @@
+//! Temporary directories for the tests touching the file system.
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// An empty directory of its own for a test, named after it and the process
+/// so that tests and concurrent runs do not share one, and removed when
+/// dropped.
+pub(crate) struct TestDir(PathBuf);
+
+impl TestDir {
+    /// Creates the directory of the test `name`.
+    pub(crate) fn new(name: &str) -> TestDir {
+        let dir = ::std::env::temp_dir().join(format!("graphql-config-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TestDir(dir)
+    }
+}
+
+impl Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TestDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,166 @@
+//! Asserting what configurations parse into, for the tests of tools.
+//!
+//! `assert_graphql_config!` parses a configuration written inline, as JSON
+//! or YAML text or as a `serde_json::Value`, and compares it with the
+//! expected one. On failure, the message lists the changes of
+//! `GraphQLConfiguration::diff` rather than two `Debug` dumps:
+//!
+//! ```
+//! #[macro_use]
+//! extern crate graphql_config;
+//! # use graphql_config::*;
+//! # fn main() -> Result<(), builder::BuildError> {
+//! let expected = GraphQLConfiguration::builder()
+//!     .schema_path("./schema.graphql")
+//!     .include("./src/**/*.graphql")
+//!     .build()?;
+//!
+//! assert_graphql_config!(
+//!     r#"{ "schemaPath": "./schema.graphql", "includes": ["./src/**/*.graphql"] }"#,
+//!     expected
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A configuration with an unexpected include fails with:
+//!
+//! ```text
+//! configurations differ, from the expected one to the actual one:
+//! + root: include "./src/*.graphql"
+//! - root: include "./src/**/*.graphql"
+//! ```
+
+use std::convert::TryFrom;
+
+use serde_json::Value;
+
+use crate::GraphQLConfiguration;
+
+/// What `assert_graphql_config!` parses: configuration text, in the format
+/// `load::Format::sniff` detects, a JSON value, or a configuration.
+pub trait ConfigLiteral {
+    /// The configuration, or why it does not parse.
+    fn parse(self) -> Result<GraphQLConfiguration, String>;
+}
+
+impl ConfigLiteral for &str {
+    fn parse(self) -> Result<GraphQLConfiguration, String> {
+        GraphQLConfiguration::try_from(self).map_err(|error| error.to_string())
+    }
+}
+
+impl ConfigLiteral for &String {
+    fn parse(self) -> Result<GraphQLConfiguration, String> {
+        ConfigLiteral::parse(self.as_str())
+    }
+}
+
+impl ConfigLiteral for Value {
+    fn parse(self) -> Result<GraphQLConfiguration, String> {
+        serde_json::from_value(self).map_err(|error| error.to_string())
+    }
+}
+
+impl ConfigLiteral for GraphQLConfiguration {
+    fn parse(self) -> Result<GraphQLConfiguration, String> {
+        Ok(self)
+    }
+}
+
+impl ConfigLiteral for &GraphQLConfiguration {
+    fn parse(self) -> Result<GraphQLConfiguration, String> {
+        Ok(self.clone())
+    }
+}
+
+/// Checks that `actual` parses into `expected`, returning the message to
+/// fail with otherwise. `assert_graphql_config!` panics with it.
+pub fn check(actual: impl ConfigLiteral, expected: impl ConfigLiteral) -> Result<(), String> {
+    let actual = actual
+        .parse()
+        .map_err(|error| format!("the configuration does not parse: {}", error))?;
+    let expected = expected
+        .parse()
+        .map_err(|error| format!("the expected configuration does not parse: {}", error))?;
+    if actual == expected {
+        return Ok(());
+    }
+    let diff = expected.diff(&actual);
+    if diff.is_empty() {
+        // The diff compares patterns as sets, so only their order differs.
+        Err(format!(
+            "configurations differ in the order of their patterns:\n  expected: {:?}\n    actual: {:?}",
+            expected, actual
+        ))
+    } else {
+        Err(format!(
+            "configurations differ, from the expected one to the actual one:\n{}",
+            diff
+        ))
+    }
+}
+
+/// Asserts that a configuration parses into the expected one, see
+/// [`testing`](testing/index.html).
+///
+/// Both sides are anything `testing::ConfigLiteral` is implemented for. A
+/// message with format arguments may follow, as with `assert_eq!`.
+#[macro_export]
+macro_rules! assert_graphql_config {
+    ($actual:expr, $expected:expr $(,)*) => {
+        if let ::std::result::Result::Err(message) = $crate::testing::check($actual, $expected) {
+            panic!("{}", message);
+        }
+    };
+    ($actual:expr, $expected:expr, $($arg:tt)+) => {
+        if let ::std::result::Result::Err(message) = $crate::testing::check($actual, $expected) {
+            panic!("{}: {}", format_args!($($arg)+), message);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic;
+
+    #[test]
+    fn it_reports_the_differences() {
+        assert_graphql_config!(
+            json!({ "schemaPath": "./schema.graphql" }),
+            r#"{ "schemaPath": "./schema.graphql" }"#
+        );
+        #[cfg(feature = "yaml")]
+        assert_graphql_config!(
+            "schemaPath: ./schema.graphql\n",
+            json!({ "schemaPath": "./schema.graphql" })
+        );
+
+        let failure = panic::catch_unwind(|| {
+            assert_graphql_config!(
+                r#"{ "schemaPath": "./other.graphql", "projects": { "app": {} } }"#,
+                json!({ "schemaPath": "./schema.graphql" }),
+                "fixture {}",
+                1
+            )
+        })
+        .unwrap_err();
+        let message = failure.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            *message,
+            "fixture 1: configurations differ, from the expected one to the actual one:\n\
+             ~ root: schemaPath changed from \"./schema.graphql\" to \"./other.graphql\"\n\
+             + project `app` added\n"
+        );
+
+        let order = check(
+            json!({ "includes": ["a/*", "b/*"] }),
+            json!({ "includes": ["b/*", "a/*"] }),
+        );
+        assert!(order.unwrap_err().contains("order of their patterns"));
+        assert!(check("{", json!({}))
+            .unwrap_err()
+            .starts_with("the configuration does not parse"));
+    }
+}
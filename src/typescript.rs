@@ -0,0 +1,313 @@
+//! Rendering configurations as `graphql.config.ts` modules.
+
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use crate::map::{self, Map};
+use crate::v3::{self, Pointer, PointerEntry};
+use crate::GraphQLConfiguration;
+
+const INDENT: &str = "  ";
+const MAX_INLINE_WIDTH: usize = 80;
+
+impl GraphQLConfiguration {
+    /// Renders the configuration as a `graphql.config.ts` module typed with
+    /// `IGraphQLConfig` from the `graphql-config` npm package.
+    ///
+    /// The module uses the v3 field names (see the `v3` module for how legacy
+    /// fields are converted) and is formatted the way Prettier would format
+    /// it with its default settings and single quotes.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "./schema.graphql",
+    ///     "includes": ["./src/**/*.graphql"]
+    /// }))?;
+    ///
+    /// assert_eq!(
+    ///     config.to_typescript(),
+    ///     "import type { IGraphQLConfig } from 'graphql-config';
+    ///
+    /// const config: IGraphQLConfig = {
+    ///   schema: './schema.graphql',
+    ///   include: ['./src/**/*.graphql'],
+    /// };
+    ///
+    /// export default config;
+    /// "
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_typescript(&self) -> String {
+        let config = v3::GraphQLConfiguration::from(self);
+
+        let mut fields = project_fields(&config.root);
+        if let Some(ref projects) = config.projects {
+            let projects = map::ordered(projects)
+                .into_iter()
+                .map(|(name, project)| (name.to_string(), Literal::Object(project_fields(project))))
+                .collect();
+            fields.push(("projects".to_owned(), Literal::Object(projects)));
+        }
+
+        let mut output = String::from("import type { IGraphQLConfig } from 'graphql-config';\n\n");
+        output.push_str("const config: IGraphQLConfig = ");
+        Literal::Object(fields).render(&mut output, 0, "const config: IGraphQLConfig = ".len());
+        output.push_str(";\n\nexport default config;\n");
+        output
+    }
+}
+
+/// A TypeScript literal which keeps object keys in insertion order.
+enum Literal {
+    Scalar(String),
+    Array(Vec<Literal>),
+    Object(Vec<(String, Literal)>),
+}
+
+fn project_fields(project: &v3::GraphQLProjectConfiguration) -> Vec<(String, Literal)> {
+    let mut fields = Vec::new();
+
+    for &(key, pointer) in &[
+        ("schema", &project.schema),
+        ("documents", &project.documents),
+        ("include", &project.include),
+        ("exclude", &project.exclude),
+    ] {
+        match *pointer {
+            Some(Pointer::One(ref pointer)) => {
+                fields.push((key.to_owned(), Literal::string(pointer)))
+            }
+            Some(Pointer::Many(ref pointers)) => fields.push((
+                key.to_owned(),
+                Literal::Array(pointers.iter().map(|p| Literal::string(p)).collect()),
+            )),
+            Some(Pointer::WithOptions(ref pointers)) => {
+                fields.push((key.to_owned(), Literal::with_options(pointers)))
+            }
+            Some(Pointer::Mixed(ref entries)) => fields.push((
+                key.to_owned(),
+                Literal::Array(
+                    entries
+                        .iter()
+                        .map(|entry| match *entry {
+                            PointerEntry::Pointer(ref pointer) => Literal::string(pointer),
+                            PointerEntry::WithOptions(ref pointers) => {
+                                Literal::with_options(pointers)
+                            }
+                        })
+                        .collect(),
+                ),
+            )),
+            None => {}
+        }
+    }
+
+    if let Some(ref extensions) = project.extensions {
+        let extensions = map::ordered(extensions)
+            .into_iter()
+            .map(|(key, value)| (key.clone(), Literal::from_json(value)))
+            .collect();
+        fields.push(("extensions".to_owned(), Literal::Object(extensions)));
+    }
+
+    fields
+}
+
+impl Literal {
+    fn string(string: &str) -> Literal {
+        Literal::Scalar(render_string(string))
+    }
+
+    fn with_options(pointers: &Map<String, Value>) -> Literal {
+        Literal::Object(
+            map::ordered(pointers)
+                .into_iter()
+                .map(|(pointer, options)| (pointer.clone(), Literal::from_json(options)))
+                .collect(),
+        )
+    }
+
+    fn from_json(value: &Value) -> Literal {
+        match *value {
+            Value::String(ref string) => Literal::string(string),
+            Value::Array(ref items) => {
+                Literal::Array(items.iter().map(Literal::from_json).collect())
+            }
+            Value::Object(ref fields) => Literal::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Literal::from_json(value)))
+                    .collect(),
+            ),
+            ref scalar => Literal::Scalar(scalar.to_string()),
+        }
+    }
+
+    /// Writes the literal, breaking it over several lines if it does not fit
+    /// on the current one, which already holds `column` characters.
+    fn render(&self, output: &mut String, depth: usize, column: usize) {
+        if let Some(inline) = self.inline() {
+            if column + inline.len() < MAX_INLINE_WIDTH || self.is_empty() {
+                output.push_str(&inline);
+                return;
+            }
+        }
+
+        let indent = INDENT.repeat(depth + 1);
+        match *self {
+            Literal::Scalar(ref scalar) => output.push_str(scalar),
+            Literal::Array(ref items) => {
+                output.push_str("[\n");
+                for item in items {
+                    output.push_str(&indent);
+                    item.render(output, depth + 1, indent.len());
+                    output.push_str(",\n");
+                }
+                output.push_str(&INDENT.repeat(depth));
+                output.push(']');
+            }
+            Literal::Object(ref fields) => {
+                output.push_str("{\n");
+                for (key, value) in fields {
+                    let key = render_key(key);
+                    write!(output, "{}{}: ", indent, key).unwrap();
+                    value.render(output, depth + 1, indent.len() + key.len() + 2);
+                    output.push_str(",\n");
+                }
+                output.push_str(&INDENT.repeat(depth));
+                output.push('}');
+            }
+        }
+    }
+
+    /// The single-line rendering of the literal. Objects are only rendered
+    /// inline when empty, as Prettier keeps them expanded otherwise.
+    fn inline(&self) -> Option<String> {
+        match *self {
+            Literal::Scalar(ref scalar) => Some(scalar.clone()),
+            Literal::Array(ref items) => {
+                let items = items
+                    .iter()
+                    .map(Literal::inline)
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("[{}]", items.join(", ")))
+            }
+            Literal::Object(ref fields) if fields.is_empty() => Some("{}".to_owned()),
+            Literal::Object(_) => None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match *self {
+            Literal::Scalar(_) => false,
+            Literal::Array(ref items) => items.is_empty(),
+            Literal::Object(ref fields) => fields.is_empty(),
+        }
+    }
+}
+
+fn render_key(key: &str) -> String {
+    let mut characters = key.chars();
+    let is_identifier = characters
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && characters.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    if is_identifier {
+        key.to_owned()
+    } else {
+        render_string(key)
+    }
+}
+
+fn render_string(string: &str) -> String {
+    let mut rendered = String::with_capacity(string.len() + 2);
+    rendered.push('\'');
+    for character in string.chars() {
+        match character {
+            '\'' => rendered.push_str("\\'"),
+            '\\' => rendered.push_str("\\\\"),
+            '\n' => rendered.push_str("\\n"),
+            '\r' => rendered.push_str("\\r"),
+            '\t' => rendered.push_str("\\t"),
+            c if c.is_control() => write!(rendered, "\\u{{{:x}}}", c as u32).unwrap(),
+            c => rendered.push(c),
+        }
+    }
+    rendered.push('\'');
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_projects_and_extensions() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": {
+                    "schemaPath": "./app.graphql",
+                    "includes": [
+                        "./packages/app/src/components/**/*.graphql",
+                        "./packages/app/src/pages/**/*.graphql"
+                    ],
+                    "extensions": {
+                        "endpoints": { "dev": "http://localhost:4000" },
+                        "with-dash": [1, true, null],
+                        "empty": {}
+                    }
+                },
+                "my-lib": { "schemaPath": "./it's.graphql" }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.to_typescript(),
+            "import type { IGraphQLConfig } from 'graphql-config';
+
+const config: IGraphQLConfig = {
+  projects: {
+    app: {
+      schema: './app.graphql',
+      include: [
+        './packages/app/src/components/**/*.graphql',
+        './packages/app/src/pages/**/*.graphql',
+      ],
+      extensions: {
+        empty: {},
+        endpoints: {
+          dev: 'http://localhost:4000',
+        },
+        'with-dash': [1, true, null],
+      },
+    },
+    'my-lib': {
+      schema: './it\\'s.graphql',
+    },
+  },
+};
+
+export default config;
+"
+        );
+    }
+
+    #[test]
+    fn it_renders_empty_configurations() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({})).unwrap();
+
+        assert!(config
+            .to_typescript()
+            .contains("const config: IGraphQLConfig = {};\n"));
+    }
+}
@@ -0,0 +1,908 @@
+//! Types for configurations following [version 3 of the graphql-config
+//! specification](https://the-guild.dev/graphql/config/docs/user/schema),
+//! which renamed `schemaPath` to `schema` and `includes`/`excludes` to
+//! `include`/`exclude`, and added `documents`.
+//!
+//! A legacy configuration can be converted with `From`. Project `name` fields
+//! have no v3 equivalent and are dropped; projects keep their keys. The rest
+//! of the crate works with legacy configurations, which v3 ones are converted
+//! into with `TryFrom` when they are loaded, see `VersionedConfiguration`.
+//!
+//! Tools reading configurations of either version deserialize a
+//! `VersionedConfiguration`, which tells which one was read:
+//!
+//! ```
+//! # extern crate graphql_config;
+//! # #[macro_use]
+//! # extern crate serde_json;
+//! # use graphql_config::v3::{SpecVersion, VersionedConfiguration};
+//! # fn main() -> Result<(), serde_json::Error> {
+//! let config: VersionedConfiguration = serde_json::from_value(json!({
+//!     "schema": "./schema.graphql",
+//!     "documents": "./src/**/*.graphql"
+//! }))?;
+//! assert_eq!(config.version(), SpecVersion::V3);
+//!
+//! let legacy: VersionedConfiguration =
+//!     serde_json::from_value(json!({ "schemaPath": "./schema.graphql" }))?;
+//! assert_eq!(legacy.version(), SpecVersion::V2);
+//! assert_eq!(legacy.into_v3().root.schema, config.into_v3().root.schema);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, Error as _, IgnoredAny, MapAccess, Visitor,
+};
+use serde_json::Value;
+
+use crate::glob::{self, GlobPattern, PatternError};
+use crate::map::{self, Map};
+use crate::projects::ProjectName;
+
+/// The v3 counterpart of `graphql_config::GraphQLConfiguration`.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct GraphQLConfiguration {
+    /// Top-level configuration goes into `root`.
+    #[serde(flatten)]
+    pub root: GraphQLProjectConfiguration,
+    /// A `Map` of project names as strings to `GraphQLProjectConfiguration`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Map<ProjectName, GraphQLProjectConfiguration>>,
+}
+
+/// The v3 counterpart of `graphql_config::GraphQLProjectConfiguration`.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct GraphQLProjectConfiguration {
+    /// Where to load the schema from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Pointer>,
+    /// The operations and fragments of the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documents: Option<Pointer>,
+    /// Files which belong to the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Pointer>,
+    /// Files which do not belong to the project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Pointer>,
+    /// Reserved namespace for tool-specific configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Map<String, Value>>,
+}
+
+/// v3 fields accept either a single pointer or a list of them. Pointers may
+/// come with options, such as the headers to introspect an endpoint with,
+/// written as an object from each pointer to its options.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum Pointer {
+    /// A single path, glob or URL.
+    One(String),
+    /// A list of paths, globs or URLs.
+    Many(Vec<String>),
+    /// Paths, globs or URLs with their options, by pointer.
+    WithOptions(Map<String, Value>),
+    /// A list mixing pointers with and without options.
+    Mixed(Vec<PointerEntry>),
+}
+
+/// An entry of a `Pointer::Mixed` list.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(untagged)]
+pub enum PointerEntry {
+    /// A path, glob or URL.
+    Pointer(String),
+    /// Paths, globs or URLs with their options, by pointer.
+    WithOptions(Map<String, Value>),
+}
+
+/// A version of the graphql-config specification.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SpecVersion {
+    /// Version 2, of `graphql_config::GraphQLConfiguration`.
+    V2,
+    /// Versions 3 and later, of `GraphQLConfiguration`.
+    V3,
+}
+
+/// The fields only version 2 has, and those only versions 3 and later have.
+const V2_FIELDS: &[&str] = &["name", "schemaPath", "includes", "excludes"];
+const V3_FIELDS: &[&str] = &["schema", "documents", "include", "exclude"];
+
+impl SpecVersion {
+    /// The version `document` follows, told from the fields of its
+    /// top-level configuration and projects. `None` when it has the fields
+    /// of neither, such as a document with extensions only, which reads the
+    /// same in both.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # use graphql_config::v3::SpecVersion;
+    /// # fn main() {
+    /// let modern = json!({ "projects": { "app": { "schema": "./schema.graphql" } } });
+    /// assert_eq!(SpecVersion::detect(&modern), Some(SpecVersion::V3));
+    /// assert_eq!(SpecVersion::detect(&json!({ "extensions": {} })), None);
+    /// # }
+    /// ```
+    pub fn detect(document: &Value) -> Option<SpecVersion> {
+        let projects = document
+            .get("projects")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|projects| projects.values());
+        let configs: Vec<&Value> = Some(document).into_iter().chain(projects).collect();
+        let has_any = |fields: &[&str]| {
+            configs
+                .iter()
+                .any(|config| fields.iter().any(|field| config.get(field).is_some()))
+        };
+        if has_any(V3_FIELDS) {
+            Some(SpecVersion::V3)
+        } else if has_any(V2_FIELDS) {
+            Some(SpecVersion::V2)
+        } else {
+            None
+        }
+    }
+
+    /// The version a configuration file named `name` is expected to
+    /// follow: `.graphqlconfig` files follow version 2, and `.graphqlrc` and
+    /// `graphql.config` files later versions.
+    pub fn of_file_name(name: &str) -> Option<SpecVersion> {
+        let stem: Vec<&str> = name.split('.').take(2).collect();
+        match stem.join(".").as_str() {
+            ".graphqlconfig" => Some(SpecVersion::V2),
+            ".graphqlrc" | "graphql.config" => Some(SpecVersion::V3),
+            _ => None,
+        }
+    }
+}
+
+/// The version of a document as `SpecVersion::detect` tells it, read from
+/// the keys of its top-level configuration and projects alone: every value
+/// but `projects` is skipped rather than built, so that loaders can detect
+/// the version before deserializing the document in it.
+pub(crate) struct DetectedVersion(pub(crate) Option<SpecVersion>);
+
+/// Whether the keys read so far include any of `V2_FIELDS` and of
+/// `V3_FIELDS`.
+#[derive(Default)]
+struct Seen {
+    v2: bool,
+    v3: bool,
+}
+
+/// A key of a configuration, as far as detecting its version goes.
+enum Key {
+    Field(SpecVersion),
+    Projects,
+    Other,
+}
+
+/// Reads the keys of a configuration, and of its projects if `top_level`.
+struct ConfigKeys<'a> {
+    seen: &'a mut Seen,
+    top_level: bool,
+}
+
+/// Reads the keys of the projects of a configuration, if there are any.
+struct ProjectsKeys<'a>(&'a mut Seen);
+
+impl<'de> Deserialize<'de> for DetectedVersion {
+    fn deserialize<D>(deserializer: D) -> Result<DetectedVersion, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut seen = Seen::default();
+        ConfigKeys {
+            seen: &mut seen,
+            top_level: true,
+        }
+        .deserialize(deserializer)?;
+        Ok(DetectedVersion(if seen.v3 {
+            Some(SpecVersion::V3)
+        } else if seen.v2 {
+            Some(SpecVersion::V2)
+        } else {
+            None
+        }))
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Key, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct KeyVisitor;
+
+        impl<'de> Visitor<'de> for KeyVisitor {
+            type Value = Key;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a key")
+            }
+
+            fn visit_str<E>(self, key: &str) -> Result<Key, E> {
+                Ok(if key == "projects" {
+                    Key::Projects
+                } else if V3_FIELDS.contains(&key) {
+                    Key::Field(SpecVersion::V3)
+                } else if V2_FIELDS.contains(&key) {
+                    Key::Field(SpecVersion::V2)
+                } else {
+                    Key::Other
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(KeyVisitor)
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ConfigKeys<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ConfigKeys<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a configuration")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<(), A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        while let Some(key) = map.next_key()? {
+            match key {
+                Key::Field(SpecVersion::V2) => self.seen.v2 = true,
+                Key::Field(SpecVersion::V3) => self.seen.v3 = true,
+                Key::Projects if self.top_level => {
+                    map.next_value_seed(ProjectsKeys(&mut *self.seen))?;
+                    continue;
+                }
+                Key::Projects | Key::Other => {}
+            }
+            map.next_value::<IgnoredAny>()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for ProjectsKeys<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ProjectsKeys<'a> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("projects")
+    }
+
+    fn visit_none<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<(), A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        while map.next_key::<IgnoredAny>()?.is_some() {
+            map.next_value_seed(ConfigKeys {
+                seen: &mut *self.0,
+                top_level: false,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A configuration of either version of the specification.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum VersionedConfiguration {
+    /// A configuration following version 2.
+    V2(crate::GraphQLConfiguration),
+    /// A configuration following version 3 or later.
+    V3(GraphQLConfiguration),
+}
+
+impl VersionedConfiguration {
+    /// The version the configuration follows.
+    pub fn version(&self) -> SpecVersion {
+        match *self {
+            VersionedConfiguration::V2(_) => SpecVersion::V2,
+            VersionedConfiguration::V3(_) => SpecVersion::V3,
+        }
+    }
+
+    /// The configuration in the shape of version 2, which the rest of the
+    /// crate works with, converting modern ones with `TryFrom`.
+    pub fn into_v2(self) -> Result<crate::GraphQLConfiguration, ConversionError> {
+        match self {
+            VersionedConfiguration::V2(config) => Ok(config),
+            VersionedConfiguration::V3(config) => crate::GraphQLConfiguration::try_from(&config),
+        }
+    }
+
+    /// The configuration in the shape of version 3, converting legacy ones
+    /// with `From`.
+    pub fn into_v3(self) -> GraphQLConfiguration {
+        match self {
+            VersionedConfiguration::V2(config) => GraphQLConfiguration::from(&config),
+            VersionedConfiguration::V3(config) => config,
+        }
+    }
+
+    /// Deserializes `document` in the version `SpecVersion::detect`
+    /// detects, and in `fallback` if it could be either.
+    pub(crate) fn from_document(
+        document: Value,
+        fallback: SpecVersion,
+    ) -> Result<VersionedConfiguration, serde_json::Error> {
+        match SpecVersion::detect(&document).unwrap_or(fallback) {
+            SpecVersion::V2 => serde_json::from_value(document).map(VersionedConfiguration::V2),
+            SpecVersion::V3 => serde_json::from_value(document).map(VersionedConfiguration::V3),
+        }
+    }
+}
+
+/// Deserializes a document in the version `SpecVersion::detect` detects, and
+/// as version 2 if it could be either.
+impl<'de> Deserialize<'de> for VersionedConfiguration {
+    fn deserialize<D>(deserializer: D) -> Result<VersionedConfiguration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let document = Value::deserialize(deserializer)?;
+        VersionedConfiguration::from_document(document, SpecVersion::V2).map_err(D::Error::custom)
+    }
+}
+
+impl GraphQLConfiguration {
+    /// An empty configuration, without projects.
+    pub fn new() -> GraphQLConfiguration {
+        GraphQLConfiguration::default()
+    }
+}
+
+impl GraphQLProjectConfiguration {
+    /// An empty project configuration.
+    pub fn new() -> GraphQLProjectConfiguration {
+        GraphQLProjectConfiguration::default()
+    }
+
+    /// The `documents` patterns, whichever form they were written in.
+    pub fn documents(&self) -> Vec<&str> {
+        self.documents
+            .as_ref()
+            .map_or_else(Vec::new, Pointer::pointers)
+    }
+
+    /// Sets the `documents` patterns, written as a single pointer when there
+    /// is one and removed when there are none. Fails, changing nothing, if
+    /// one of them is malformed.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::v3::{GraphQLProjectConfiguration, Pointer};
+    /// # fn main() -> Result<(), graphql_config::glob::PatternError> {
+    /// let mut project = GraphQLProjectConfiguration::new();
+    ///
+    /// project.set_documents(vec!["./src/**/*.graphql"])?;
+    /// assert_eq!(project.documents, Some(Pointer::One("./src/**/*.graphql".to_owned())));
+    /// assert_eq!(project.documents(), ["./src/**/*.graphql"]);
+    ///
+    /// assert!(project.set_documents(vec!["./src/{a,b"]).is_err());
+    /// assert_eq!(project.documents(), ["./src/**/*.graphql"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_documents<I>(&mut self, patterns: I) -> Result<(), PatternError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let mut patterns: Vec<String> = patterns.into_iter().map(Into::into).collect();
+        for pattern in &patterns {
+            glob::validate(pattern)?;
+        }
+
+        self.documents = match patterns.len() {
+            0 => None,
+            1 => patterns.pop().map(Pointer::One),
+            _ => Some(Pointer::Many(patterns)),
+        };
+        Ok(())
+    }
+}
+
+impl Pointer {
+    /// The paths, globs or URLs, whichever form they were written in.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # use graphql_config::v3::Pointer;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let schema: Pointer = serde_json::from_value(json!([
+    ///     "./schema.graphql",
+    ///     { "https://example.com/graphql": { "headers": { "Authorization": "Bearer token" } } }
+    /// ]))?;
+    ///
+    /// assert_eq!(schema.pointers(), ["./schema.graphql", "https://example.com/graphql"]);
+    /// assert_eq!(
+    ///     schema.options("https://example.com/graphql"),
+    ///     Some(&json!({ "headers": { "Authorization": "Bearer token" } }))
+    /// );
+    /// assert_eq!(schema.options("./schema.graphql"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pointers(&self) -> Vec<&str> {
+        self.entries().map(|(pointer, _)| pointer).collect()
+    }
+
+    /// The options of `pointer`, if it has any.
+    pub fn options(&self, pointer: &str) -> Option<&Value> {
+        self.entries()
+            .find(|&(entry, _)| entry == pointer)
+            .and_then(|(_, options)| options)
+    }
+
+    /// The pointers with their options, in the order they were written in.
+    fn entries(&self) -> Box<dyn Iterator<Item = (&str, Option<&Value>)> + '_> {
+        match *self {
+            Pointer::One(ref pointer) => Box::new(Some((pointer.as_str(), None)).into_iter()),
+            Pointer::Many(ref pointers) => {
+                Box::new(pointers.iter().map(|pointer| (pointer.as_str(), None)))
+            }
+            Pointer::WithOptions(ref pointers) => Box::new(with_options(pointers)),
+            Pointer::Mixed(ref entries) => {
+                Box::new(entries.iter().flat_map(|entry| match *entry {
+                    PointerEntry::Pointer(ref pointer) => {
+                        Box::new(Some((pointer.as_str(), None)).into_iter())
+                            as Box<dyn Iterator<Item = _>>
+                    }
+                    PointerEntry::WithOptions(ref pointers) => Box::new(with_options(pointers)),
+                }))
+            }
+        }
+    }
+}
+
+fn with_options(pointers: &Map<String, Value>) -> impl Iterator<Item = (&str, Option<&Value>)> {
+    map::ordered(pointers)
+        .into_iter()
+        .map(|(pointer, options)| (pointer.as_str(), Some(options)))
+}
+
+impl Hash for Pointer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Pointer::One(ref pointer) => (0u8, pointer).hash(state),
+            Pointer::Many(ref pointers) => (1u8, pointers).hash(state),
+            Pointer::WithOptions(ref pointers) => {
+                state.write_u8(2);
+                map::hash(Some(pointers), state);
+            }
+            Pointer::Mixed(ref entries) => (3u8, entries).hash(state),
+        }
+    }
+}
+
+impl Hash for PointerEntry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            PointerEntry::Pointer(ref pointer) => (0u8, pointer).hash(state),
+            PointerEntry::WithOptions(ref pointers) => {
+                state.write_u8(1);
+                map::hash(Some(pointers), state);
+            }
+        }
+    }
+}
+
+impl Hash for GraphQLConfiguration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.root.hash(state);
+        map::hash(self.projects.as_ref(), state);
+    }
+}
+
+impl Hash for GraphQLProjectConfiguration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.schema.hash(state);
+        self.documents.hash(state);
+        self.include.hash(state);
+        self.exclude.hash(state);
+        map::hash(self.extensions.as_ref(), state);
+    }
+}
+
+impl<'a> From<&'a crate::GraphQLConfiguration> for GraphQLConfiguration {
+    fn from(config: &'a crate::GraphQLConfiguration) -> GraphQLConfiguration {
+        GraphQLConfiguration {
+            root: (&config.root).into(),
+            projects: config.projects.as_ref().map(|projects| {
+                projects
+                    .iter()
+                    .map(|(name, project)| (name.clone(), project.into()))
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl<'a> From<&'a crate::GraphQLProjectConfiguration> for GraphQLProjectConfiguration {
+    fn from(project: &'a crate::GraphQLProjectConfiguration) -> GraphQLProjectConfiguration {
+        GraphQLProjectConfiguration {
+            schema: project
+                .schema_path
+                .as_ref()
+                .map(|path| Pointer::One(path.to_string_lossy().into_owned())),
+            documents: None,
+            include: project.includes.as_deref().map(patterns),
+            exclude: project.excludes.as_deref().map(patterns),
+            extensions: project.extensions.clone(),
+        }
+    }
+}
+
+/// The error returned for configurations which cannot be converted to
+/// version 2.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ConversionError {
+    /// The project, or the top-level configuration for `None`, has several
+    /// schemas, where `schemaPath` holds one.
+    SeveralSchemas(Option<ProjectName>),
+    /// A pointer of the project, or of the top-level configuration for
+    /// `None`, has options, which version 2 configurations cannot hold.
+    PointerOptions(Option<ProjectName>, String),
+    /// A pattern of `documents`, `include` or `exclude` of the project, or of
+    /// the top-level configuration for `None`, is malformed.
+    Pattern(Option<ProjectName>, PatternError),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |project: &Option<ProjectName>| match *project {
+            Some(ref name) => format!("project `{}`", name),
+            None => "the top-level configuration".to_owned(),
+        };
+        match *self {
+            ConversionError::SeveralSchemas(ref project) => write!(
+                f,
+                "{} has several schemas, which version 2 configurations cannot hold",
+                describe(project)
+            ),
+            ConversionError::PointerOptions(ref project, ref pointer) => write!(
+                f,
+                "{} has options for `{}`, which version 2 configurations cannot hold",
+                describe(project),
+                pointer
+            ),
+            ConversionError::Pattern(ref project, ref error) => {
+                write!(f, "{}: {}", describe(project), error)
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            ConversionError::Pattern(_, ref error) => Some(error),
+            ConversionError::SeveralSchemas(_) | ConversionError::PointerOptions(..) => None,
+        }
+    }
+}
+
+/// Converts a configuration to version 2: `schema` becomes `schemaPath`, and
+/// `documents` and `include` together become `includes`, as both decide
+/// which files belong to a project.
+impl<'a> TryFrom<&'a GraphQLConfiguration> for crate::GraphQLConfiguration {
+    type Error = ConversionError;
+
+    fn try_from(config: &'a GraphQLConfiguration) -> Result<Self, ConversionError> {
+        let mut converted = crate::GraphQLConfiguration::new();
+        converted.root = legacy_project(None, &config.root)?;
+        if let Some(ref projects) = config.projects {
+            let projects = projects
+                .iter()
+                .map(|(name, project)| Ok((name.clone(), legacy_project(Some(name), project)?)))
+                .collect::<Result<_, ConversionError>>()?;
+            converted.projects = Some(projects);
+        }
+        Ok(converted)
+    }
+}
+
+fn legacy_project(
+    name: Option<&ProjectName>,
+    project: &GraphQLProjectConfiguration,
+) -> Result<crate::GraphQLProjectConfiguration, ConversionError> {
+    let schema = match project.schema {
+        Some(ref schema) => without_options(name, schema)?,
+        None => Vec::new(),
+    };
+    let schema_path = match schema[..] {
+        [] => None,
+        [schema] => Some(schema.into()),
+        _ => return Err(ConversionError::SeveralSchemas(name.cloned())),
+    };
+    let patterns = |pointers: &[&Option<Pointer>]| {
+        if pointers.iter().all(|pointer| pointer.is_none()) {
+            return Ok(None);
+        }
+        let mut patterns = Vec::new();
+        for pointer in pointers.iter().filter_map(|pointer| pointer.as_ref()) {
+            for pattern in without_options(name, pointer)? {
+                let pattern = GlobPattern::new(pattern)
+                    .map_err(|error| ConversionError::Pattern(name.cloned(), error))?;
+                patterns.push(pattern);
+            }
+        }
+        Ok(Some(patterns))
+    };
+
+    Ok(crate::GraphQLProjectConfiguration {
+        name: None,
+        schema_path,
+        includes: patterns(&[&project.documents, &project.include])?,
+        excludes: patterns(&[&project.exclude])?,
+        extensions: project.extensions.clone(),
+    })
+}
+
+/// The pointers of `pointer`, failing if one of them has options.
+fn without_options<'a>(
+    name: Option<&ProjectName>,
+    pointer: &'a Pointer,
+) -> Result<Vec<&'a str>, ConversionError> {
+    let entries: Vec<(&str, Option<&Value>)> = pointer.entries().collect();
+    match entries.iter().find(|&&(_, options)| options.is_some()) {
+        Some(&(with_options, _)) => Err(ConversionError::PointerOptions(
+            name.cloned(),
+            with_options.to_owned(),
+        )),
+        None => Ok(entries.into_iter().map(|(pointer, _)| pointer).collect()),
+    }
+}
+
+fn patterns(patterns: &[GlobPattern]) -> Pointer {
+    Pointer::Many(patterns.iter().map(GlobPattern::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_legacy_configurations() {
+        let legacy: crate::GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "./schema.graphql",
+            "includes": ["./src/*.graphql"],
+            "projects": {
+                "app": { "name": "App", "schemaPath": "./app.graphql", "excludes": ["./gen/*"] }
+            }
+        }))
+        .unwrap();
+
+        let converted = ::serde_json::to_value(GraphQLConfiguration::from(&legacy)).unwrap();
+
+        assert_eq!(
+            converted,
+            json!({
+                "schema": "./schema.graphql",
+                "include": ["./src/*.graphql"],
+                "projects": {
+                    "app": { "schema": "./app.graphql", "exclude": ["./gen/*"] }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn it_converts_to_legacy_configurations() {
+        let modern: VersionedConfiguration = ::serde_json::from_value(json!({
+            "schema": "./schema.graphql",
+            "documents": "./src/**/*.graphql",
+            "include": ["./lib/*.graphql"],
+            "projects": {
+                "app": { "schema": ["./app.graphql"], "exclude": "./gen/*" }
+            }
+        }))
+        .unwrap();
+
+        let expected: crate::GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "./schema.graphql",
+            "includes": ["./src/**/*.graphql", "./lib/*.graphql"],
+            "projects": {
+                "app": { "schemaPath": "./app.graphql", "excludes": ["./gen/*"] }
+            }
+        }))
+        .unwrap();
+        assert_eq!(modern.into_v2().unwrap(), expected);
+
+        let several: VersionedConfiguration = ::serde_json::from_value(json!({
+            "projects": { "app": { "schema": ["./a.graphql", "./b.graphql"] } }
+        }))
+        .unwrap();
+        let app = ProjectName::new("app").unwrap();
+        assert_eq!(
+            several.into_v2(),
+            Err(ConversionError::SeveralSchemas(Some(app)))
+        );
+        let malformed: VersionedConfiguration =
+            ::serde_json::from_value(json!({ "documents": "./src/{a,b" })).unwrap();
+        match malformed.into_v2() {
+            Err(ConversionError::Pattern(None, _)) => {}
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn it_deserializes_single_and_multiple_pointers() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schema": ["./a.graphql", "./b.graphql"],
+            "documents": "./src/**/*.graphql"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.root.schema,
+            Some(Pointer::Many(vec![
+                "./a.graphql".to_owned(),
+                "./b.graphql".to_owned()
+            ]))
+        );
+        assert_eq!(
+            config.root.documents,
+            Some(Pointer::One("./src/**/*.graphql".to_owned()))
+        );
+    }
+
+    #[test]
+    fn it_deserializes_pointers_with_options() {
+        let headers = json!({ "headers": { "Authorization": "Bearer token" } });
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schema": { "https://example.com/graphql": headers },
+            "projects": {
+                "app": {
+                    "documents": ["./src/*.graphql", { "./src/*.ts": { "pluck": true } }]
+                }
+            }
+        }))
+        .unwrap();
+
+        let schema = config.root.schema.as_ref().unwrap();
+        assert_eq!(schema.pointers(), ["https://example.com/graphql"]);
+        assert_eq!(
+            schema.options("https://example.com/graphql"),
+            Some(&headers)
+        );
+        let app = &config.projects.as_ref().unwrap()["app"];
+        assert_eq!(app.documents(), ["./src/*.graphql", "./src/*.ts"]);
+        assert_eq!(
+            ::serde_json::to_value(&config).unwrap()["projects"]["app"]["documents"][1],
+            json!({ "./src/*.ts": { "pluck": true } })
+        );
+
+        assert_eq!(
+            VersionedConfiguration::V3(config).into_v2(),
+            Err(ConversionError::PointerOptions(
+                None,
+                "https://example.com/graphql".to_owned()
+            ))
+        );
+    }
+
+    #[test]
+    fn it_normalizes_documents_on_write() {
+        let mut project = GraphQLProjectConfiguration::new();
+
+        project
+            .set_documents(vec!["a.graphql", "b.graphql"])
+            .unwrap();
+        assert_eq!(
+            project.documents,
+            Some(Pointer::Many(vec![
+                "a.graphql".to_owned(),
+                "b.graphql".to_owned()
+            ]))
+        );
+
+        project.set_documents(Vec::<String>::new()).unwrap();
+        assert_eq!(project.documents, None);
+        assert!(project.documents().is_empty());
+    }
+
+    #[test]
+    fn it_tells_the_versions_apart() {
+        let legacy = VersionedConfiguration::try_from(r#"{ "schemaPath": "./a.graphql" }"#);
+        assert_eq!(legacy.unwrap().version(), SpecVersion::V2);
+        let modern = r#"{ "projects": { "app": { "documents": ["./src/*.graphql"] } } }"#;
+        let modern = VersionedConfiguration::try_from(modern).unwrap();
+        assert_eq!(modern.version(), SpecVersion::V3);
+        assert_eq!(
+            modern.into_v3().projects.unwrap()["app"].documents(),
+            ["./src/*.graphql"]
+        );
+
+        assert_eq!(
+            SpecVersion::of_file_name(".graphqlconfig.yml"),
+            Some(SpecVersion::V2)
+        );
+        assert_eq!(
+            SpecVersion::of_file_name(".graphqlrc"),
+            Some(SpecVersion::V3)
+        );
+        assert_eq!(
+            SpecVersion::of_file_name("graphql.config.json"),
+            Some(SpecVersion::V3)
+        );
+        assert_eq!(SpecVersion::of_file_name("schema.graphql"), None);
+
+        #[cfg(feature = "fs")]
+        {
+            let dir = crate::test_dir::TestDir::new("it_tells_the_versions_apart");
+            let path = dir.join(".graphqlrc.json");
+            ::std::fs::write(&path, r#"{ "extensions": { "a": 1 } }"#).unwrap();
+            let config = VersionedConfiguration::try_from(path.as_path()).unwrap();
+            assert_eq!(config.version(), SpecVersion::V3);
+        }
+    }
+
+    #[test]
+    fn it_detects_versions_from_keys_alone() {
+        for document in [
+            json!({ "schemaPath": "./a.graphql", "projects": null }),
+            json!({ "extensions": { "schema": 1 }, "projects": { "app": { "include": [] } } }),
+            json!({ "schema": "./a.graphql", "schemaPath": "./a.graphql" }),
+            json!({ "projects": { "app": { "projects": { "lib": { "schema": "" } } } } }),
+            json!({ "extensions": {} }),
+        ] {
+            let detected: DetectedVersion = ::serde_json::from_str(&document.to_string()).unwrap();
+            assert_eq!(detected.0, SpecVersion::detect(&document), "{}", document);
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Walking the configuration tree.
+//!
+//! `GraphQLConfiguration::visit` calls a `ConfigVisitor` for the top-level
+//! configuration and then every project, and within each of them for the
+//! schema path, every pattern and every extension, so that linters and
+//! collectors need not repeat the traversal. `visit_mut` does the same with a
+//! `ConfigVisitorMut`, for rewriters.
+//!
+//! Projects are visited after the top-level configuration, in the order
+//! `GraphQLConfiguration::iter_projects` lists them. Every method receives
+//! the key of the project being walked, `None` for the top-level
+//! configuration, and does nothing by default.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::glob::GlobPattern;
+use crate::map;
+use crate::normalize::PatternList;
+use crate::{GraphQLConfiguration, GraphQLProjectConfiguration};
+
+/// Visits the parts of a configuration, see `GraphQLConfiguration::visit`.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # extern crate graphql_config;
+/// # use graphql_config::*;
+/// # use graphql_config::glob::GlobPattern;
+/// # use graphql_config::normalize::PatternList;
+/// # use graphql_config::visit::ConfigVisitor;
+/// struct Patterns(Vec<String>);
+///
+/// impl ConfigVisitor for Patterns {
+///     fn visit_pattern(&mut self, project: Option<&str>, _: PatternList, pattern: &GlobPattern) {
+///         self.0.push(format!("{}: {}", project.unwrap_or("root"), pattern));
+///     }
+/// }
+///
+/// # fn main() -> Result<(), serde_json::Error> {
+/// let config: GraphQLConfiguration = serde_json::from_value(json!({
+///     "includes": ["./src/*.graphql"],
+///     "projects": { "app": { "excludes": ["./app/gen/*"] } }
+/// }))?;
+///
+/// let mut patterns = Patterns(Vec::new());
+/// config.visit(&mut patterns);
+/// assert_eq!(patterns.0, vec!["root: ./src/*.graphql", "app: ./app/gen/*"]);
+/// # Ok(())
+/// # }
+/// ```
+pub trait ConfigVisitor {
+    /// Called for a project before its parts.
+    fn visit_project(&mut self, _project: Option<&str>, _config: &GraphQLProjectConfiguration) {}
+
+    /// Called for the `schemaPath` of a project.
+    fn visit_schema_path(&mut self, _project: Option<&str>, _schema_path: &Path) {}
+
+    /// Called for every pattern of `includes` and `excludes`.
+    fn visit_pattern(
+        &mut self,
+        _project: Option<&str>,
+        _list: PatternList,
+        _pattern: &GlobPattern,
+    ) {
+    }
+
+    /// Called for every entry of `extensions`.
+    fn visit_extension(&mut self, _project: Option<&str>, _name: &str, _value: &Value) {}
+}
+
+/// Visits and possibly rewrites the parts of a configuration, see
+/// `GraphQLConfiguration::visit_mut`.
+pub trait ConfigVisitorMut {
+    /// Called for a project before its parts.
+    fn visit_project(&mut self, _project: Option<&str>, _config: &mut GraphQLProjectConfiguration) {
+    }
+
+    /// Called for the `schemaPath` of a project.
+    fn visit_schema_path(&mut self, _project: Option<&str>, _schema_path: &mut PathBuf) {}
+
+    /// Called for every pattern of `includes` and `excludes`.
+    fn visit_pattern(
+        &mut self,
+        _project: Option<&str>,
+        _list: PatternList,
+        _pattern: &mut GlobPattern,
+    ) {
+    }
+
+    /// Called for every entry of `extensions`.
+    fn visit_extension(&mut self, _project: Option<&str>, _name: &str, _value: &mut Value) {}
+}
+
+impl GraphQLConfiguration {
+    /// Walks the configuration with `visitor`.
+    pub fn visit(&self, visitor: &mut impl ConfigVisitor) {
+        walk(visitor, None, &self.root);
+        for (key, project) in self.projects.iter().flat_map(map::ordered) {
+            walk(visitor, Some(key.as_str()), project);
+        }
+    }
+
+    /// Walks the configuration with `visitor`, which may change what it
+    /// visits.
+    pub fn visit_mut(&mut self, visitor: &mut impl ConfigVisitorMut) {
+        walk_mut(visitor, None, &mut self.root);
+        for (key, project) in self.projects.iter_mut().flat_map(map::ordered_mut) {
+            walk_mut(visitor, Some(key.as_str()), project);
+        }
+    }
+}
+
+fn walk(
+    visitor: &mut impl ConfigVisitor,
+    key: Option<&str>,
+    project: &GraphQLProjectConfiguration,
+) {
+    visitor.visit_project(key, project);
+    if let Some(ref schema_path) = project.schema_path {
+        visitor.visit_schema_path(key, schema_path);
+    }
+    for &(list, patterns) in &[
+        (PatternList::Includes, &project.includes),
+        (PatternList::Excludes, &project.excludes),
+    ] {
+        for pattern in patterns.iter().flatten() {
+            visitor.visit_pattern(key, list, pattern);
+        }
+    }
+    for (name, value) in project.extensions.iter().flat_map(map::ordered) {
+        visitor.visit_extension(key, name, value);
+    }
+}
+
+fn walk_mut(
+    visitor: &mut impl ConfigVisitorMut,
+    key: Option<&str>,
+    project: &mut GraphQLProjectConfiguration,
+) {
+    visitor.visit_project(key, project);
+    if let Some(ref mut schema_path) = project.schema_path {
+        visitor.visit_schema_path(key, schema_path);
+    }
+    for &mut (list, ref mut patterns) in &mut [
+        (PatternList::Includes, &mut project.includes),
+        (PatternList::Excludes, &mut project.excludes),
+    ] {
+        for pattern in patterns.iter_mut().flatten() {
+            visitor.visit_pattern(key, list, pattern);
+        }
+    }
+    for (name, value) in project.extensions.iter_mut().flat_map(map::ordered_mut) {
+        visitor.visit_extension(key, name, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Prefix;
+
+    impl ConfigVisitorMut for Prefix {
+        fn visit_schema_path(&mut self, _: Option<&str>, schema_path: &mut PathBuf) {
+            *schema_path = Path::new("schemas").join(&*schema_path);
+        }
+
+        fn visit_extension(&mut self, project: Option<&str>, name: &str, value: &mut Value) {
+            *value = json!(format!("{}/{}", project.unwrap_or("root"), name));
+        }
+    }
+
+    #[test]
+    fn it_rewrites_every_project() {
+        let mut config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "schemaPath": "root.graphql",
+            "projects": {
+                "app": { "schemaPath": "app.graphql", "extensions": { "a": 1, "b": 2 } }
+            }
+        }))
+        .unwrap();
+
+        config.visit_mut(&mut Prefix);
+
+        assert_eq!(config.root.schema_path, Some("schemas/root.graphql".into()));
+        let app = config.project("app").unwrap();
+        assert_eq!(app.schema_path, Some("schemas/app.graphql".into()));
+        assert_eq!(
+            ::serde_json::to_value(&app.extensions).unwrap(),
+            json!({ "a": "app/a", "b": "app/b" })
+        );
+    }
+}
@@ -0,0 +1,751 @@
+//! Watching the configuration file, for long-running tools.
+//!
+//! A `ConfigWatcher` polls the modification time and size of the file from a
+//! thread of its own, and loads it again once they stop changing for
+//! `DEFAULT_DEBOUNCE`, or the duration of `WatcherBuilder::debounce`. The
+//! configuration is then validated, and what came of it is sent as a
+//! `WatchEvent` over the channel of `ConfigWatcher::events`:
+//!
+//! ```no_run
+//! # extern crate graphql_config;
+//! # use graphql_config::watch::{ConfigWatcher, WatchEvent};
+//! # fn main() {
+//! let watcher = ConfigWatcher::new("/repo/.graphqlconfig");
+//! for event in watcher.events() {
+//!     match event {
+//!         WatchEvent::Reloaded(config) => println!("{} projects", config.projects().count()),
+//!         WatchEvent::Invalid(error) => eprintln!("keeping the previous configuration: {}", error),
+//!         WatchEvent::Rejected(problems) => eprintln!("keeping the previous configuration: {:?}", problems),
+//!         WatchEvent::Removed => break,
+//!         _ => {}
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! With `WatcherBuilder::documents`, the documents of every project are
+//! watched too: the files its includes and excludes match, as listed by
+//! `ProjectHandle::document_files`, are polled along with the configuration,
+//! and each file added, changed or removed is reported with the project it
+//! belongs to. A file belonging to several projects is reported once per
+//! project.
+//!
+//! The thread stops when the watcher is dropped, or when the `CancelToken`
+//! of `WatcherBuilder::cancel_token` is cancelled, which disconnects the
+//! channel of events.
+//!
+//! `WatcherBuilder::document_sets` turns the events into the successive
+//! sets of documents of one project, for pipelines regenerating code from
+//! them.
+//!
+//! Servers which only need the latest configuration can hold a
+//! `ConfigHandle` instead, which swaps each configuration the watcher loads
+//! in and tells its subscribers.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::cancel::CancelToken;
+use crate::load::LoadError;
+use crate::projects::ProjectName;
+use crate::resolved::ResolvedGraphQLConfig;
+use crate::schema::SchemaPointer;
+use crate::GraphQLConfiguration;
+
+/// How often `ConfigWatcher::new` checks the file.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long the configuration file must stay unchanged before it is loaded
+/// again, unless `WatcherBuilder::debounce` is given.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// What a change of the configuration file amounts to.
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// The file changed, or was created, and was loaded.
+    Reloaded(Box<ResolvedGraphQLConfig>),
+    /// The file changed but could not be loaded.
+    Invalid(LoadError),
+    /// The file changed and loaded, but the configuration is not valid for
+    /// the reasons given, such as a schema file which does not exist. It is
+    /// not swapped in.
+    Rejected(Vec<String>),
+    /// The file was removed.
+    Removed,
+    /// A document was added to a project, because it was created or because
+    /// the configuration now includes it.
+    DocumentAdded {
+        /// The project.
+        project: ProjectName,
+        /// The document.
+        path: PathBuf,
+    },
+    /// A document of a project changed.
+    DocumentChanged {
+        /// The project.
+        project: ProjectName,
+        /// The document.
+        path: PathBuf,
+    },
+    /// A document was removed from a project, because it was deleted or
+    /// because the configuration no longer includes it.
+    DocumentRemoved {
+        /// The project.
+        project: ProjectName,
+        /// The document.
+        path: PathBuf,
+    },
+}
+
+/// The options of a `ConfigWatcher`, see `ConfigWatcher::builder`.
+#[derive(Clone, Debug)]
+pub struct WatcherBuilder {
+    path: PathBuf,
+    interval: Duration,
+    debounce: Duration,
+    documents: bool,
+    cancel: Option<CancelToken>,
+}
+
+impl WatcherBuilder {
+    /// Checks the files every `interval`, rather than every
+    /// `DEFAULT_INTERVAL`.
+    pub fn interval(mut self, interval: Duration) -> WatcherBuilder {
+        self.interval = interval;
+        self
+    }
+
+    /// Waits for the configuration file to stay unchanged for `debounce`
+    /// before loading it, so that a burst of edits is loaded once.
+    pub fn debounce(mut self, debounce: Duration) -> WatcherBuilder {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Stops the watcher once `cancel` is cancelled, as if it were dropped.
+    pub fn cancel_token(mut self, cancel: CancelToken) -> WatcherBuilder {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Watches the documents of the projects too.
+    pub fn documents(mut self, documents: bool) -> WatcherBuilder {
+        self.documents = documents;
+        self
+    }
+
+    /// Starts watching.
+    pub fn spawn(self) -> ConfigWatcher {
+        let (events, receiver) = mpsc::channel();
+        let state = State::new(self.path, self.debounce, self.documents);
+        let poller = Poller::spawn(state, self.interval, self.cancel, move |event| {
+            events.send(event).is_ok()
+        });
+        ConfigWatcher {
+            events: receiver,
+            _poller: poller,
+        }
+    }
+
+    /// Starts watching the documents of `project`, whose sets are yielded by
+    /// the `DocumentSets` returned. The configuration is loaded first, for
+    /// the initial set.
+    pub fn document_sets(self, project: impl Into<String>) -> Result<DocumentSets, LoadError> {
+        let project = project.into();
+        let config = GraphQLConfiguration::try_from(self.path.as_path())?;
+        let documents = documents_of(&ResolvedGraphQLConfig::new(config, &self.path), &project);
+        Ok(DocumentSets {
+            watcher: self.documents(true).spawn(),
+            project,
+            initial: Some(documents.iter().cloned().collect()),
+            documents,
+        })
+    }
+
+    /// Loads the configuration and starts keeping a `ConfigHandle` to it up
+    /// to date. Documents are not watched, and the configuration loaded
+    /// first is not validated, there being none to keep instead.
+    pub fn handle(self) -> Result<ConfigHandle, LoadError> {
+        let state = State::new(self.path, self.debounce, false);
+        let config = GraphQLConfiguration::try_from(state.path.as_path())?;
+        let config = Arc::new(RwLock::new(Arc::new(ResolvedGraphQLConfig::new(
+            config,
+            &state.path,
+        ))));
+        let subscribers: Arc<Mutex<Vec<Sender<Arc<ResolvedGraphQLConfig>>>>> = Arc::default();
+
+        let (latest, notified) = (Arc::clone(&config), Arc::clone(&subscribers));
+        let poller = Poller::spawn(state, self.interval, self.cancel, move |event| {
+            if let WatchEvent::Reloaded(config) = event {
+                let config = Arc::new(*config);
+                *latest.write().unwrap_or_else(|error| error.into_inner()) = Arc::clone(&config);
+                notified
+                    .lock()
+                    .unwrap_or_else(|error| error.into_inner())
+                    .retain(|subscriber| subscriber.send(Arc::clone(&config)).is_ok());
+            }
+            true
+        });
+        Ok(ConfigHandle {
+            config,
+            subscribers,
+            _poller: Arc::new(poller),
+        })
+    }
+}
+
+/// A thread watching a configuration file.
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    events: Receiver<WatchEvent>,
+    _poller: Poller,
+}
+
+impl ConfigWatcher {
+    /// Watches `path`, checking it every `DEFAULT_INTERVAL`.
+    pub fn new(path: impl Into<PathBuf>) -> ConfigWatcher {
+        ConfigWatcher::builder(path).spawn()
+    }
+
+    /// Watches `path`, checking it every `interval`.
+    pub fn with_interval(path: impl Into<PathBuf>, interval: Duration) -> ConfigWatcher {
+        ConfigWatcher::builder(path).interval(interval).spawn()
+    }
+
+    /// The options of a watcher of `path`, to start with
+    /// `WatcherBuilder::spawn` or `WatcherBuilder::handle`.
+    pub fn builder(path: impl Into<PathBuf>) -> WatcherBuilder {
+        WatcherBuilder {
+            path: path.into(),
+            interval: DEFAULT_INTERVAL,
+            debounce: DEFAULT_DEBOUNCE,
+            documents: false,
+            cancel: None,
+        }
+    }
+
+    /// The events, in the order the changes were seen.
+    pub fn events(&self) -> &Receiver<WatchEvent> {
+        &self.events
+    }
+}
+
+/// The latest valid configuration of a file, swapped in by a watcher
+/// whenever the file changes, loads and validates. Invalid, rejected and
+/// removed files leave the previous configuration in place.
+///
+/// Clones share the configuration and the watcher, which stops when the
+/// last clone is dropped.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::watch::ConfigHandle;
+/// # fn main() -> Result<(), graphql_config::load::LoadError> {
+/// let handle = ConfigHandle::load("/repo/.graphqlconfig")?;
+/// let changes = handle.subscribe();
+///
+/// let config = handle.get();
+/// println!("{} projects", config.projects().count());
+/// for config in changes {
+///     println!("reloaded, {} projects", config.projects().count());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConfigHandle {
+    config: Arc<RwLock<Arc<ResolvedGraphQLConfig>>>,
+    subscribers: Arc<Mutex<Vec<Sender<Arc<ResolvedGraphQLConfig>>>>>,
+    _poller: Arc<Poller>,
+}
+
+impl ConfigHandle {
+    /// Loads the configuration at `path` and checks it for changes every
+    /// `DEFAULT_INTERVAL`, see `WatcherBuilder::handle`.
+    pub fn load(path: impl Into<PathBuf>) -> Result<ConfigHandle, LoadError> {
+        ConfigWatcher::builder(path).handle()
+    }
+
+    /// The latest valid configuration.
+    pub fn get(&self) -> Arc<ResolvedGraphQLConfig> {
+        Arc::clone(
+            &self
+                .config
+                .read()
+                .unwrap_or_else(|error| error.into_inner()),
+        )
+    }
+
+    /// A channel receiving each configuration swapped in from now on.
+    pub fn subscribe(&self) -> Receiver<Arc<ResolvedGraphQLConfig>> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .push(sender);
+        receiver
+    }
+}
+
+/// The documents of a project: the current set first, then the set again
+/// after every change to the configuration or to the documents. Changes
+/// seen in the same poll are yielded as one set. The iterator ends when the
+/// configuration file is removed or the watcher is cancelled.
+///
+/// ```no_run
+/// # extern crate graphql_config;
+/// # use graphql_config::watch::ConfigWatcher;
+/// # fn main() -> Result<(), graphql_config::load::LoadError> {
+/// let sets = ConfigWatcher::builder("/repo/.graphqlconfig").document_sets("web")?;
+/// for documents in sets {
+///     println!("generating code for {} documents", documents.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DocumentSets {
+    watcher: ConfigWatcher,
+    project: String,
+    initial: Option<Vec<PathBuf>>,
+    documents: BTreeSet<PathBuf>,
+}
+
+impl Iterator for DocumentSets {
+    /// The documents of the project, sorted.
+    type Item = Vec<PathBuf>;
+
+    fn next(&mut self) -> Option<Vec<PathBuf>> {
+        if let Some(initial) = self.initial.take() {
+            return Some(initial);
+        }
+        let mut changed = false;
+        let mut event = self.watcher.events().recv().ok()?;
+        loop {
+            match event {
+                WatchEvent::Reloaded(config) => {
+                    self.documents = documents_of(&config, &self.project);
+                    changed = true;
+                }
+                WatchEvent::DocumentAdded { project, path } if *project == self.project => {
+                    changed |= self.documents.insert(path);
+                }
+                WatchEvent::DocumentRemoved { project, path } if *project == self.project => {
+                    changed |= self.documents.remove(&path);
+                }
+                WatchEvent::DocumentChanged { project, .. } if *project == self.project => {
+                    changed = true;
+                }
+                WatchEvent::Removed => return None,
+                _ => {}
+            }
+            event = match self.watcher.events().try_recv() {
+                Ok(event) => event,
+                Err(_) if changed => return Some(self.documents.iter().cloned().collect()),
+                Err(TryRecvError::Empty) => self.watcher.events().recv().ok()?,
+                Err(TryRecvError::Disconnected) => return None,
+            };
+        }
+    }
+}
+
+/// The documents of `project` in `config`, none if it has no such project
+/// or its directory cannot be read.
+fn documents_of(config: &ResolvedGraphQLConfig, project: &str) -> BTreeSet<PathBuf> {
+    config
+        .project_handles()
+        .into_iter()
+        .find(|handle| handle.name() == project)
+        .and_then(|handle| handle.document_files().ok())
+        .unwrap_or_default()
+        .into_iter()
+        .collect()
+}
+
+/// The thread polling a `State`, stopped and joined when dropped.
+#[derive(Debug)]
+struct Poller {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Poller {
+    /// Polls `state` every `interval`, giving the events to `on_event` until
+    /// it returns `false` or `cancel` is cancelled.
+    fn spawn(
+        mut state: State,
+        interval: Duration,
+        cancel: Option<CancelToken>,
+        mut on_event: impl FnMut(WatchEvent) -> bool + Send + 'static,
+    ) -> Poller {
+        let (stop, stopped) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                if cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+                    return;
+                }
+                for event in state.poll() {
+                    if !on_event(event) {
+                        return;
+                    }
+                }
+            }
+        });
+        Poller {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Poller {
+    fn drop(&mut self) {
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The modification time and size of a file.
+type Stamp = (SystemTime, u64);
+
+/// What the thread of a watcher last saw.
+struct State {
+    path: PathBuf,
+    config_stamp: Option<Stamp>,
+    debounce: Duration,
+    /// When the configuration file last changed, while it has not been
+    /// loaded since.
+    changed_at: Option<Instant>,
+    watch_documents: bool,
+    /// The last valid configuration loaded, whose documents are watched.
+    /// Only kept when documents are watched.
+    config: Option<ResolvedGraphQLConfig>,
+    documents: BTreeMap<(ProjectName, PathBuf), Stamp>,
+}
+
+impl State {
+    fn new(path: PathBuf, debounce: Duration, documents: bool) -> State {
+        let config = if documents {
+            GraphQLConfiguration::try_from(path.as_path())
+                .ok()
+                .map(|config| ResolvedGraphQLConfig::new(config, &path))
+        } else {
+            None
+        };
+        let mut state = State {
+            config_stamp: stamp(&path),
+            path,
+            debounce,
+            changed_at: None,
+            watch_documents: documents,
+            config,
+            documents: BTreeMap::new(),
+        };
+        state.documents = state.scan_documents();
+        state
+    }
+
+    fn poll(&mut self) -> Vec<WatchEvent> {
+        let mut events = Vec::new();
+        let current = stamp(&self.path);
+        if current != self.config_stamp {
+            self.config_stamp = current;
+            self.changed_at = Some(Instant::now());
+        }
+        if self
+            .changed_at
+            .is_some_and(|changed_at| changed_at.elapsed() >= self.debounce)
+        {
+            self.changed_at = None;
+            events.push(self.reload());
+        }
+        if self.watch_documents {
+            self.poll_documents(&mut events);
+        }
+        events
+    }
+
+    fn reload(&mut self) -> WatchEvent {
+        if self.config_stamp.is_none() {
+            return WatchEvent::Removed;
+        }
+        let config = match GraphQLConfiguration::try_from(self.path.as_path()) {
+            Ok(config) => ResolvedGraphQLConfig::new(config, &self.path),
+            Err(error) => return WatchEvent::Invalid(error),
+        };
+        let problems = problems(&config);
+        if !problems.is_empty() {
+            return WatchEvent::Rejected(problems);
+        }
+        if self.watch_documents {
+            self.config = Some(config.clone());
+        }
+        WatchEvent::Reloaded(Box::new(config))
+    }
+
+    fn poll_documents(&mut self, events: &mut Vec<WatchEvent>) {
+        let documents = self.scan_documents();
+        for ((project, path), stamp) in &documents {
+            let key = (project.clone(), path.clone());
+            match self.documents.get(&key) {
+                None => events.push(WatchEvent::DocumentAdded {
+                    project: key.0,
+                    path: key.1,
+                }),
+                Some(previous) if previous != stamp => events.push(WatchEvent::DocumentChanged {
+                    project: key.0,
+                    path: key.1,
+                }),
+                Some(_) => {}
+            }
+        }
+        for (project, path) in self.documents.keys() {
+            if !documents.contains_key(&(project.clone(), path.clone())) {
+                events.push(WatchEvent::DocumentRemoved {
+                    project: project.clone(),
+                    path: path.clone(),
+                });
+            }
+        }
+        self.documents = documents;
+    }
+
+    /// The documents of every project with their stamps. The documents of a
+    /// project whose directory cannot be read are those seen last.
+    fn scan_documents(&self) -> BTreeMap<(ProjectName, PathBuf), Stamp> {
+        let mut documents = BTreeMap::new();
+        let config = match self.config {
+            Some(ref config) => config,
+            None => return documents,
+        };
+        for (name, _) in config.projects() {
+            let project = config
+                .project_handle(name)
+                .expect("the name was listed by `projects`");
+            match project.document_files() {
+                Ok(files) => {
+                    for file in files {
+                        if let Some(stamp) = stamp(&file) {
+                            documents.insert((name.clone(), file), stamp);
+                        }
+                    }
+                }
+                Err(_) => documents.extend(
+                    self.documents
+                        .iter()
+                        .filter(|&((project, _), _)| project == name)
+                        .map(|(key, stamp)| (key.clone(), *stamp)),
+                ),
+            }
+        }
+        documents
+    }
+}
+
+/// What makes `config` unusable: the schema files of projects which do not
+/// exist.
+fn problems(config: &ResolvedGraphQLConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    for project in config.project_handles() {
+        let schema_path = match project.config().schema_path {
+            Some(ref schema_path) => schema_path.to_string_lossy(),
+            None => continue,
+        };
+        if let SchemaPointer::File(schema) = SchemaPointer::classify(&schema_path, config.dir()) {
+            if !schema.is_file() {
+                problems.push(format!(
+                    "project `{}`: the schema `{}` does not exist",
+                    project.name(),
+                    schema.display()
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// The stamp of the file at `path`, or `None` if there is none.
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn it_reports_changes_of_the_file() {
+        let dir = TestDir::new("it_reports_changes_of_the_file");
+        let path = dir.join(".graphqlconfig");
+        fs::write(&path, r#"{ "schemaPath": "a.graphql" }"#).unwrap();
+        fs::write(dir.join("schema.graphql"), "").unwrap();
+
+        let watcher = ConfigWatcher::with_interval(&path, Duration::from_millis(10));
+        let next = || {
+            watcher
+                .events()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+        };
+
+        fs::write(&path, "{ not json").unwrap();
+        fs::write(&path, r#"{ "schemaPath": "schema.graphql" }"#).unwrap();
+        match next() {
+            WatchEvent::Reloaded(config) => {
+                assert_eq!(
+                    config.project("default").unwrap().schema_path,
+                    Some("schema.graphql".into())
+                );
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        fs::write(&path, "{ not json").unwrap();
+        match next() {
+            WatchEvent::Invalid(LoadError::Json(_)) => {}
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        fs::write(&path, r#"{ "schemaPath": "missing.graphql" }"#).unwrap();
+        match next() {
+            WatchEvent::Rejected(problems) => assert!(problems[0].contains("missing.graphql")),
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        fs::remove_file(&path).unwrap();
+        match next() {
+            WatchEvent::Removed => {}
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn it_reports_documents_by_project() {
+        let dir = TestDir::new("it_reports_documents_by_project");
+        fs::create_dir_all(dir.join("src/generated")).unwrap();
+        let path = dir.join(".graphqlconfig");
+        fs::write(
+            &path,
+            r#"{ "includes": ["src/**/*.graphql"], "excludes": ["src/generated/**"] }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("src/a.graphql"), "").unwrap();
+
+        let watcher = ConfigWatcher::builder(&path)
+            .interval(Duration::from_millis(10))
+            .documents(true)
+            .spawn();
+        let next = || {
+            watcher
+                .events()
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+        };
+
+        fs::write(dir.join("src/generated/b.graphql"), "").unwrap();
+        fs::write(dir.join("src/b.graphql"), "").unwrap();
+        match next() {
+            WatchEvent::DocumentAdded { project, path } => {
+                assert_eq!(project, "default");
+                assert_eq!(path, dir.join("src/b.graphql"));
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        fs::write(dir.join("src/a.graphql"), "{ a }").unwrap();
+        match next() {
+            WatchEvent::DocumentChanged { path, .. } => assert_eq!(path, dir.join("src/a.graphql")),
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        fs::remove_file(dir.join("src/b.graphql")).unwrap();
+        match next() {
+            WatchEvent::DocumentRemoved { path, .. } => assert_eq!(path, dir.join("src/b.graphql")),
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn handles_keep_the_latest_valid_configuration() {
+        let dir = TestDir::new("handles_keep_the_latest_valid_configuration");
+        let path = dir.join(".graphqlconfig");
+        fs::write(&path, r#"{ "schemaPath": "a.graphql" }"#).unwrap();
+        fs::write(dir.join("schema.graphql"), "").unwrap();
+
+        let handle = ConfigWatcher::builder(&path)
+            .interval(Duration::from_millis(10))
+            .handle()
+            .unwrap();
+        let changes = handle.clone().subscribe();
+        let schema_path =
+            |config: &ResolvedGraphQLConfig| config.project("default").unwrap().schema_path;
+        assert_eq!(schema_path(&handle.get()), Some("a.graphql".into()));
+
+        fs::write(&path, "{ not json").unwrap();
+        fs::write(&path, r#"{ "schemaPath": "schema.graphql" }"#).unwrap();
+        let config = changes.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(schema_path(&config), Some("schema.graphql".into()));
+        assert_eq!(schema_path(&handle.get()), Some("schema.graphql".into()));
+
+        fs::write(&path, r#"{ "schemaPath": "missing.graphql" }"#).unwrap();
+        thread::sleep(DEFAULT_DEBOUNCE * 3);
+        assert_eq!(schema_path(&handle.get()), Some("schema.graphql".into()));
+    }
+
+    #[test]
+    fn cancelled_watchers_disconnect() {
+        let dir = TestDir::new("cancelled_watchers_disconnect");
+        let cancel = CancelToken::new();
+        let watcher = ConfigWatcher::builder(dir.join(".graphqlconfig"))
+            .interval(Duration::from_millis(10))
+            .cancel_token(cancel.clone())
+            .spawn();
+
+        cancel.cancel();
+        assert!(matches!(
+            watcher.events().recv_timeout(Duration::from_secs(5)),
+            Err(RecvTimeoutError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn document_sets_follow_the_files() {
+        let dir = TestDir::new("document_sets_follow_the_files");
+        let path = dir.join(".graphqlconfig");
+        fs::write(
+            &path,
+            r#"{ "projects": { "web": { "includes": ["*.graphql"] } } }"#,
+        )
+        .unwrap();
+        fs::write(dir.join("a.graphql"), "").unwrap();
+
+        let mut sets = ConfigWatcher::builder(&path)
+            .interval(Duration::from_millis(10))
+            .document_sets("web")
+            .unwrap();
+        assert_eq!(sets.next(), Some(vec![dir.join("a.graphql")]));
+
+        fs::write(dir.join("b.graphql"), "").unwrap();
+        assert_eq!(
+            sets.next(),
+            Some(vec![dir.join("a.graphql"), dir.join("b.graphql")])
+        );
+
+        fs::write(
+            &path,
+            r#"{ "projects": { "web": { "includes": ["b.graphql"] } } }"#,
+        )
+        .unwrap();
+        assert_eq!(sets.next(), Some(vec![dir.join("b.graphql")]));
+    }
+}
@@ -0,0 +1,55 @@
+//! Writing configurations to files and streams.
+
+use std::io::Write;
+
+use crate::GraphQLConfiguration;
+
+impl GraphQLConfiguration {
+    /// Serializes the configuration as pretty-printed JSON, followed by a
+    /// newline, straight into `writer`.
+    ///
+    /// ```
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), serde_json::Error> {
+    /// let mut config = GraphQLConfiguration::new();
+    /// config.root.schema_path = Some("schema.graphql".into());
+    ///
+    /// let mut written = Vec::new();
+    /// config.write_json(&mut written)?;
+    /// assert_eq!(serde_json::from_slice::<GraphQLConfiguration>(&written)?, config);
+    /// assert!(written.ends_with(b"}\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_json(&self, writer: &mut impl Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer_pretty(&mut *writer, self)?;
+        writer.write_all(b"\n").map_err(serde_json::Error::io)
+    }
+
+    /// Serializes the configuration as YAML straight into `writer`. The
+    /// fields are the ones of the JSON representation, see `export_v3_yaml`
+    /// for the v3 format.
+    #[cfg(feature = "yaml")]
+    pub fn write_yaml(&self, writer: &mut impl Write) -> Result<(), serde_yaml::Error> {
+        serde_yaml::to_writer(writer, self)
+    }
+}
+
+#[cfg(all(test, feature = "yaml"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_yaml() {
+        let config: GraphQLConfiguration =
+            ::serde_json::from_value(json!({ "includes": ["src/*.graphql"] })).unwrap();
+
+        let mut written = Vec::new();
+        config.write_yaml(&mut written).unwrap();
+        assert_eq!(
+            ::serde_yaml::from_slice::<GraphQLConfiguration>(&written).unwrap(),
+            config
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! YAML support, enabled by the `yaml` feature.
+
+use std::convert::TryFrom;
+
+use crate::v3;
+use crate::GraphQLConfiguration;
+
+/// Extracts a configuration embedded in a larger YAML document.
+///
+/// ```
+/// # extern crate graphql_config;
+/// # extern crate serde_yaml;
+/// # use graphql_config::*;
+/// # use std::convert::TryFrom;
+/// # fn main() -> Result<(), serde_yaml::Error> {
+/// let document: serde_yaml::Value = serde_yaml::from_str("graphql:\n  schemaPath: ./schema.graphql\n")?;
+///
+/// let config = GraphQLConfiguration::try_from(document["graphql"].clone())?;
+/// assert_eq!(config.root.schema_path, Some("./schema.graphql".into()));
+/// # Ok(())
+/// # }
+/// ```
+impl TryFrom<serde_yaml::Value> for GraphQLConfiguration {
+    type Error = serde_yaml::Error;
+
+    fn try_from(value: serde_yaml::Value) -> Result<GraphQLConfiguration, serde_yaml::Error> {
+        serde_yaml::from_value(value)
+    }
+}
+
+/// Converts a configuration into a YAML tree, to be inserted into a larger
+/// document. The fields are the ones of the JSON representation.
+impl<'a> TryFrom<&'a GraphQLConfiguration> for serde_yaml::Value {
+    type Error = serde_yaml::Error;
+
+    fn try_from(config: &'a GraphQLConfiguration) -> Result<serde_yaml::Value, serde_yaml::Error> {
+        serde_yaml::to_value(config)
+    }
+}
+
+impl GraphQLConfiguration {
+    /// Converts the configuration to the v3 format and serializes it as YAML,
+    /// ready to be written to a `.graphqlrc.yml` file.
+    ///
+    /// See the `v3` module for how legacy fields are converted.
+    ///
+    /// ```
+    /// # #[macro_use]
+    /// # extern crate serde_json;
+    /// # extern crate graphql_config;
+    /// # use graphql_config::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let config: GraphQLConfiguration = serde_json::from_value(json!({
+    ///     "schemaPath": "./schema.graphql",
+    ///     "includes": ["./src/*.graphql"]
+    /// }))?;
+    ///
+    /// assert_eq!(
+    ///     config.export_v3_yaml()?,
+    ///     "schema: ./schema.graphql\ninclude:\n- ./src/*.graphql\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_v3_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(&v3::GraphQLConfiguration::from(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_converts_to_and_from_yaml_values() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "includes": ["./src/*.graphql"],
+            "projects": { "app": { "extensions": { "lastUpdatedAt": 1 } } }
+        }))
+        .unwrap();
+
+        let value = serde_yaml::Value::try_from(&config).unwrap();
+        assert_eq!(value["projects"]["app"]["extensions"]["lastUpdatedAt"], 1);
+        assert_eq!(GraphQLConfiguration::try_from(value).unwrap(), config);
+    }
+
+    #[test]
+    fn it_exports_projects_and_extensions() {
+        let config: GraphQLConfiguration = ::serde_json::from_value(json!({
+            "projects": {
+                "app": {
+                    "name": "app",
+                    "schemaPath": "./app.graphql",
+                    "extensions": { "endpoints": { "dev": "http://localhost:4000" } }
+                }
+            }
+        }))
+        .unwrap();
+
+        let exported = config.export_v3_yaml().unwrap();
+
+        assert_eq!(
+            exported,
+            "projects:\n  app:\n    schema: ./app.graphql\n    extensions:\n      endpoints:\n        dev: http://localhost:4000\n"
+        );
+        let reparsed: v3::GraphQLConfiguration = serde_yaml::from_str(&exported).unwrap();
+        assert_eq!(reparsed, v3::GraphQLConfiguration::from(&config));
+    }
+}
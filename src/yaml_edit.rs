@@ -0,0 +1,732 @@
+//! Targeted edits of YAML configuration files.
+//!
+//! Re-serializing a `GraphQLConfiguration` throws away everything serde does
+//! not model: comments, key order, blank lines and quoting style. The
+//! `YamlConfigEditor` instead patches the source text in place, touching only
+//! the lines an edit is about. It understands block-style mappings and
+//! sequences, which is what hand-written `.graphqlconfig.yaml` files use;
+//! flow-style collections are only supported for `includes` and `excludes`
+//! lists that fit on one line.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::map;
+use crate::GraphQLProjectConfiguration;
+
+/// The error returned when a `YamlConfigEditor` edit cannot be applied.
+#[derive(Debug, PartialEq)]
+pub enum YamlEditError {
+    /// The edit refers to a project which is not present in `projects`.
+    ProjectNotFound(String),
+    /// `add_project` was called with the name of an existing project.
+    ProjectExists(String),
+    /// The part of the document to be edited uses syntax the editor does not
+    /// understand, such as a flow-style mapping.
+    Unsupported(String),
+}
+
+impl fmt::Display for YamlEditError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            YamlEditError::ProjectNotFound(ref name) => write!(f, "project `{}` not found", name),
+            YamlEditError::ProjectExists(ref name) => {
+                write!(f, "project `{}` already exists", name)
+            }
+            YamlEditError::Unsupported(ref reason) => {
+                write!(f, "unsupported YAML syntax: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for YamlEditError {}
+
+/// Applies edits to the text of a YAML configuration while preserving its
+/// comments and formatting.
+///
+/// Edits take an optional project name: `None` edits the top-level
+/// configuration, `Some(name)` edits the entry under `projects`.
+///
+/// ```
+/// # use graphql_config::yaml_edit::YamlConfigEditor;
+/// let mut editor = YamlConfigEditor::new("# Our schema\nschemaPath: ./schema.graphql\n");
+/// editor.add_include(None, "./src/**/*.graphql").unwrap();
+///
+/// assert_eq!(
+///     editor.to_string(),
+///     "# Our schema\nschemaPath: ./schema.graphql\nincludes:\n  - ./src/**/*.graphql\n"
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct YamlConfigEditor {
+    lines: Vec<String>,
+    newline: &'static str,
+    trailing_newline: bool,
+}
+
+/// A range of lines holding the entries of a block mapping or sequence, all
+/// of which start at `indent`.
+#[derive(Clone, Copy, Debug)]
+struct Block {
+    indent: usize,
+    start: usize,
+    end: usize,
+}
+
+/// A `key: value` entry of a block mapping.
+#[derive(Debug)]
+struct Entry {
+    key: String,
+    line: usize,
+    /// One past the last line belonging to the entry's value.
+    end: usize,
+    /// The byte range of a value written on the key's line, if any.
+    inline_value: Option<(usize, usize)>,
+}
+
+impl YamlConfigEditor {
+    /// Creates an editor for the given YAML source.
+    pub fn new(source: &str) -> YamlConfigEditor {
+        let newline = if source.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let trailing_newline = source.ends_with('\n');
+        let mut lines: Vec<String> = source
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r').to_owned())
+            .collect();
+        if trailing_newline {
+            lines.pop();
+        }
+
+        YamlConfigEditor {
+            lines,
+            newline,
+            trailing_newline,
+        }
+    }
+
+    /// Sets `schemaPath`, replacing the current value or adding the key at
+    /// the end of the mapping.
+    pub fn set_schema_path(
+        &mut self,
+        project: Option<&str>,
+        schema_path: &str,
+    ) -> Result<(), YamlEditError> {
+        let mapping = self.mapping(project)?;
+        let value = render_scalar(schema_path, false);
+
+        match self.find_entry(mapping, "schemaPath")? {
+            Some(Entry {
+                line,
+                end,
+                inline_value: Some((from, to)),
+                ..
+            }) => {
+                self.lines[line].replace_range(from..to, &value);
+                let continuation = line + 1..end;
+                self.lines.drain(continuation);
+            }
+            Some(Entry { line, end, .. }) => {
+                self.lines[line].push(' ');
+                self.lines[line].push_str(&value);
+                self.lines.drain(line + 1..end);
+            }
+            None => {
+                let at = self.insertion_point(mapping);
+                let line = format!("{}schemaPath: {}", spaces(mapping.indent), value);
+                self.lines.insert(at, line);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends a pattern to `includes`, creating the list if needed. Patterns
+    /// which are already listed are left alone.
+    pub fn add_include(
+        &mut self,
+        project: Option<&str>,
+        pattern: &str,
+    ) -> Result<(), YamlEditError> {
+        self.add_sequence_item(project, "includes", pattern)
+    }
+
+    /// Appends a pattern to `excludes`, creating the list if needed. Patterns
+    /// which are already listed are left alone.
+    pub fn add_exclude(
+        &mut self,
+        project: Option<&str>,
+        pattern: &str,
+    ) -> Result<(), YamlEditError> {
+        self.add_sequence_item(project, "excludes", pattern)
+    }
+
+    /// Adds a project under `projects`, creating the mapping if needed.
+    pub fn add_project(
+        &mut self,
+        name: &str,
+        project: &GraphQLProjectConfiguration,
+    ) -> Result<(), YamlEditError> {
+        let unit = self.indent_unit();
+        let root = self.root()?;
+
+        let (at, indent) = match self.find_entry(root, "projects")? {
+            Some(Entry {
+                inline_value: Some(_),
+                ..
+            }) => {
+                return Err(YamlEditError::Unsupported(
+                    "`projects` is not a block mapping".to_owned(),
+                ))
+            }
+            Some(entry) => {
+                let projects = self.child_block(&entry)?;
+                if self.find_entry(projects, name)?.is_some() {
+                    return Err(YamlEditError::ProjectExists(name.to_owned()));
+                }
+                (self.insertion_point(projects), projects.indent)
+            }
+            None => {
+                let at = self.insertion_point(root);
+                self.lines
+                    .insert(at, format!("{}projects:", spaces(root.indent)));
+                (at + 1, root.indent + unit)
+            }
+        };
+
+        let rendered = render_project(name, project, indent, unit);
+        for (offset, line) in rendered.into_iter().enumerate() {
+            self.lines.insert(at + offset, line);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the edited document.
+    pub fn into_string(self) -> String {
+        self.to_string()
+    }
+
+    fn add_sequence_item(
+        &mut self,
+        project: Option<&str>,
+        key: &str,
+        item: &str,
+    ) -> Result<(), YamlEditError> {
+        let unit = self.indent_unit();
+        let mapping = self.mapping(project)?;
+
+        let entry = match self.find_entry(mapping, key)? {
+            Some(entry) => entry,
+            None => {
+                let at = self.insertion_point(mapping);
+                let lines = vec![
+                    format!("{}{}:", spaces(mapping.indent), key),
+                    format!(
+                        "{}- {}",
+                        spaces(mapping.indent + unit),
+                        render_scalar(item, false)
+                    ),
+                ];
+                for (offset, line) in lines.into_iter().enumerate() {
+                    self.lines.insert(at + offset, line);
+                }
+                return Ok(());
+            }
+        };
+
+        if let Some((from, to)) = entry.inline_value {
+            let value = self.lines[entry.line][from..to].to_owned();
+            if !(value.starts_with('[') && value.ends_with(']')) {
+                return Err(YamlEditError::Unsupported(format!(
+                    "`{}` is neither a block nor a single-line flow sequence",
+                    key
+                )));
+            }
+
+            let inner = value[1..value.len() - 1].trim();
+            if flow_items(inner)
+                .into_iter()
+                .any(|existing| unquote(existing.trim()) == item)
+            {
+                return Ok(());
+            }
+
+            let rendered = render_scalar(item, true);
+            let replacement = if inner.is_empty() {
+                format!("[{}]", rendered)
+            } else {
+                format!("[{}, {}]", inner, rendered)
+            };
+            self.lines[entry.line].replace_range(from..to, &replacement);
+            return Ok(());
+        }
+
+        let sequence = self.child_block(&entry)?;
+        for index in sequence.start..sequence.end {
+            let line = &self.lines[index];
+            if is_ignorable(line) || indent_of(line) != sequence.indent {
+                continue;
+            }
+            let content = &line[sequence.indent..];
+            if !(content == "-" || content.starts_with("- ")) {
+                return Err(YamlEditError::Unsupported(format!(
+                    "`{}` is not a sequence",
+                    key
+                )));
+            }
+            let (from, to) = value_range(content, 1);
+            if unquote(&content[from..to]) == item {
+                return Ok(());
+            }
+        }
+        let line = format!(
+            "{}- {}",
+            spaces(sequence.indent),
+            render_scalar(item, false)
+        );
+        self.lines.insert(entry.end, line);
+        Ok(())
+    }
+
+    /// The block mapping holding the top-level configuration.
+    fn root(&self) -> Result<Block, YamlEditError> {
+        let first = self.lines.iter().find(|line| !is_ignorable(line));
+        let indent = match first {
+            Some(line) => {
+                let content = line.trim_start();
+                if content.starts_with('{') || content.starts_with('[') {
+                    return Err(YamlEditError::Unsupported(
+                        "flow-style documents are not supported".to_owned(),
+                    ));
+                }
+                indent_of(line)
+            }
+            None => 0,
+        };
+
+        Ok(Block {
+            indent,
+            start: 0,
+            end: self.lines.len(),
+        })
+    }
+
+    /// The block mapping holding the configuration of `project`, or the
+    /// top-level configuration if `project` is `None`.
+    fn mapping(&self, project: Option<&str>) -> Result<Block, YamlEditError> {
+        let root = self.root()?;
+        let name = match project {
+            Some(name) => name,
+            None => return Ok(root),
+        };
+
+        let not_found = || YamlEditError::ProjectNotFound(name.to_owned());
+        let projects = self.find_entry(root, "projects")?.ok_or_else(not_found)?;
+        if projects.inline_value.is_some() {
+            return Err(not_found());
+        }
+        let projects = self.child_block(&projects)?;
+        let project = self.find_entry(projects, name)?.ok_or_else(not_found)?;
+        if project.inline_value.is_some() {
+            return Err(YamlEditError::Unsupported(format!(
+                "project `{}` is not a block mapping",
+                name
+            )));
+        }
+
+        self.child_block(&project)
+    }
+
+    fn find_entry(&self, block: Block, key: &str) -> Result<Option<Entry>, YamlEditError> {
+        Ok(self
+            .entries(block)?
+            .into_iter()
+            .find(|entry| entry.key == key))
+    }
+
+    fn entries(&self, block: Block) -> Result<Vec<Entry>, YamlEditError> {
+        let mut entries: Vec<Entry> = Vec::new();
+
+        for index in block.start..block.end {
+            let line = &self.lines[index];
+            if is_ignorable(line) {
+                continue;
+            }
+            let indent = indent_of(line);
+            if indent > block.indent {
+                if let Some(entry) = entries.last_mut() {
+                    entry.end = index + 1;
+                }
+                continue;
+            }
+
+            let content = &line[indent..];
+            if content == "-" || content.starts_with("- ") {
+                match entries.last_mut() {
+                    Some(entry) if entry.inline_value.is_none() => {
+                        entry.end = index + 1;
+                        continue;
+                    }
+                    _ => {
+                        return Err(YamlEditError::Unsupported(
+                            "expected a mapping, found a sequence".to_owned(),
+                        ))
+                    }
+                }
+            }
+
+            let (key, colon) = parse_key(content).ok_or_else(|| {
+                YamlEditError::Unsupported(format!("cannot parse mapping entry `{}`", content))
+            })?;
+            let (from, to) = value_range(content, colon + 1);
+            entries.push(Entry {
+                key,
+                line: index,
+                end: index + 1,
+                inline_value: if from == to {
+                    None
+                } else {
+                    Some((indent + from, indent + to))
+                },
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// The block nested under an entry without an inline value.
+    fn child_block(&self, entry: &Entry) -> Result<Block, YamlEditError> {
+        (entry.line + 1..entry.end)
+            .find(|&index| !is_ignorable(&self.lines[index]))
+            .map(|index| Block {
+                indent: indent_of(&self.lines[index]),
+                start: entry.line + 1,
+                end: entry.end,
+            })
+            .ok_or_else(|| YamlEditError::Unsupported(format!("`{}` has no value", entry.key)))
+    }
+
+    /// Where a new entry should go so that it ends up after the last entry of
+    /// `block` but before any trailing comments.
+    fn insertion_point(&self, block: Block) -> usize {
+        (block.start..block.end)
+            .rev()
+            .find(|&index| !is_ignorable(&self.lines[index]))
+            .map_or(block.end, |index| index + 1)
+    }
+
+    /// The indentation step used by the document, defaulting to two spaces.
+    fn indent_unit(&self) -> usize {
+        let mut content = self.lines.iter().filter(|line| !is_ignorable(line));
+        let mut previous = match content.next() {
+            Some(line) => indent_of(line),
+            None => return 2,
+        };
+
+        for line in content {
+            let indent = indent_of(line);
+            if indent > previous {
+                return indent - previous;
+            }
+            previous = indent;
+        }
+
+        2
+    }
+}
+
+impl fmt::Display for YamlConfigEditor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, line) in self.lines.iter().enumerate() {
+            if index > 0 {
+                f.write_str(self.newline)?;
+            }
+            f.write_str(line)?;
+        }
+        if self.trailing_newline {
+            f.write_str(self.newline)?;
+        }
+        Ok(())
+    }
+}
+
+fn render_project(
+    name: &str,
+    project: &GraphQLProjectConfiguration,
+    indent: usize,
+    unit: usize,
+) -> Vec<String> {
+    let key = render_scalar(name, false);
+    let field = spaces(indent + unit);
+    let item = spaces(indent + 2 * unit);
+    let mut lines = vec![format!("{}{}:", spaces(indent), key)];
+
+    if let Some(ref name) = project.name {
+        lines.push(format!("{}name: {}", field, render_scalar(name, false)));
+    }
+    if let Some(ref schema_path) = project.schema_path {
+        let schema_path = schema_path.to_string_lossy();
+        lines.push(format!(
+            "{}schemaPath: {}",
+            field,
+            render_scalar(&schema_path, false)
+        ));
+    }
+    for &(key, patterns) in &[
+        ("includes", &project.includes),
+        ("excludes", &project.excludes),
+    ] {
+        if let Some(ref patterns) = *patterns {
+            lines.push(format!("{}{}:", field, key));
+            for pattern in patterns {
+                lines.push(format!("{}- {}", item, render_scalar(pattern, false)));
+            }
+        }
+    }
+    if let Some(ref extensions) = project.extensions {
+        lines.push(format!("{}extensions:", field));
+        for (key, value) in map::ordered(extensions) {
+            // JSON is valid flow-style YAML.
+            lines.push(format!("{}{}: {}", item, render_scalar(key, false), value));
+        }
+    }
+
+    if lines.len() == 1 {
+        lines[0].push_str(" {}");
+    }
+    lines
+}
+
+fn spaces(count: usize) -> String {
+    " ".repeat(count)
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_ignorable(line: &str) -> bool {
+    let content = line.trim();
+    content.is_empty() || content.starts_with('#') || content == "---" || content == "..."
+}
+
+/// Parses the key of a mapping entry, returning it along with the byte
+/// offset of the `:` separating it from its value.
+fn parse_key(content: &str) -> Option<(String, usize)> {
+    let quote = content.chars().next()?;
+    if quote == '"' || quote == '\'' {
+        let close = content[1..].find(quote)? + 1;
+        if !content[close + 1..].starts_with(':') {
+            return None;
+        }
+        return Some((unquote(&content[..=close]), close + 1));
+    }
+
+    let bytes = content.as_bytes();
+    let colon = (0..bytes.len()).find(|&index| {
+        bytes[index] == b':' && (index + 1 == bytes.len() || bytes[index + 1] == b' ')
+    })?;
+    Some((content[..colon].trim_end().to_owned(), colon))
+}
+
+/// The byte range of the scalar starting after `from`, excluding surrounding
+/// whitespace and any trailing comment.
+fn value_range(content: &str, from: usize) -> (usize, usize) {
+    let start = from + (content[from..].len() - content[from..].trim_start().len());
+    let mut end = content.len();
+    let mut quote = None;
+    let mut previous = ' ';
+
+    for (offset, character) in content[start..].char_indices() {
+        match quote {
+            Some(open) if character == open => quote = None,
+            Some(_) => {}
+            None if character == '"' || character == '\'' => quote = Some(character),
+            None if character == '#' && previous.is_whitespace() => {
+                end = start + offset;
+                break;
+            }
+            None => {}
+        }
+        previous = character;
+    }
+
+    let end = start + content[start..end].trim_end().len();
+    (start, end.max(start))
+}
+
+/// Splits the contents of a flow sequence at the commas separating its
+/// items, skipping those inside quotes and nested brackets or braces.
+fn flow_items(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (offset, character) in inner.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if character == '\\' => escaped = true,
+            Some(open) if character == open => quote = None,
+            Some(_) => {}
+            None => match character {
+                '"' | '\'' => quote = Some(character),
+                '[' | '{' => depth += 1,
+                ']' | '}' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    items.push(&inner[start..offset]);
+                    start = offset + 1;
+                }
+                _ => {}
+            },
+        }
+    }
+    items.push(&inner[start..]);
+    items
+}
+
+fn unquote(scalar: &str) -> String {
+    if scalar.len() >= 2 && scalar.starts_with('\'') && scalar.ends_with('\'') {
+        return scalar[1..scalar.len() - 1].replace("''", "'");
+    }
+    if scalar.len() >= 2 && scalar.starts_with('"') && scalar.ends_with('"') {
+        if let Ok(unquoted) = ::serde_json::from_str::<String>(scalar) {
+            return unquoted;
+        }
+    }
+    scalar.to_owned()
+}
+
+/// Renders a string as a plain scalar when that is unambiguous and as a
+/// double-quoted one otherwise.
+fn render_scalar(value: &str, in_flow: bool) -> String {
+    let first_is_safe = value
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == '/' || c == '_');
+    let reserved = ["true", "false", "yes", "no", "on", "off", "null", "~"]
+        .iter()
+        .any(|word| value.eq_ignore_ascii_case(word));
+    let plain = first_is_safe
+        && !reserved
+        && value.parse::<f64>().is_err()
+        && value.trim() == value
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.ends_with(':')
+        && !value.chars().any(|c| c.is_control())
+        && !(in_flow && value.contains(|c| ",[]{}".contains(c)));
+
+    if plain {
+        value.to_owned()
+    } else {
+        ::serde_json::Value::from(value).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit<F>(source: &str, f: F) -> String
+    where
+        F: FnOnce(&mut YamlConfigEditor) -> Result<(), YamlEditError>,
+    {
+        let mut editor = YamlConfigEditor::new(source);
+        f(&mut editor).unwrap();
+        editor.into_string()
+    }
+
+    #[test]
+    fn it_replaces_the_schema_path_keeping_comments() {
+        let source = "# Top-level config\nschemaPath: ./old.graphql # generated\nincludes:\n  - ./src/*.graphql\n";
+
+        let edited = edit(source, |e| e.set_schema_path(None, "./new.graphql"));
+
+        assert_eq!(
+            edited,
+            "# Top-level config\nschemaPath: ./new.graphql # generated\nincludes:\n  - ./src/*.graphql\n"
+        );
+    }
+
+    #[test]
+    fn it_adds_includes_to_block_and_flow_sequences() {
+        let source = "projects:\n  app:\n    includes:\n    - ./app/*.graphql\n    excludes: [./app/generated/*]\n  # trailing comment\n";
+
+        let edited = edit(source, |e| {
+            e.add_include(Some("app"), "**/*.gql")?;
+            e.add_include(Some("app"), "./app/*.graphql")?;
+            e.add_exclude(Some("app"), "./app/vendor/*")
+        });
+
+        assert_eq!(
+            edited,
+            "projects:\n  app:\n    includes:\n    - ./app/*.graphql\n    - \"**/*.gql\"\n    excludes: [./app/generated/*, ./app/vendor/*]\n  # trailing comment\n"
+        );
+    }
+
+    #[test]
+    fn it_keeps_flow_items_with_commas_whole() {
+        let source = "includes: [\"src/{a,b}.graphql\", 'it''s, quoted']\n";
+
+        let edited = edit(source, |e| {
+            e.add_include(None, "src/{a,b}.graphql")?;
+            e.add_include(None, "it's, quoted")?;
+            e.add_include(None, "src/{c,d}.graphql")
+        });
+
+        assert_eq!(
+            edited,
+            "includes: [\"src/{a,b}.graphql\", 'it''s, quoted', \"src/{c,d}.graphql\"]\n"
+        );
+        assert_eq!(
+            flow_items(r#""a\",b", [c, d], {e: f}"#),
+            vec![r#""a\",b""#, " [c, d]", " {e: f}"]
+        );
+    }
+
+    #[test]
+    fn it_adds_projects() {
+        let source = "schemaPath: ./schema.graphql\r\n";
+        let project = GraphQLProjectConfiguration {
+            name: None,
+            schema_path: Some("./admin.graphql".into()),
+            includes: Some(vec!["./admin/*.graphql".parse().unwrap()]),
+            excludes: None,
+            extensions: Some(map! { "endpoint".to_owned() => json!("http://localhost") }),
+        };
+
+        let mut editor = YamlConfigEditor::new(source);
+        editor.add_project("admin", &project).unwrap();
+        editor
+            .set_schema_path(Some("admin"), "./admin.schema.graphql")
+            .unwrap();
+
+        assert_eq!(
+            editor.add_project("admin", &project),
+            Err(YamlEditError::ProjectExists("admin".to_owned()))
+        );
+        assert_eq!(
+            editor.into_string(),
+            "schemaPath: ./schema.graphql\r\nprojects:\r\n  admin:\r\n    schemaPath: ./admin.schema.graphql\r\n    includes:\r\n      - ./admin/*.graphql\r\n    extensions:\r\n      endpoint: \"http://localhost\"\r\n"
+        );
+    }
+
+    #[test]
+    fn it_reports_missing_projects_and_flow_documents() {
+        let mut editor = YamlConfigEditor::new("schemaPath: ./schema.graphql\n");
+        assert_eq!(
+            editor.add_include(Some("missing"), "*.graphql"),
+            Err(YamlEditError::ProjectNotFound("missing".to_owned()))
+        );
+
+        let mut editor = YamlConfigEditor::new("{ schemaPath: ./schema.graphql }\n");
+        assert!(editor.set_schema_path(None, "./other.graphql").is_err());
+    }
+}
@@ -0,0 +1,370 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn workspace(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graphql-config-cli-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    for &(path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn run(dir: &PathBuf, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_graphql-config"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn validate_reports_errors_with_their_location() {
+    let dir = workspace(
+        "validate",
+        &[
+            (".graphqlconfig", "{\n  \"includes\": [\"src/[a\"]\n}\n"),
+            ("nested/.keep", ""),
+        ],
+    );
+
+    let output = run(&dir.join("nested"), &["validate"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{}:2:24: error: invalid JSON configuration: invalid pattern `src/[a`: unclosed `[`\n",
+            dir.join(".graphqlconfig").display()
+        )
+    );
+
+    fs::write(dir.join(".graphqlconfig"), r#"{ "excludes": [] }"#).unwrap();
+    let output = run(&dir, &["validate"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("warning: root: removed empty excludes"));
+    assert_eq!(run(&dir, &["validate", "--strict"]).status.code(), Some(1));
+}
+
+#[test]
+fn get_schema_writes_the_introspected_schema() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = Vec::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line == "\r\n" {
+                break;
+            }
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            head.push(line);
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let response = r#"{"data":{"__schema":{"queryType":{"name":"Query"},"directives":[],"types":[{"kind":"OBJECT","name":"Query","interfaces":[],"fields":[{"name":"hello","args":[],"type":{"kind":"SCALAR","name":"String"}}]}]}}}"#;
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+            response.len(),
+            response
+        )
+        .unwrap();
+        (head, String::from_utf8(body).unwrap())
+    });
+
+    let config = format!(
+        r#"{{
+            "schemaPath": "schema/schema.graphql",
+            "extensions": {{ "endpoints": {{ "default": {{
+                "url": "http://{}/graphql",
+                "headers": {{ "Authorization": "Bearer token" }}
+            }} }} }}
+        }}"#,
+        address
+    );
+    let dir = workspace("get-schema", &[(".graphqlconfig", &config)]);
+
+    let output = run(&dir, &["get-schema"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert_eq!(
+        fs::read_to_string(dir.join("schema/schema.graphql")).unwrap(),
+        "type Query {\n  hello: String\n}\n"
+    );
+
+    let (head, body) = server.join().unwrap();
+    assert_eq!(head[0], "POST /graphql HTTP/1.1\r\n");
+    assert!(head.contains(&"Authorization: Bearer token\r\n".to_owned()));
+    assert!(body.contains("IntrospectionQuery"));
+}
+
+#[test]
+fn projects_and_show_describe_the_projects() {
+    let dir = workspace(
+        "projects",
+        &[
+            (
+                ".graphqlconfig",
+                r#"{
+                    "schemaPath": "schema.graphql",
+                    "extensions": { "codegen": { "output": "gen" } },
+                    "projects": {
+                        "admin": { "schemaPath": "admin.graphql", "includes": ["admin/*.graphql"] },
+                        "web": { "includes": ["web/*.graphql"] }
+                    }
+                }"#,
+            ),
+            ("web/a.graphql", ""),
+            ("web/b.graphql", ""),
+            ("admin/a.graphql", ""),
+        ],
+    );
+
+    let output = run(&dir, &["projects"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        "admin  admin.graphql  1 document\nweb    schema.graphql  2 documents\n"
+    );
+
+    let output = run(&dir, &["show", "--project", "web"]);
+    assert_eq!(output.status.code(), Some(0));
+    let shown: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(shown["schemaPath"], "schema.graphql");
+    assert_eq!(shown["extensions"]["codegen"]["output"], "gen");
+
+    assert_eq!(run(&dir, &["show"]).status.code(), Some(2));
+
+    fs::write(dir.join(".graphqlconfig"), "{}").unwrap();
+    let output = run(&dir, &["show"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr, "error: the configuration defines no projects\n");
+}
+
+#[test]
+fn projects_reads_modern_configurations() {
+    let dir = workspace(
+        "projects-v3",
+        &[
+            (
+                ".graphqlrc",
+                r#"{
+                    "projects": {
+                        "web": { "schema": "schema.graphql", "documents": "web/*.graphql" }
+                    }
+                }"#,
+            ),
+            ("web/a.graphql", ""),
+        ],
+    );
+
+    let output = run(&dir, &["projects"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "web  schema.graphql  1 document\n");
+
+    fs::write(
+        dir.join(".graphqlrc"),
+        r#"{ "schema": ["a.graphql", "b.graphql"] }"#,
+    )
+    .unwrap();
+    assert_eq!(run(&dir, &["validate"]).status.code(), Some(2));
+}
+
+#[test]
+fn which_project_explains_its_answer() {
+    let dir = workspace(
+        "which-project",
+        &[
+            (
+                ".graphqlconfig",
+                r#"{ "projects": {
+                    "app": { "includes": ["src/**/*.graphql"], "excludes": ["src/generated/**"] },
+                    "wide": { "includes": ["**/*.graphql"], "excludes": ["src/generated/**"] }
+                } }"#,
+            ),
+            ("src/.keep", ""),
+        ],
+    );
+
+    let output = run(&dir.join("src"), &["which-project", "query.graphql"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "app\n");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "project `wide` also includes it, but `app` comes first\n"
+    );
+
+    let output = run(
+        &dir,
+        &["which-project", "src/generated/../generated/a.graphql"],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("project `app` excludes it with `src/generated/**`\n"));
+}
+
+#[test]
+fn init_writes_a_template() {
+    let dir = workspace("init", &[("schema.graphql", "type Query { a: Int }")]);
+
+    let output = run(
+        &dir,
+        &["init", "--template", "monorepo", "--packages", "web, admin"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let written: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join(".graphqlconfig")).unwrap()).unwrap();
+    assert_eq!(written["schemaPath"], "schema.graphql");
+    assert_eq!(
+        written["projects"]["admin"]["includes"][0],
+        "packages/admin/src/**/*.graphql"
+    );
+    assert!(written.get("name").is_none());
+
+    let output = run(&dir, &["init", "--template", "single"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = run(&dir, &["init", "--template", "single", "--force"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(run(&dir, &["validate"]).status.code(), Some(0));
+}
+
+#[test]
+fn migrate_writes_a_v3_configuration() {
+    let dir = workspace(
+        "migrate",
+        &[(
+            ".graphqlconfig",
+            r#"{ "projects": { "app": {
+                "name": "App",
+                "schemaPath": "${env:SCHEMA}",
+                "includes": ["src/*.graphql"]
+            } } }"#,
+        )],
+    );
+
+    let output = run(&dir, &["migrate"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        fs::read_to_string(dir.join(".graphqlrc.yml")).unwrap(),
+        "projects:\n  app:\n    schema: ${env:SCHEMA}\n    include:\n    - src/*.graphql\n"
+    );
+    let notes = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(notes.lines().count(), 3, "{}", notes);
+    assert!(notes.contains("project `app`: the name `App` was dropped"));
+
+    let output = run(&dir, &["migrate", "--config", ".graphqlconfig"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn diff_lists_changes_and_fails_on_them() {
+    let dir = workspace(
+        "diff",
+        &[
+            ("a.json", r#"{ "includes": ["src/*.graphql"] }"#),
+            ("b.json", r#"{ "includes": ["src/**/*.graphql"] }"#),
+        ],
+    );
+
+    let output = run(&dir, &["diff", "--strict", "a.json", "b.json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        stdout(&output),
+        "+ root: include \"src/**/*.graphql\"\n- root: include \"src/*.graphql\"\n"
+    );
+
+    let output = run(&dir, &["diff", "--format", "json", "a.json", "b.json"]);
+    let diff: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(diff["result"]["changes"][0]["change"], "includeAdded");
+    assert_eq!(diff["findings"][0]["level"], "warning");
+
+    assert_eq!(
+        run(&dir, &["diff", "a.json", "a.json"]).status.code(),
+        Some(0)
+    );
+    assert_eq!(
+        run(&dir, &["diff", "a.json", "c.json"]).status.code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn drift_fails_on_uncommitted_schemas() {
+    let dir = workspace(
+        "drift",
+        &[
+            (".graphqlconfig", r#"{ "schemaPath": "schema.graphql" }"#),
+            ("schema.graphql", "type Query { a: Int }\n"),
+        ],
+    );
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "schema"]);
+
+    let output = run(&dir, &["drift"]);
+    assert_eq!(output.status.code(), Some(0), "{:?}", output);
+    assert!(stdout(&output).ends_with("schema.graphql: in sync\n"));
+
+    fs::write(dir.join("schema.graphql"), "type Query { b: Int }\n").unwrap();
+    let output = run(&dir, &["drift"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).ends_with("schema.graphql: differs from HEAD\n"));
+}
+
+#[test]
+fn sarif_reports_locate_the_problems() {
+    let dir = workspace(
+        "sarif",
+        &[(".graphqlconfig", "{\n  \"schemaPath\": 1\n}\n")],
+    );
+
+    let output = run(&dir, &["validate", "--format", "sarif"]);
+    assert_eq!(output.status.code(), Some(2));
+    let sarif: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(result["ruleId"], "invalid-config");
+    assert_eq!(result["level"], "error");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], ".graphqlconfig");
+    assert_eq!(location["region"]["startLine"], 2);
+
+    let empty = workspace("sarif-empty", &[(".keep", "")]);
+    let output = run(&empty, &["projects", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(3));
+    let report: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(report["exitCode"], 3);
+    assert_eq!(report["findings"][0]["rule"], "config-not-found");
+}
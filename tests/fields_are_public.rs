@@ -18,16 +18,13 @@ fn it_surfaces_fields_as_public() {
         "name": "George"
     });
 
-    let expected = GraphQLConfiguration {
-        root: GraphQLProjectConfiguration {
-            name: Some("George".to_owned()),
-            schema_path: Some("./schema.graphql".into()),
-            includes: None,
-            excludes: None,
-            extensions: None,
-        },
-        projects: None,
-    };
+    let mut expected = GraphQLConfiguration::new();
+    expected.root.name = Some("George".to_owned());
+    expected.root.schema_path = Some("./schema.graphql".into());
+    expected.root.includes = None;
+    expected.root.excludes = None;
+    expected.root.extensions = None;
+    expected.projects = None;
 
     test_deserialization(config, expected);
 }